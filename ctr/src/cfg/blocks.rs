@@ -0,0 +1,744 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/Config_Savegame) for the documentation
+*/
+
+use crate::{
+    formats::config_save::BlockAccessFlag,
+    res::{error, CtrResult},
+    utils::convert::bytes_to_utf16le_string,
+};
+use alloc::{string::String, vec::Vec};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+/// A block in the config savegame, which has a fixed id and size.
+pub trait ConfigBlock: Sized {
+    const ID: u32;
+    const SIZE: usize;
+
+    /// Decodes the block.  `bytes` is at least [ConfigBlock::SIZE] bytes long.
+    fn decode(bytes: &[u8]) -> CtrResult<Self>;
+
+    /// Checks the size of the block before decoding it.
+    fn from_bytes(bytes: &[u8]) -> CtrResult<Self> {
+        if bytes.len() < Self::SIZE {
+            return Err(error::invalid_size());
+        }
+
+        Self::decode(bytes)
+    }
+}
+
+/// A config block that can be changed.
+pub trait WritableConfigBlock: ConfigBlock {
+    /// The block's access flags in the config savegame, which decide the command that can set it.
+    const FLAGS: u16;
+
+    /// Whether the block can be set with SetConfigInfoBlk4.
+    /// Blocks without [BlockAccessFlag::SystemWrite] can only be set with the Blk8 command.
+    fn is_system_writable() -> bool {
+        Self::FLAGS & u16::from(BlockAccessFlag::SystemWrite) != 0
+    }
+
+    /// Encodes the block over its current bytes, so fields that aren't decoded are kept.
+    /// `bytes` is at least [ConfigBlock::SIZE] bytes long.
+    fn encode(&self, bytes: &mut [u8]) -> CtrResult;
+
+    /// Checks the size of the block before encoding it.
+    fn write_bytes(&self, bytes: &mut [u8]) -> CtrResult {
+        if bytes.len() < Self::SIZE {
+            return Err(error::invalid_size());
+        }
+
+        self.encode(bytes)
+    }
+}
+
+fn decode_enum<T: TryFromPrimitive<Primitive = u8>>(raw: u8) -> CtrResult<T> {
+    T::try_from_primitive(raw).map_err(|_| error::invalid_enum_value())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum SystemLanguage {
+    Japanese = 0,
+    English = 1,
+    French = 2,
+    German = 3,
+    Italian = 4,
+    Spanish = 5,
+    SimplifiedChinese = 6,
+    Korean = 7,
+    Dutch = 8,
+    Portuguese = 9,
+    Russian = 10,
+    TraditionalChinese = 11,
+}
+
+impl ConfigBlock for SystemLanguage {
+    const ID: u32 = 0xa0002;
+    const SIZE: usize = 1;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        decode_enum(bytes[0])
+    }
+}
+
+impl WritableConfigBlock for SystemLanguage {
+    const FLAGS: u16 = 0xe;
+
+    fn encode(&self, bytes: &mut [u8]) -> CtrResult {
+        bytes[0] = (*self).into();
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum Region {
+    Japan = 0,
+    America = 1,
+    Europe = 2,
+    Australia = 3,
+    China = 4,
+    Korea = 5,
+    Taiwan = 6,
+}
+
+impl Region {
+    pub fn from_raw(raw_region: u8) -> CtrResult<Self> {
+        decode_enum(raw_region)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum SystemModel {
+    Old3ds = 0,
+    Old3dsXl = 1,
+    New3ds = 2,
+    Old2ds = 3,
+    New3dsXl = 4,
+    New2dsXl = 5,
+}
+
+impl SystemModel {
+    pub fn is_new_3ds(&self) -> bool {
+        matches!(self, Self::New3ds | Self::New3dsXl | Self::New2dsXl)
+    }
+
+    pub fn is_2ds(&self) -> bool {
+        matches!(self, Self::Old2ds | Self::New2dsXl)
+    }
+}
+
+impl ConfigBlock for SystemModel {
+    const ID: u32 = 0xf0004;
+    const SIZE: usize = 4;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        decode_enum(bytes[0])
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CountryInfo {
+    pub state: u8,
+    pub country: u8,
+}
+
+impl ConfigBlock for CountryInfo {
+    const ID: u32 = 0xb0000;
+    const SIZE: usize = 4;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        Ok(Self {
+            state: bytes[2],
+            country: bytes[3],
+        })
+    }
+}
+
+impl WritableConfigBlock for CountryInfo {
+    const FLAGS: u16 = 0xe;
+
+    fn encode(&self, bytes: &mut [u8]) -> CtrResult {
+        bytes[2] = self.state;
+        bytes[3] = self.country;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Birthday {
+    pub month: u8,
+    pub day: u8,
+}
+
+impl ConfigBlock for Birthday {
+    const ID: u32 = 0xa0001;
+    const SIZE: usize = 2;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        Ok(Self {
+            month: bytes[0],
+            day: bytes[1],
+        })
+    }
+}
+
+impl WritableConfigBlock for Birthday {
+    const FLAGS: u16 = 0xc;
+
+    fn encode(&self, bytes: &mut [u8]) -> CtrResult {
+        if !(1..=12).contains(&self.month) || !(1..=31).contains(&self.day) {
+            return Err(error::invalid_value());
+        }
+
+        bytes[0] = self.month;
+        bytes[1] = self.day;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum SoundOutputMode {
+    Mono = 0,
+    Stereo = 1,
+    Surround = 2,
+}
+
+impl ConfigBlock for SoundOutputMode {
+    const ID: u32 = 0x70001;
+    const SIZE: usize = 1;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        decode_enum(bytes[0])
+    }
+}
+
+impl WritableConfigBlock for SoundOutputMode {
+    const FLAGS: u16 = 0xe;
+
+    fn encode(&self, bytes: &mut [u8]) -> CtrResult {
+        bytes[0] = (*self).into();
+        Ok(())
+    }
+}
+
+/// The version of the EULA the user accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EulaVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl ConfigBlock for EulaVersion {
+    const ID: u32 = 0xd0000;
+    const SIZE: usize = 4;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        Ok(Self {
+            minor: bytes[0],
+            major: bytes[1],
+        })
+    }
+}
+
+impl WritableConfigBlock for EulaVersion {
+    const FLAGS: u16 = 0xe;
+
+    fn encode(&self, bytes: &mut [u8]) -> CtrResult {
+        bytes[0] = self.minor;
+        bytes[1] = self.major;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u32)]
+pub enum ParentalRestriction {
+    InternetBrowser = 0x1,
+    Display3d = 0x2,
+    SharingImages = 0x4,
+    OnlineInteraction = 0x8,
+    StreetPass = 0x10,
+    FriendRegistration = 0x20,
+    DsDownloadPlay = 0x40,
+    ShoppingServices = 0x80,
+    DistributedVideos = 0x100,
+    MiiverseView = 0x200,
+    MiiversePost = 0x400,
+    Enabled = 0x80000000,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParentalControls {
+    pub restrictions: u32,
+    pub rating_organization: u8,
+    pub maximum_age: u8,
+    pub secret_question: u8,
+    pub pin: String,
+    pub secret_answer: String,
+}
+
+impl ParentalControls {
+    pub fn is_enabled(&self) -> bool {
+        self.is_restricted(ParentalRestriction::Enabled)
+    }
+
+    pub fn is_restricted(&self, restriction: ParentalRestriction) -> bool {
+        self.restrictions & u32::from(restriction) != 0
+    }
+}
+
+impl ConfigBlock for ParentalControls {
+    const ID: u32 = 0xc0000;
+    const SIZE: usize = 0xc0;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        let pin_end = bytes[0xc..0x14]
+            .iter()
+            .position(|byte| *byte == 0)
+            .map_or(0x14, |index| 0xc + index);
+
+        Ok(Self {
+            restrictions: u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            rating_organization: bytes[0x8],
+            maximum_age: bytes[0x9],
+            secret_question: bytes[0xa],
+            pin: String::from(core::str::from_utf8(&bytes[0xc..pin_end])?),
+            secret_answer: bytes_to_utf16le_string(&bytes[0x14..0x7c])?,
+        })
+    }
+}
+
+/// Restrictions from the Children's Online Privacy Protection Act.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CoppacsInfo {
+    pub restriction: u8,
+    pub data: [u8; 0x14],
+}
+
+impl CoppacsInfo {
+    pub fn is_restricted(&self) -> bool {
+        self.restriction != 0
+    }
+}
+
+impl ConfigBlock for CoppacsInfo {
+    const ID: u32 = 0xc0001;
+    const SIZE: usize = 0x14;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        let mut data = [0; 0x14];
+        data.copy_from_slice(&bytes[..Self::SIZE]);
+
+        Ok(Self {
+            restriction: bytes[0],
+            data,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SystemSetup {
+    /// Whether the initial system setup has been completed.
+    pub is_complete: bool,
+}
+
+impl ConfigBlock for SystemSetup {
+    const ID: u32 = 0x110000;
+    const SIZE: usize = 4;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        Ok(Self {
+            is_complete: u16::from_le_bytes([bytes[0], bytes[1]]) != 0,
+        })
+    }
+}
+
+impl WritableConfigBlock for SystemSetup {
+    const FLAGS: u16 = 0xc;
+
+    fn encode(&self, bytes: &mut [u8]) -> CtrResult {
+        bytes[0..2].copy_from_slice(&u16::from(self.is_complete).to_le_bytes());
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BacklightSettings {
+    /// Automatically dims the screens to save power.
+    pub power_saving_enabled: bool,
+    /// The brightness level, from 1 to 5.
+    pub brightness_level: u8,
+}
+
+impl ConfigBlock for BacklightSettings {
+    const ID: u32 = 0x50001;
+    const SIZE: usize = 2;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        Ok(Self {
+            power_saving_enabled: bytes[0] != 0,
+            brightness_level: bytes[1],
+        })
+    }
+}
+
+impl WritableConfigBlock for BacklightSettings {
+    const FLAGS: u16 = 0xc;
+
+    fn encode(&self, bytes: &mut [u8]) -> CtrResult {
+        if !(1..=5).contains(&self.brightness_level) {
+            return Err(error::invalid_value());
+        }
+
+        bytes[0] = self.power_saving_enabled.into();
+        bytes[1] = self.brightness_level;
+        Ok(())
+    }
+}
+
+/// The maximum number of UTF-16 code units in a username.
+pub const MAX_USERNAME_LENGTH: usize = 10;
+/// The username is followed by a null terminator and padding.
+const USERNAME_FIELD_SIZE: usize = 0x18;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Username {
+    pub name: String,
+}
+
+impl ConfigBlock for Username {
+    const ID: u32 = 0xa0000;
+    const SIZE: usize = 0x1c;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        Ok(Self {
+            name: bytes_to_utf16le_string(&bytes[..USERNAME_FIELD_SIZE])?,
+        })
+    }
+}
+
+impl WritableConfigBlock for Username {
+    const FLAGS: u16 = 0xe;
+
+    fn encode(&self, bytes: &mut [u8]) -> CtrResult {
+        let name = self.name.encode_utf16().collect::<Vec<u16>>();
+
+        if name.is_empty() || name.contains(&0) {
+            return Err(error::invalid_value());
+        }
+
+        if name.len() > MAX_USERNAME_LENGTH {
+            return Err(error::invalid_size());
+        }
+
+        bytes[..USERNAME_FIELD_SIZE].fill(0);
+
+        for (index, code_unit) in name.iter().enumerate() {
+            bytes[index * 2..index * 2 + 2].copy_from_slice(&code_unit.to_le_bytes());
+        }
+
+        Ok(())
+    }
+}
+
+/// The offset the user set from the real time clock, in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UserTimeOffset {
+    pub milliseconds: i64,
+}
+
+impl ConfigBlock for UserTimeOffset {
+    const ID: u32 = 0x30001;
+    const SIZE: usize = 8;
+
+    fn decode(bytes: &[u8]) -> CtrResult<Self> {
+        let mut raw_offset = [0; 8];
+        raw_offset.copy_from_slice(&bytes[..Self::SIZE]);
+
+        Ok(Self {
+            milliseconds: i64::from_le_bytes(raw_offset),
+        })
+    }
+}
+
+impl WritableConfigBlock for UserTimeOffset {
+    const FLAGS: u16 = 0x8;
+
+    fn encode(&self, bytes: &mut [u8]) -> CtrResult {
+        bytes[..Self::SIZE].copy_from_slice(&self.milliseconds.to_le_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_error_if_the_block_is_too_small() {
+        let result = CountryInfo::from_bytes(&[0, 0, 0]).unwrap_err();
+        assert_eq!(result, error::invalid_size());
+    }
+
+    #[test]
+    fn should_decode_enum_blocks() {
+        assert_eq!(
+            SystemLanguage::from_bytes(&[1]).unwrap(),
+            SystemLanguage::English
+        );
+        assert_eq!(
+            SoundOutputMode::from_bytes(&[2]).unwrap(),
+            SoundOutputMode::Surround
+        );
+        assert_eq!(Region::from_raw(2).unwrap(), Region::Europe);
+    }
+
+    #[test]
+    fn should_error_for_unknown_enum_values() {
+        assert_eq!(
+            SystemLanguage::from_bytes(&[12]).unwrap_err(),
+            error::invalid_enum_value()
+        );
+        assert_eq!(
+            Region::from_raw(7).unwrap_err(),
+            error::invalid_enum_value()
+        );
+    }
+
+    #[test]
+    fn should_decode_the_system_model() {
+        let model = SystemModel::from_bytes(&[4, 0, 0, 0]).unwrap();
+
+        assert_eq!(model, SystemModel::New3dsXl);
+        assert!(model.is_new_3ds());
+        assert!(!model.is_2ds());
+        assert!(!SystemModel::Old2ds.is_new_3ds());
+    }
+
+    #[test]
+    fn should_decode_country_info() {
+        let result = CountryInfo::from_bytes(&[0, 0, 0x2, 0x31]).unwrap();
+        assert_eq!(
+            result,
+            CountryInfo {
+                state: 0x2,
+                country: 0x31
+            }
+        );
+    }
+
+    #[test]
+    fn should_decode_the_birthday() {
+        let result = Birthday::from_bytes(&[12, 25]).unwrap();
+        assert_eq!(result, Birthday { month: 12, day: 25 });
+    }
+
+    #[test]
+    fn should_decode_the_eula_version() {
+        let result = EulaVersion::from_bytes(&[0x2, 0x1, 0, 0]).unwrap();
+        assert_eq!(result, EulaVersion { major: 1, minor: 2 });
+    }
+
+    #[test]
+    fn should_decode_parental_controls() {
+        let mut bytes = [0u8; 0xc0];
+        bytes[0..4].copy_from_slice(&0x80000011u32.to_le_bytes());
+        bytes[0x8] = 1;
+        bytes[0x9] = 12;
+        bytes[0xa] = 3;
+        bytes[0xc..0x10].copy_from_slice(b"1234");
+        bytes[0x14..0x1a].copy_from_slice(&[0x43, 0x00, 0x61, 0x00, 0x74, 0x00]);
+
+        let result = ParentalControls::from_bytes(&bytes).unwrap();
+
+        assert!(result.is_enabled());
+        assert!(result.is_restricted(ParentalRestriction::InternetBrowser));
+        assert!(result.is_restricted(ParentalRestriction::StreetPass));
+        assert!(!result.is_restricted(ParentalRestriction::MiiversePost));
+        assert_eq!(result.rating_organization, 1);
+        assert_eq!(result.maximum_age, 12);
+        assert_eq!(result.secret_question, 3);
+        assert_eq!(result.pin, "1234");
+        assert_eq!(result.secret_answer, "Cat");
+    }
+
+    #[test]
+    fn should_decode_coppacs_info() {
+        let mut bytes = [0u8; 0x14];
+        bytes[0] = 3;
+
+        let result = CoppacsInfo::from_bytes(&bytes).unwrap();
+
+        assert!(result.is_restricted());
+        assert_eq!(result.data, bytes);
+    }
+
+    #[test]
+    fn should_decode_system_setup() {
+        assert!(SystemSetup::from_bytes(&[1, 0, 0, 0]).unwrap().is_complete);
+        assert!(!SystemSetup::from_bytes(&[0, 0, 0, 0]).unwrap().is_complete);
+    }
+
+    #[test]
+    fn should_round_trip_the_system_setup_as_a_u16() {
+        let mut bytes = [0xffu8; 4];
+
+        let result = SystemSetup::from_bytes(&[0, 1, 0, 0]).unwrap();
+        assert!(result.is_complete);
+
+        SystemSetup { is_complete: false }
+            .write_bytes(&mut bytes)
+            .unwrap();
+        assert_eq!(bytes, [0, 0, 0xff, 0xff]);
+        assert!(!SystemSetup::from_bytes(&bytes).unwrap().is_complete);
+
+        result.write_bytes(&mut bytes).unwrap();
+        assert_eq!(bytes, [1, 0, 0xff, 0xff]);
+        assert_eq!(SystemSetup::from_bytes(&bytes).unwrap(), result);
+    }
+
+    #[test]
+    fn should_only_set_blocks_with_system_write_access_with_blk4() {
+        assert!(SystemLanguage::is_system_writable());
+        assert!(SystemSetup::is_system_writable());
+        assert!(!UserTimeOffset::is_system_writable());
+    }
+
+    #[test]
+    fn should_decode_backlight_settings() {
+        let result = BacklightSettings::from_bytes(&[1, 4]).unwrap();
+        assert_eq!(
+            result,
+            BacklightSettings {
+                power_saving_enabled: true,
+                brightness_level: 4
+            }
+        );
+    }
+
+    #[test]
+    fn should_decode_the_username() {
+        let mut bytes = [0u8; 0x1c];
+        bytes[..8].copy_from_slice(&[0x54, 0x00, 0x65, 0x00, 0x73, 0x00, 0x74, 0x00]);
+
+        let result = Username::from_bytes(&bytes).unwrap();
+        assert_eq!(result.name, "Test");
+    }
+
+    #[test]
+    fn should_encode_blocks_over_their_current_bytes() {
+        let mut bytes = [0xffu8; 4];
+        CountryInfo {
+            state: 0x2,
+            country: 0x31,
+        }
+        .write_bytes(&mut bytes)
+        .unwrap();
+
+        assert_eq!(bytes, [0xff, 0xff, 0x2, 0x31]);
+    }
+
+    #[test]
+    fn should_error_if_the_encoded_block_is_too_small() {
+        let mut bytes = [0u8; 1];
+        let result = Birthday { month: 1, day: 1 }
+            .write_bytes(&mut bytes)
+            .unwrap_err();
+
+        assert_eq!(result, error::invalid_size());
+    }
+
+    #[test]
+    fn should_round_trip_writable_blocks() {
+        let mut bytes = [0u8; 4];
+
+        SystemLanguage::German.write_bytes(&mut bytes).unwrap();
+        assert_eq!(
+            SystemLanguage::from_bytes(&bytes).unwrap(),
+            SystemLanguage::German
+        );
+
+        let eula_version = EulaVersion { major: 1, minor: 2 };
+        eula_version.write_bytes(&mut bytes).unwrap();
+        assert_eq!(EulaVersion::from_bytes(&bytes).unwrap(), eula_version);
+
+        let setup = SystemSetup { is_complete: true };
+        setup.write_bytes(&mut bytes).unwrap();
+        assert_eq!(SystemSetup::from_bytes(&bytes).unwrap(), setup);
+
+        let offset = UserTimeOffset {
+            milliseconds: -3_600_000,
+        };
+        let mut offset_bytes = [0u8; 8];
+        offset.write_bytes(&mut offset_bytes).unwrap();
+        assert_eq!(UserTimeOffset::from_bytes(&offset_bytes).unwrap(), offset);
+    }
+
+    #[test]
+    fn should_validate_the_birthday_and_brightness() {
+        let mut bytes = [0u8; 2];
+
+        assert_eq!(
+            Birthday { month: 13, day: 1 }
+                .write_bytes(&mut bytes)
+                .unwrap_err(),
+            error::invalid_value()
+        );
+        assert_eq!(
+            BacklightSettings {
+                power_saving_enabled: false,
+                brightness_level: 0
+            }
+            .write_bytes(&mut bytes)
+            .unwrap_err(),
+            error::invalid_value()
+        );
+    }
+
+    #[test]
+    fn should_encode_the_username_as_utf16() {
+        let mut bytes = [0xffu8; 0x1c];
+        let username = Username {
+            name: String::from("Tést"),
+        };
+
+        username.write_bytes(&mut bytes).unwrap();
+
+        assert_eq!(bytes[..8], [0x54, 0x00, 0xe9, 0x00, 0x73, 0x00, 0x74, 0x00]);
+        assert_eq!(bytes[8..0x18], [0; 0x10]);
+        assert_eq!(bytes[0x18..], [0xff; 4]);
+        assert_eq!(Username::from_bytes(&bytes).unwrap(), username);
+    }
+
+    #[test]
+    fn should_validate_the_username_length() {
+        let mut bytes = [0u8; 0x1c];
+
+        Username {
+            name: String::from("0123456789"),
+        }
+        .write_bytes(&mut bytes)
+        .unwrap();
+
+        let too_long = Username {
+            name: String::from("0123456789a"),
+        };
+        assert_eq!(
+            too_long.write_bytes(&mut bytes).unwrap_err(),
+            error::invalid_size()
+        );
+
+        let empty = Username {
+            name: String::new(),
+        };
+        assert_eq!(
+            empty.write_bytes(&mut bytes).unwrap_err(),
+            error::invalid_value()
+        );
+    }
+}
@@ -0,0 +1,278 @@
+mod blocks;
+pub use blocks::*;
+
+use crate::{
+    formats::local_friend_code_seed::LocalFriendCodeSeed,
+    ipc::{Command, PermissionBuffer},
+    res::CtrResult,
+    service_session::{create_session_manager, session},
+    srv::get_service_handle_direct,
+    utils::cstring::parse_null_terminated_str,
+};
+use alloc::{string::String, vec};
+use core::convert::TryInto;
+use no_std_io::{EndianRead, EndianWrite};
+
+create_session_manager!({
+    get_service_handle_direct("cfg:i")
+        .or_else(|_| get_service_handle_direct("cfg:s"))
+        .or_else(|_| get_service_handle_direct("cfg:u"))?
+});
+
+#[derive(EndianRead, EndianWrite)]
+struct OutBufferIn {
+    out_size: u32,
+    out: PermissionBuffer,
+}
+
+fn get_local_friend_code_seed_data_impl() -> CtrResult<[u8; 0x110]> {
+    let mut out: [u8; 0x110] = [0; 0x110];
+
+    let input = OutBufferIn {
+        out_size: out.len().try_into()?,
+        out: PermissionBuffer::new_write(&mut out),
+    };
+
+    Command::new(0x4040042, input).send(get_handle())?;
+
+    Ok(out)
+}
+
+/// The serial number is 15 bytes, including padding.
+const SERIAL_NUMBER_SIZE: usize = 0xf;
+
+fn secure_info_get_serial_number_impl() -> CtrResult<[u8; SERIAL_NUMBER_SIZE]> {
+    let mut out: [u8; SERIAL_NUMBER_SIZE] = [0; SERIAL_NUMBER_SIZE];
+
+    let input = OutBufferIn {
+        out_size: out.len().try_into()?,
+        out: PermissionBuffer::new_write(&mut out),
+    };
+
+    Command::new(0x4080042, input).send(get_handle())?;
+
+    Ok(out)
+}
+
+#[derive(EndianRead, EndianWrite)]
+struct ConfigInfoBlkIn {
+    out_size: u32,
+    block_id: u32,
+    out: PermissionBuffer,
+}
+
+pub fn get_config_info_blk2(out: &mut [u8], block_id: u32) -> CtrResult {
+    let out_size = out.len().try_into()?;
+
+    let input = ConfigInfoBlkIn {
+        out_size,
+        block_id,
+        out: PermissionBuffer::new_write(out),
+    };
+
+    Command::new(0x10082, input).send(get_handle())?;
+
+    Ok(())
+}
+
+/// Gets a block that system titles are allowed to read. Requires cfg:s or cfg:i.
+pub fn get_config_info_blk8(out: &mut [u8], block_id: u32) -> CtrResult {
+    let out_size = out.len().try_into()?;
+
+    let input = ConfigInfoBlkIn {
+        out_size,
+        block_id,
+        out: PermissionBuffer::new_write(out),
+    };
+
+    Command::new(0x4010082, input).send(get_handle())?;
+
+    Ok(())
+}
+
+#[derive(EndianRead, EndianWrite)]
+struct SetConfigInfoBlkIn {
+    in_size: u32,
+    block_id: u32,
+    data: PermissionBuffer,
+}
+
+fn set_config_info_blk_impl(command_id: u32, data: &[u8], block_id: u32) -> CtrResult {
+    let input = SetConfigInfoBlkIn {
+        in_size: data.len().try_into()?,
+        block_id,
+        data: PermissionBuffer::new_read(data),
+    };
+
+    Command::new(command_id, input).send(get_handle())?;
+
+    Ok(())
+}
+
+/// Sets a block that system titles are allowed to write. Requires cfg:s or cfg:i.
+/// Changes are only saved by [update_config_savegame].
+pub fn set_config_info_blk4(data: &[u8], block_id: u32) -> CtrResult {
+    set_config_info_blk_impl(0x4020082, data, block_id)
+}
+
+/// Sets a block that only cfg:i is allowed to write.
+/// Changes are only saved by [update_config_savegame].
+pub fn set_config_info_blk8(data: &[u8], block_id: u32) -> CtrResult {
+    set_config_info_blk_impl(0x8020082, data, block_id)
+}
+
+fn update_config_savegame_impl() -> CtrResult {
+    Command::new(0x4030000, ()).send(get_handle())
+}
+
+fn secure_info_get_region_impl() -> CtrResult<u8> {
+    Command::new(0x20000, ()).send(get_handle())
+}
+
+pub fn get_local_friend_code_seed_data() -> CtrResult<[u8; 0x110]> {
+    session!(cfg);
+    get_local_friend_code_seed_data_impl()
+}
+
+/// Gets the serial number printed on the console, e.g. `CW123456789`.
+/// This requires cfg:s or cfg:i.
+pub fn get_serial_number() -> CtrResult<String> {
+    session!(cfg);
+    let serial_number = secure_info_get_serial_number_impl()?;
+    Ok(String::from(parse_null_terminated_str(&serial_number)))
+}
+
+/// Gets and validates the console's [LocalFriendCodeSeed].
+pub fn get_local_friend_code_seed() -> CtrResult<LocalFriendCodeSeed> {
+    session!(cfg);
+    LocalFriendCodeSeed::parse(&get_local_friend_code_seed_data_impl()?)
+}
+
+/// Reads and decodes a config block.
+pub fn get_config_block<T: ConfigBlock>() -> CtrResult<T> {
+    session!(cfg);
+
+    let mut bytes = vec![0; T::SIZE];
+    get_config_info_blk2(&mut bytes, T::ID)?;
+    T::from_bytes(&bytes)
+}
+
+/// Changes a config block, keeping any bytes the block doesn't decode.
+/// Blocks without system write access are set with the Blk8 command, which requires cfg:i.
+/// Changes are only saved by [update_config_savegame].
+pub fn set_config_block<T: WritableConfigBlock>(block: &T) -> CtrResult {
+    session!(cfg);
+
+    // Blocks that can be set aren't always readable with Blk2, but they're all readable with Blk8
+    let mut bytes = vec![0; T::SIZE];
+    get_config_info_blk8(&mut bytes, T::ID)?;
+    block.write_bytes(&mut bytes)?;
+
+    if T::is_system_writable() {
+        set_config_info_blk4(&bytes, T::ID)
+    } else {
+        set_config_info_blk8(&bytes, T::ID)
+    }
+}
+
+/// Writes the config blocks to the config savegame on the NAND.
+pub fn update_config_savegame() -> CtrResult {
+    session!(cfg);
+    update_config_savegame_impl()
+}
+
+pub fn get_console_username() -> CtrResult<String> {
+    Ok(get_config_block::<Username>()?.name)
+}
+
+/// Sets the console username, which can be at most [MAX_USERNAME_LENGTH] UTF-16 code units.
+pub fn set_console_username(name: &str) -> CtrResult {
+    set_config_block(&Username {
+        name: String::from(name),
+    })
+}
+
+pub fn get_system_language() -> CtrResult<SystemLanguage> {
+    get_config_block()
+}
+
+pub fn set_system_language(language: SystemLanguage) -> CtrResult {
+    set_config_block(&language)
+}
+
+pub fn get_region() -> CtrResult<Region> {
+    session!(cfg);
+    Region::from_raw(secure_info_get_region_impl()?)
+}
+
+pub fn get_system_model() -> CtrResult<SystemModel> {
+    get_config_block()
+}
+
+pub fn is_new_3ds() -> CtrResult<bool> {
+    Ok(get_system_model()?.is_new_3ds())
+}
+
+pub fn get_country_info() -> CtrResult<CountryInfo> {
+    get_config_block()
+}
+
+pub fn set_country_info(country_info: CountryInfo) -> CtrResult {
+    set_config_block(&country_info)
+}
+
+pub fn get_birthday() -> CtrResult<Birthday> {
+    get_config_block()
+}
+
+pub fn set_birthday(birthday: Birthday) -> CtrResult {
+    set_config_block(&birthday)
+}
+
+pub fn get_sound_output_mode() -> CtrResult<SoundOutputMode> {
+    get_config_block()
+}
+
+pub fn set_sound_output_mode(mode: SoundOutputMode) -> CtrResult {
+    set_config_block(&mode)
+}
+
+pub fn get_eula_version() -> CtrResult<EulaVersion> {
+    get_config_block()
+}
+
+pub fn set_eula_version(eula_version: EulaVersion) -> CtrResult {
+    set_config_block(&eula_version)
+}
+
+pub fn get_parental_controls() -> CtrResult<ParentalControls> {
+    get_config_block()
+}
+
+pub fn get_coppacs_info() -> CtrResult<CoppacsInfo> {
+    get_config_block()
+}
+
+pub fn get_system_setup() -> CtrResult<SystemSetup> {
+    get_config_block()
+}
+
+pub fn set_system_setup(system_setup: SystemSetup) -> CtrResult {
+    set_config_block(&system_setup)
+}
+
+pub fn get_backlight_settings() -> CtrResult<BacklightSettings> {
+    get_config_block()
+}
+
+pub fn set_backlight_settings(backlight_settings: BacklightSettings) -> CtrResult {
+    set_config_block(&backlight_settings)
+}
+
+pub fn get_user_time_offset() -> CtrResult<UserTimeOffset> {
+    get_config_block()
+}
+
+pub fn set_user_time_offset(milliseconds: i64) -> CtrResult {
+    set_config_block(&UserTimeOffset { milliseconds })
+}
@@ -0,0 +1,220 @@
+use super::{Aes128, AES_128_KEY_SIZE, AES_BLOCK_SIZE};
+use crate::res::{error, CtrResult};
+
+const MIN_NONCE_SIZE: usize = 7;
+const MAX_NONCE_SIZE: usize = 13;
+
+/// Checks the parameters and returns the size of the length field, which is `L` in RFC 3610.
+fn get_length_size(nonce: &[u8], data: &[u8], mac: &[u8]) -> CtrResult<usize> {
+    if !(MIN_NONCE_SIZE..=MAX_NONCE_SIZE).contains(&nonce.len()) {
+        return Err(error::invalid_size());
+    }
+
+    if mac.len() < 4 || mac.len() > AES_BLOCK_SIZE || !mac.len().is_multiple_of(2) {
+        return Err(error::invalid_size());
+    }
+
+    let length_size = AES_BLOCK_SIZE - 1 - nonce.len();
+
+    if length_size < 8 && data.len() as u64 >> (length_size * 8) != 0 {
+        return Err(error::too_large());
+    }
+
+    Ok(length_size)
+}
+
+fn create_counter_block(nonce: &[u8], length_size: usize, counter: usize) -> [u8; AES_BLOCK_SIZE] {
+    let mut block = [0; AES_BLOCK_SIZE];
+    block[0] = (length_size - 1) as u8;
+    block[1..=nonce.len()].copy_from_slice(nonce);
+
+    let counter_bytes = (counter as u64).to_be_bytes();
+    block[AES_BLOCK_SIZE - length_size..]
+        .copy_from_slice(&counter_bytes[counter_bytes.len() - length_size..]);
+
+    block
+}
+
+fn calculate_mac(
+    cipher: &Aes128,
+    nonce: &[u8],
+    length_size: usize,
+    plaintext: &[u8],
+    mac: &mut [u8],
+) {
+    let mut block = create_counter_block(nonce, length_size, plaintext.len());
+    block[0] = ((((mac.len() - 2) / 2) as u8) << 3) | (length_size - 1) as u8;
+    cipher.encrypt_block(&mut block);
+
+    for chunk in plaintext.chunks(AES_BLOCK_SIZE) {
+        for (byte, data_byte) in block.iter_mut().zip(chunk.iter()) {
+            *byte ^= data_byte;
+        }
+        cipher.encrypt_block(&mut block);
+    }
+
+    mac.copy_from_slice(&block[..mac.len()]);
+}
+
+/// XORs the data with the key stream, starting from counter 1.
+/// Counter 0 is reserved for encrypting the MAC.
+fn apply_key_stream(cipher: &Aes128, nonce: &[u8], length_size: usize, data: &mut [u8]) {
+    for (index, chunk) in data.chunks_mut(AES_BLOCK_SIZE).enumerate() {
+        let mut key_stream = create_counter_block(nonce, length_size, index + 1);
+        cipher.encrypt_block(&mut key_stream);
+
+        for (byte, key_byte) in chunk.iter_mut().zip(key_stream.iter()) {
+            *byte ^= key_byte;
+        }
+    }
+}
+
+fn encrypt_mac(cipher: &Aes128, nonce: &[u8], length_size: usize, mac: &mut [u8]) {
+    let mut key_stream = create_counter_block(nonce, length_size, 0);
+    cipher.encrypt_block(&mut key_stream);
+
+    for (byte, key_byte) in mac.iter_mut().zip(key_stream.iter()) {
+        *byte ^= key_byte;
+    }
+}
+
+/// Encrypts `data` in place with AES-128-CCM and writes the MAC to `mac`.
+/// The MAC size is the length of `mac`.  Associated data isn't supported.
+pub fn aes_128_ccm_encrypt(
+    key: &[u8; AES_128_KEY_SIZE],
+    nonce: &[u8],
+    data: &mut [u8],
+    mac: &mut [u8],
+) -> CtrResult {
+    let length_size = get_length_size(nonce, data, mac)?;
+    let cipher = Aes128::new(key);
+
+    calculate_mac(&cipher, nonce, length_size, data, mac);
+    encrypt_mac(&cipher, nonce, length_size, mac);
+    apply_key_stream(&cipher, nonce, length_size, data);
+
+    Ok(())
+}
+
+/// Decrypts `data` in place with AES-128-CCM and verifies it against `mac`.
+/// If the MAC doesn't match, `data` is zeroed and an error is returned.
+pub fn aes_128_ccm_decrypt(
+    key: &[u8; AES_128_KEY_SIZE],
+    nonce: &[u8],
+    data: &mut [u8],
+    mac: &[u8],
+) -> CtrResult {
+    let length_size = get_length_size(nonce, data, mac)?;
+    let cipher = Aes128::new(key);
+
+    apply_key_stream(&cipher, nonce, length_size, data);
+
+    let mut expected_mac = [0; AES_BLOCK_SIZE];
+    let expected_mac = &mut expected_mac[..mac.len()];
+    calculate_mac(&cipher, nonce, length_size, data, expected_mac);
+    encrypt_mac(&cipher, nonce, length_size, expected_mac);
+
+    // Compare every byte so the time taken doesn't depend on where the MACs differ
+    let difference = expected_mac
+        .iter()
+        .zip(mac.iter())
+        .fold(0, |difference, (left, right)| difference | (left ^ right));
+
+    if difference != 0 {
+        data.fill(0);
+        return Err(error::invalid_value());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const KEY: [u8; AES_128_KEY_SIZE] = [
+        0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48, 0x49, 0x4a, 0x4b, 0x4c, 0x4d, 0x4e,
+        0x4f,
+    ];
+    const NONCE: [u8; 12] = [
+        0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
+    ];
+    const CIPHERTEXT: [u8; 37] = [
+        0xe3, 0xb2, 0x01, 0xa9, 0xf5, 0xb7, 0x1a, 0x7a, 0x9b, 0x1c, 0xea, 0xec, 0xcd, 0x97, 0xe7,
+        0x0b, 0x61, 0x76, 0xaa, 0xd9, 0xa4, 0x42, 0x8a, 0xa5, 0x54, 0x1b, 0xd1, 0xd4, 0x16, 0xfa,
+        0x0c, 0xe3, 0xec, 0x37, 0xaf, 0x20, 0x6e,
+    ];
+    const MAC: [u8; 16] = [
+        0x66, 0xcf, 0x5b, 0x0e, 0xe1, 0xc2, 0xf2, 0x18, 0xa1, 0x78, 0xd2, 0xe1, 0x86, 0x98, 0x7e,
+        0x75,
+    ];
+
+    fn create_plaintext() -> [u8; 37] {
+        let mut plaintext = [0; 37];
+        for (index, byte) in plaintext.iter_mut().enumerate() {
+            *byte = 0x20 + index as u8;
+        }
+        plaintext
+    }
+
+    mod aes_128_ccm_encrypt {
+        use super::*;
+
+        #[test]
+        fn should_encrypt_data() {
+            let mut data = create_plaintext();
+            let mut mac = [0; 16];
+
+            aes_128_ccm_encrypt(&KEY, &NONCE, &mut data, &mut mac).unwrap();
+
+            assert_eq!(data, CIPHERTEXT);
+            assert_eq!(mac, MAC);
+        }
+
+        #[test]
+        fn should_support_short_nonces_and_macs() {
+            let mut data = [0x20, 0x21, 0x22, 0x23];
+            let mut mac = [0; 8];
+
+            aes_128_ccm_encrypt(&KEY, &NONCE[..7], &mut data, &mut mac).unwrap();
+
+            assert_eq!(data, [0x71, 0x62, 0x01, 0x5b]);
+            assert_eq!(mac, [0xc8, 0x2d, 0x4d, 0x98, 0x43, 0xcc, 0x84, 0xb6]);
+        }
+
+        #[test]
+        fn should_return_an_error_for_invalid_nonce_sizes() {
+            let mut data = create_plaintext();
+            let mut mac = [0; 16];
+
+            let result = aes_128_ccm_encrypt(&KEY, &NONCE[..6], &mut data, &mut mac);
+
+            assert_eq!(result, Err(error::invalid_size()));
+        }
+    }
+
+    mod aes_128_ccm_decrypt {
+        use super::*;
+
+        #[test]
+        fn should_decrypt_data() {
+            let mut data = CIPHERTEXT;
+
+            aes_128_ccm_decrypt(&KEY, &NONCE, &mut data, &MAC).unwrap();
+
+            assert_eq!(data, create_plaintext());
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_mac_does_not_match() {
+            let mut data = CIPHERTEXT;
+            let mut mac = MAC;
+            mac[15] ^= 1;
+
+            let result = aes_128_ccm_decrypt(&KEY, &NONCE, &mut data, &mac);
+
+            assert_eq!(result, Err(error::invalid_value()));
+            assert_eq!(data, [0; 37]);
+        }
+    }
+}
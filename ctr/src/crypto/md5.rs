@@ -0,0 +1,229 @@
+pub const MD5_HASH_SIZE: usize = 16;
+
+const BLOCK_SIZE: usize = 64;
+
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9,
+    14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10, 15,
+    21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+const INITIAL_STATE: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+/// An incremental MD5 hasher.  MD5 isn't secure, so this is only meant for protocols that require it.
+#[derive(Clone)]
+pub struct Md5 {
+    state: [u32; 4],
+    buffer: [u8; BLOCK_SIZE],
+    buffer_len: usize,
+    length: u64,
+}
+
+impl Md5 {
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL_STATE,
+            buffer: [0; BLOCK_SIZE],
+            buffer_len: 0,
+            length: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+
+        while !data.is_empty() {
+            let copy_size = core::cmp::min(BLOCK_SIZE - self.buffer_len, data.len());
+            self.buffer[self.buffer_len..self.buffer_len + copy_size]
+                .copy_from_slice(&data[..copy_size]);
+            self.buffer_len += copy_size;
+            data = &data[copy_size..];
+
+            if self.buffer_len == BLOCK_SIZE {
+                let block = self.buffer;
+                self.process_block(&block);
+                self.buffer_len = 0;
+            }
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; MD5_HASH_SIZE] {
+        let bit_length = self.length.wrapping_mul(8);
+
+        self.update(&[0x80]);
+        while self.buffer_len != BLOCK_SIZE - 8 {
+            self.update(&[0]);
+        }
+        self.update(&bit_length.to_le_bytes());
+
+        let mut result = [0; MD5_HASH_SIZE];
+        for (chunk, word) in result.chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+
+        result
+    }
+
+    fn process_block(&mut self, block: &[u8; BLOCK_SIZE]) {
+        let mut words = [0u32; 16];
+
+        for (word, chunk) in words.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+
+        for index in 0..64 {
+            let (function, word_index) = match index {
+                0..=15 => ((b & c) | (!b & d), index),
+                16..=31 => ((d & b) | (!d & c), (5 * index + 1) % 16),
+                32..=47 => (b ^ c ^ d, (3 * index + 5) % 16),
+                _ => (c ^ (b | !d), (7 * index) % 16),
+            };
+
+            let temp = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(
+                a.wrapping_add(function)
+                    .wrapping_add(ROUND_CONSTANTS[index])
+                    .wrapping_add(words[word_index])
+                    .rotate_left(SHIFTS[index]),
+            );
+            a = temp;
+        }
+
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d].iter()) {
+            *state = state.wrapping_add(*value);
+        }
+    }
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn md5(data: &[u8]) -> [u8; MD5_HASH_SIZE] {
+    let mut hasher = Md5::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// HMAC-MD5, as described in RFC 2104.
+pub fn hmac_md5(key: &[u8], data: &[u8]) -> [u8; MD5_HASH_SIZE] {
+    let mut block_key = [0u8; BLOCK_SIZE];
+
+    if key.len() > BLOCK_SIZE {
+        block_key[..MD5_HASH_SIZE].copy_from_slice(&md5(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner_key = [0x36u8; BLOCK_SIZE];
+    let mut outer_key = [0x5cu8; BLOCK_SIZE];
+    for ((inner, outer), key_byte) in inner_key
+        .iter_mut()
+        .zip(outer_key.iter_mut())
+        .zip(block_key.iter())
+    {
+        *inner ^= key_byte;
+        *outer ^= key_byte;
+    }
+
+    let mut inner = Md5::new();
+    inner.update(&inner_key);
+    inner.update(data);
+
+    let mut outer = Md5::new();
+    outer.update(&outer_key);
+    outer.update(&inner.finalize());
+    outer.finalize()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_hash_empty_data() {
+        let result = md5(&[]);
+        assert_eq!(
+            result,
+            [
+                0xd4, 0x1d, 0x8c, 0xd9, 0x8f, 0x00, 0xb2, 0x04, 0xe9, 0x80, 0x09, 0x98, 0xec, 0xf8,
+                0x42, 0x7e
+            ]
+        );
+    }
+
+    #[test]
+    fn should_hash_data() {
+        let result = md5(b"abc");
+        assert_eq!(
+            result,
+            [
+                0x90, 0x01, 0x50, 0x98, 0x3c, 0xd2, 0x4f, 0xb0, 0xd6, 0x96, 0x3f, 0x7d, 0x28, 0xe1,
+                0x7f, 0x72
+            ]
+        );
+    }
+
+    #[test]
+    fn should_hash_data_across_multiple_updates() {
+        let data = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        let mut hasher = Md5::new();
+
+        for chunk in data.chunks(7) {
+            hasher.update(chunk);
+        }
+
+        assert_eq!(hasher.finalize(), md5(data));
+        assert_eq!(
+            md5(data),
+            [
+                0x82, 0x15, 0xef, 0x07, 0x96, 0xa2, 0x0b, 0xca, 0xaa, 0xe1, 0x16, 0xd3, 0x87, 0x6c,
+                0x66, 0x4a
+            ]
+        );
+    }
+
+    #[test]
+    fn should_calculate_an_hmac() {
+        let result = hmac_md5(&[0x0b; 16], b"Hi There");
+        assert_eq!(
+            result,
+            [
+                0x92, 0x94, 0x72, 0x7a, 0x36, 0x38, 0xbb, 0x1c, 0x13, 0xf4, 0x8e, 0xf8, 0x15, 0x8b,
+                0xfc, 0x9d
+            ]
+        );
+    }
+
+    #[test]
+    fn should_hash_long_hmac_keys() {
+        let result = hmac_md5(
+            &[0xaa; 80],
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+        );
+        assert_eq!(
+            result,
+            [
+                0x6b, 0x1a, 0xb7, 0xfe, 0x4b, 0xd7, 0xbf, 0x8f, 0x0b, 0x62, 0xe6, 0xce, 0x61, 0xb9,
+                0xd0, 0xcd
+            ]
+        );
+    }
+}
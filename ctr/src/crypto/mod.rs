@@ -0,0 +1,12 @@
+mod aes;
+pub use aes::*;
+mod ccm;
+pub use ccm::*;
+mod md5;
+pub use md5::*;
+mod rc4;
+pub use rc4::*;
+mod sha1;
+pub use sha1::*;
+mod sha256;
+pub use sha256::*;
@@ -0,0 +1,72 @@
+/// An RC4 keystream.  RC4 isn't secure, so this is only meant for protocols that require it.
+///
+/// The keystream continues across calls to `apply`, so one instance should be kept per stream.
+#[derive(Clone)]
+pub struct Rc4 {
+    state: [u8; 256],
+    i: u8,
+    j: u8,
+}
+
+impl Rc4 {
+    /// Creates a keystream.  The key must be between 1 and 256 bytes.
+    pub fn new(key: &[u8]) -> Self {
+        assert!(!key.is_empty() && key.len() <= 256);
+
+        let mut state = [0u8; 256];
+        for (index, value) in state.iter_mut().enumerate() {
+            *value = index as u8;
+        }
+
+        let mut j = 0u8;
+        for index in 0..256 {
+            j = j
+                .wrapping_add(state[index])
+                .wrapping_add(key[index % key.len()]);
+            state.swap(index, j as usize);
+        }
+
+        Self { state, i: 0, j: 0 }
+    }
+
+    /// Encrypts or decrypts the data in place.
+    pub fn apply(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            self.i = self.i.wrapping_add(1);
+            self.j = self.j.wrapping_add(self.state[self.i as usize]);
+            self.state.swap(self.i as usize, self.j as usize);
+
+            let index = self.state[self.i as usize].wrapping_add(self.state[self.j as usize]);
+            *byte ^= self.state[index as usize];
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_encrypt_data() {
+        let mut data = *b"Plaintext";
+        Rc4::new(b"Key").apply(&mut data);
+        assert_eq!(data, [0xbb, 0xf3, 0x16, 0xe8, 0xd9, 0x40, 0xaf, 0x0a, 0xd3]);
+    }
+
+    #[test]
+    fn should_continue_the_keystream_across_calls() {
+        let mut expected = *b"Attack at dawn";
+        Rc4::new(b"Secret").apply(&mut expected);
+
+        let mut data = *b"Attack at dawn";
+        let mut rc4 = Rc4::new(b"Secret");
+        rc4.apply(&mut data[..5]);
+        rc4.apply(&mut data[5..]);
+
+        assert_eq!(data, expected);
+        assert_eq!(
+            expected,
+            [0x45, 0xa0, 0x1f, 0x64, 0x5f, 0xc3, 0x5b, 0x38, 0x35, 0x52, 0x54, 0x4b, 0x9b, 0xf5]
+        );
+    }
+}
@@ -0,0 +1,484 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/CIA, https://www.3dbrew.org/wiki/Ticket,
+    https://www.3dbrew.org/wiki/Title_metadata, and https://www.3dbrew.org/wiki/Certificates)
+    for the documentation
+*/
+
+mod signature;
+pub use signature::*;
+
+mod ticket;
+pub use ticket::*;
+
+mod tmd;
+pub use tmd::*;
+
+use super::{read_exact, smdh::Smdh};
+use crate::{
+    fs::FileOps,
+    res::{error, CtrResult},
+    TitleId,
+};
+use alloc::vec::Vec;
+use no_std_io::Reader;
+
+pub const CIA_HEADER_SIZE: usize = 0x2020;
+pub const CIA_META_SIZE: usize = 0x3ac0;
+
+const CONTENT_INDEX_OFFSET: usize = 0x20;
+const SMDH_OFFSET: usize = 0x400;
+// The root certificate isn't included in certificate chains
+const ROOT_ISSUER: &str = "Root";
+
+/// Sections in a CIA are aligned to 0x40 bytes.
+fn align_section(offset: u64) -> CtrResult<u64> {
+    let offset = offset.checked_add(0x3f).ok_or_else(error::invalid_size)?;
+    Ok(offset & !0x3f)
+}
+
+/// Returns the aligned offset of the section after one at `offset` with `size` bytes.
+fn next_section(offset: u64, size: u64) -> CtrResult<u64> {
+    align_section(offset.checked_add(size).ok_or_else(error::invalid_size)?)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CiaHeader {
+    pub header_size: u32,
+    pub cia_type: u16,
+    pub version: u16,
+    pub certificate_chain_size: u32,
+    pub ticket_size: u32,
+    pub tmd_size: u32,
+    pub meta_size: u32,
+    pub content_size: u64,
+    /// A bit field of the content indexes included in the CIA.
+    pub content_index: Vec<u8>,
+}
+
+impl CiaHeader {
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        if bytes.len() < CIA_HEADER_SIZE {
+            return Err(error::invalid_size());
+        }
+
+        Ok(Self {
+            header_size: bytes.read_le(0)?,
+            cia_type: bytes.read_le(0x4)?,
+            version: bytes.read_le(0x6)?,
+            certificate_chain_size: bytes.read_le(0x8)?,
+            ticket_size: bytes.read_le(0xc)?,
+            tmd_size: bytes.read_le(0x10)?,
+            meta_size: bytes.read_le(0x14)?,
+            content_size: bytes.read_le(0x18)?,
+            content_index: bytes[CONTENT_INDEX_OFFSET..CIA_HEADER_SIZE].to_vec(),
+        })
+    }
+
+    pub fn has_content(&self, index: u16) -> bool {
+        let byte = self.content_index[index as usize / 8];
+        byte & (0x80 >> (index % 8)) != 0
+    }
+
+    pub fn certificate_chain_offset(&self) -> CtrResult<u64> {
+        align_section(self.header_size as u64)
+    }
+
+    pub fn ticket_offset(&self) -> CtrResult<u64> {
+        next_section(
+            self.certificate_chain_offset()?,
+            self.certificate_chain_size as u64,
+        )
+    }
+
+    pub fn tmd_offset(&self) -> CtrResult<u64> {
+        next_section(self.ticket_offset()?, self.ticket_size as u64)
+    }
+
+    pub fn content_offset(&self) -> CtrResult<u64> {
+        next_section(self.tmd_offset()?, self.tmd_size as u64)
+    }
+
+    /// Errors if the content size would put the meta section past the end of a u64.
+    pub fn meta_offset(&self) -> CtrResult<u64> {
+        next_section(self.content_offset()?, self.content_size)
+    }
+}
+
+/// The optional meta section of a CIA.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CiaMeta {
+    /// The program ids of modules the title depends on.
+    pub dependencies: Vec<TitleId>,
+    pub core_version: u32,
+    pub smdh: Smdh,
+}
+
+impl CiaMeta {
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        if bytes.len() < CIA_META_SIZE {
+            return Err(error::invalid_size());
+        }
+
+        let dependencies = (0..48)
+            .map(|index| bytes.read_le::<TitleId>(index * 8))
+            .filter(|dependency| !matches!(dependency, Ok(title_id) if title_id.raw() == 0))
+            .collect::<Result<Vec<TitleId>, no_std_io::Error>>()?;
+
+        Ok(Self {
+            dependencies,
+            core_version: bytes.read_le(0x300)?,
+            smdh: Smdh::parse(&bytes[SMDH_OFFSET..])?,
+        })
+    }
+}
+
+/// A content included in a CIA.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CiaContent {
+    pub record: ContentChunkRecord,
+    /// The offset of the content in the CIA.
+    pub offset: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cia {
+    pub header: CiaHeader,
+    pub certificate_chain: Vec<Certificate>,
+    pub ticket: Ticket,
+    pub tmd: Tmd,
+    pub meta: Option<CiaMeta>,
+}
+
+impl Cia {
+    /// Reads everything except the contents of a CIA,
+    /// so the contents never need to be in memory.
+    pub fn read(file: &impl FileOps) -> CtrResult<Self> {
+        let header = CiaHeader::parse(&read_exact(file, 0, CIA_HEADER_SIZE)?)?;
+        let read_section = |offset: u64, size: u32| read_exact(file, offset, size as usize);
+
+        let certificate_chain = parse_certificate_chain(&read_section(
+            header.certificate_chain_offset()?,
+            header.certificate_chain_size,
+        )?)?;
+        let ticket = Ticket::parse(&read_section(header.ticket_offset()?, header.ticket_size)?)?;
+        let tmd = Tmd::parse(&read_section(header.tmd_offset()?, header.tmd_size)?)?;
+        let meta = match header.meta_size {
+            0 => None,
+            meta_size => Some(CiaMeta::parse(&read_section(
+                header.meta_offset()?,
+                meta_size,
+            )?)?),
+        };
+
+        Ok(Self {
+            header,
+            certificate_chain,
+            ticket,
+            tmd,
+            meta,
+        })
+    }
+
+    /// The contents included in the CIA, in the order they're stored.
+    /// Content 0 is usually an NCCH that can be read with [crate::formats::ncch::Ncch::read_at].
+    ///
+    /// Errors if the content sizes would put an offset past the end of a u64.
+    pub fn contents(&self) -> CtrResult<Vec<CiaContent>> {
+        let mut offset = self.header.content_offset()?;
+
+        self.tmd
+            .content_chunk_records
+            .iter()
+            .filter(|record| self.header.has_content(record.index))
+            .map(|record| {
+                let content = CiaContent {
+                    record: *record,
+                    offset,
+                };
+                offset = offset
+                    .checked_add(record.size)
+                    .ok_or_else(error::invalid_size)?;
+                Ok(content)
+            })
+            .collect()
+    }
+
+    /// Finds a certificate by the name other signatures use as their issuer.
+    pub fn find_certificate(&self, full_name: &str) -> Option<&Certificate> {
+        self.certificate_chain
+            .iter()
+            .find(|certificate| certificate.full_name() == full_name)
+    }
+
+    /// Checks the CIA is consistent before it's installed.
+    ///
+    /// This makes sure the ticket and TMD are for the same title, the TMD hashes are correct,
+    /// the content sizes add up, and every signature has an issuer with the right key type.
+    /// Signatures themselves aren't verified.
+    pub fn validate(&self) -> CtrResult {
+        if self.ticket.title_id != self.tmd.title_id {
+            return Err(error::invalid_combination());
+        }
+
+        if !self.tmd.verify_hashes() {
+            return Err(error::invalid_value());
+        }
+
+        let content_size = self
+            .contents()?
+            .iter()
+            .try_fold(0u64, |total, content| {
+                total.checked_add(content.record.size)
+            })
+            .ok_or_else(error::invalid_size)?;
+
+        if content_size != self.header.content_size {
+            return Err(error::invalid_size());
+        }
+
+        self.validate_issuer(&self.ticket.issuer, &self.ticket.signature)?;
+        self.validate_issuer(&self.tmd.issuer, &self.tmd.signature)?;
+
+        for certificate in self.certificate_chain.iter() {
+            if certificate.issuer != ROOT_ISSUER {
+                self.validate_issuer(&certificate.issuer, &certificate.signature)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_issuer(&self, issuer: &str, signature: &Signature) -> CtrResult {
+        let certificate = self.find_certificate(issuer).ok_or_else(error::not_found)?;
+
+        if certificate.key_type != signature.signature_type.key_type() {
+            return Err(error::invalid_combination());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        formats::smdh::{SMDH_MAGIC, SMDH_SIZE},
+        fs::{ArchiveId, ArchiveOps, FsPath, MemoryArchive, MemoryFile, MemoryFs, OpenFlags},
+    };
+    use alloc::vec;
+    use core::convert::TryFrom;
+    use no_std_io::Writer;
+    use signature::test::create_certificate;
+    use ticket::test::create_ticket;
+    use tmd::test::create_tmd;
+
+    const TITLE_ID: u64 = 0x0004000000123400;
+
+    fn create_certificate_chain() -> Vec<u8> {
+        let mut bytes = create_certificate(
+            SignatureType::Rsa4096Sha256,
+            "Root",
+            "CA00000003",
+            PublicKeyType::Rsa2048,
+        );
+        bytes.extend(create_certificate(
+            SignatureType::Rsa2048Sha256,
+            "Root-CA00000003",
+            "XS0000000c",
+            PublicKeyType::Rsa2048,
+        ));
+        bytes.extend(create_certificate(
+            SignatureType::Rsa2048Sha256,
+            "Root-CA00000003",
+            "CP0000000b",
+            PublicKeyType::Rsa2048,
+        ));
+        bytes
+    }
+
+    fn create_cia(ticket: &[u8], tmd: &[u8], contents: &[&[u8]]) -> Vec<u8> {
+        let certificate_chain = create_certificate_chain();
+        let content_size = contents.iter().map(|content| content.len()).sum::<usize>();
+        let mut meta = vec![0; CIA_META_SIZE];
+        meta.write_le(0, &0x0004013000001002u64).unwrap();
+        meta.write_le(0x300, &2u32).unwrap();
+        meta[SMDH_OFFSET..SMDH_OFFSET + 4].copy_from_slice(&SMDH_MAGIC);
+        assert_eq!(meta.len() - SMDH_OFFSET, SMDH_SIZE);
+
+        let mut bytes = vec![0; CIA_HEADER_SIZE];
+        bytes.write_le(0, &(CIA_HEADER_SIZE as u32)).unwrap();
+        bytes
+            .write_le(0x8, &(certificate_chain.len() as u32))
+            .unwrap();
+        bytes.write_le(0xc, &(ticket.len() as u32)).unwrap();
+        bytes.write_le(0x10, &(tmd.len() as u32)).unwrap();
+        bytes.write_le(0x14, &(meta.len() as u32)).unwrap();
+        bytes.write_le(0x18, &(content_size as u64)).unwrap();
+        // Contents 0 and 2 are included
+        bytes[CONTENT_INDEX_OFFSET] = 0xa0;
+
+        let sections = [
+            certificate_chain.as_slice(),
+            ticket,
+            tmd,
+            &contents.concat(),
+            &meta,
+        ];
+        for section in sections.iter() {
+            bytes.resize(align_section(bytes.len() as u64).unwrap() as usize, 0);
+            bytes.extend_from_slice(section);
+        }
+
+        bytes
+    }
+
+    fn create_file(contents: &[u8]) -> (MemoryArchive, MemoryFile) {
+        let fs = MemoryFs::new();
+        fs.mount_archive(ArchiveId::Sdmc, &FsPath::new_empty_path());
+        let archive = fs
+            .open_archive(ArchiveId::Sdmc, &FsPath::new_empty_path())
+            .unwrap();
+        let mut file = archive
+            .open_file(
+                &FsPath::try_from("/title.cia").unwrap(),
+                OpenFlags::ReadWriteCreate,
+            )
+            .unwrap();
+        file.write(contents).unwrap();
+        (archive, file)
+    }
+
+    fn read_cia(ticket: &[u8], tmd: &[u8]) -> Cia {
+        let contents: [&[u8]; 2] = [&[1; 0x40], &[2; 0x10]];
+        let (_archive, file) = create_file(&create_cia(ticket, tmd, &contents));
+        Cia::read(&file).unwrap()
+    }
+
+    mod read {
+        use super::*;
+
+        #[test]
+        fn should_read_every_section() {
+            let cia = read_cia(
+                &create_ticket(TITLE_ID, 0),
+                &create_tmd(TITLE_ID, &[0x40, 0x20, 0x10]),
+            );
+
+            assert_eq!(cia.certificate_chain.len(), 3);
+            assert_eq!(cia.ticket.title_id, TitleId::new(TITLE_ID));
+            assert_eq!(cia.tmd.content_chunk_records.len(), 3);
+
+            let meta = cia.meta.unwrap();
+            assert_eq!(meta.dependencies, vec![TitleId::new(0x0004013000001002)]);
+            assert_eq!(meta.core_version, 2);
+        }
+
+        #[test]
+        fn should_find_included_contents() {
+            let contents: [&[u8]; 2] = [&[1; 0x40], &[2; 0x10]];
+            let bytes = create_cia(
+                &create_ticket(TITLE_ID, 0),
+                &create_tmd(TITLE_ID, &[0x40, 0x20, 0x10]),
+                &contents,
+            );
+            let (_archive, file) = create_file(&bytes);
+            let cia = Cia::read(&file).unwrap();
+
+            let result = cia.contents().unwrap();
+
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0].record.index, 0);
+            assert_eq!(result[1].record.index, 2);
+            assert_eq!(bytes[result[0].offset as usize], 1);
+            assert_eq!(bytes[result[1].offset as usize], 2);
+        }
+
+        #[test]
+        fn should_error_if_the_meta_offset_overflows() {
+            let mut cia = read_cia(
+                &create_ticket(TITLE_ID, 0),
+                &create_tmd(TITLE_ID, &[0x40, 0x20, 0x10]),
+            );
+            cia.header.content_size = u64::MAX;
+
+            let result = cia.header.meta_offset().unwrap_err();
+
+            assert_eq!(result, error::invalid_size());
+        }
+
+        #[test]
+        fn should_error_if_a_section_is_truncated() {
+            let contents: [&[u8]; 2] = [&[1; 0x40], &[2; 0x10]];
+            let ticket = create_ticket(TITLE_ID, 0);
+            let bytes = create_cia(
+                &ticket[..0x104],
+                &create_tmd(TITLE_ID, &[0x40, 0x20, 0x10]),
+                &contents,
+            );
+            let (_archive, file) = create_file(&bytes);
+
+            let result = Cia::read(&file).unwrap_err();
+
+            assert_eq!(result, error::invalid_size());
+        }
+    }
+
+    mod validate {
+        use super::*;
+
+        #[test]
+        fn should_accept_valid_cias() {
+            let cia = read_cia(
+                &create_ticket(TITLE_ID, 0),
+                &create_tmd(TITLE_ID, &[0x40, 0x20, 0x10]),
+            );
+            cia.validate().unwrap();
+        }
+
+        #[test]
+        fn should_error_if_the_title_ids_do_not_match() {
+            let cia = read_cia(
+                &create_ticket(TITLE_ID + 0x100, 0),
+                &create_tmd(TITLE_ID, &[0x40, 0x20, 0x10]),
+            );
+
+            let result = cia.validate().unwrap_err();
+            assert_eq!(result, error::invalid_combination());
+        }
+
+        #[test]
+        fn should_error_if_the_content_sizes_do_not_match() {
+            let cia = read_cia(
+                &create_ticket(TITLE_ID, 0),
+                &create_tmd(TITLE_ID, &[0x40, 0x20, 0x20]),
+            );
+
+            let result = cia.validate().unwrap_err();
+            assert_eq!(result, error::invalid_size());
+        }
+
+        #[test]
+        fn should_error_if_a_content_size_overflows() {
+            let cia = read_cia(
+                &create_ticket(TITLE_ID, 0),
+                &create_tmd(TITLE_ID, &[u64::MAX, 0x20, 0x10]),
+            );
+
+            let result = cia.validate().unwrap_err();
+            assert_eq!(result, error::invalid_size());
+        }
+
+        #[test]
+        fn should_error_if_an_issuer_is_missing() {
+            let mut cia = read_cia(
+                &create_ticket(TITLE_ID, 0),
+                &create_tmd(TITLE_ID, &[0x40, 0x20, 0x10]),
+            );
+            cia.certificate_chain.remove(2);
+
+            let result = cia.validate().unwrap_err();
+            assert_eq!(result, error::not_found());
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{
+    formats::read_padded_string,
+    res::{error, CtrResult},
+};
+use alloc::{format, string::String, vec::Vec};
+use core::convert::TryFrom;
+use no_std_io::Reader;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum SignatureType {
+    Rsa4096Sha1 = 0x10000,
+    Rsa2048Sha1 = 0x10001,
+    EcdsaSha1 = 0x10002,
+    Rsa4096Sha256 = 0x10003,
+    Rsa2048Sha256 = 0x10004,
+    EcdsaSha256 = 0x10005,
+}
+
+impl SignatureType {
+    pub fn signature_size(&self) -> usize {
+        match self {
+            Self::Rsa4096Sha1 | Self::Rsa4096Sha256 => 0x200,
+            Self::Rsa2048Sha1 | Self::Rsa2048Sha256 => 0x100,
+            Self::EcdsaSha1 | Self::EcdsaSha256 => 0x3c,
+        }
+    }
+
+    fn padding_size(&self) -> usize {
+        match self {
+            Self::EcdsaSha1 | Self::EcdsaSha256 => 0x40,
+            _ => 0x3c,
+        }
+    }
+
+    /// The size of the signature type, signature, and padding that come before the signed data.
+    pub fn signed_data_offset(&self) -> usize {
+        4 + self.signature_size() + self.padding_size()
+    }
+
+    pub fn uses_sha256(&self) -> bool {
+        matches!(
+            self,
+            Self::Rsa4096Sha256 | Self::Rsa2048Sha256 | Self::EcdsaSha256
+        )
+    }
+
+    /// The type of key that can verify this signature.
+    pub fn key_type(&self) -> PublicKeyType {
+        match self {
+            Self::Rsa4096Sha1 | Self::Rsa4096Sha256 => PublicKeyType::Rsa4096,
+            Self::Rsa2048Sha1 | Self::Rsa2048Sha256 => PublicKeyType::Rsa2048,
+            Self::EcdsaSha1 | Self::EcdsaSha256 => PublicKeyType::Ecc,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub signature_type: SignatureType,
+    pub signature: Vec<u8>,
+}
+
+impl Signature {
+    /// Parses a signature, returning an error if the bytes end before the padding does.
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let raw_type: u32 = bytes.read_be(0)?;
+        let signature_type =
+            SignatureType::try_from(raw_type).map_err(|_| error::invalid_enum_value())?;
+
+        if bytes.len() < signature_type.signed_data_offset() {
+            return Err(error::invalid_size());
+        }
+
+        let signature = bytes
+            .get(4..4 + signature_type.signature_size())
+            .ok_or_else(error::invalid_size)?
+            .to_vec();
+
+        Ok(Self {
+            signature_type,
+            signature,
+        })
+    }
+
+    /// The offset of the signed data that follows the signature.
+    pub fn signed_data_offset(&self) -> usize {
+        self.signature_type.signed_data_offset()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum PublicKeyType {
+    Rsa4096 = 0,
+    Rsa2048 = 1,
+    Ecc = 2,
+}
+
+impl PublicKeyType {
+    /// The size of the key, including its exponent and padding.
+    fn size(&self) -> usize {
+        match self {
+            Self::Rsa4096 => 0x238,
+            Self::Rsa2048 => 0x138,
+            Self::Ecc => 0x78,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Certificate {
+    pub signature: Signature,
+    pub issuer: String,
+    pub key_type: PublicKeyType,
+    pub name: String,
+    pub expiration: u32,
+    /// The RSA modulus or ECC public key.
+    pub public_key: Vec<u8>,
+    /// The RSA exponent, or None for ECC keys.
+    pub exponent: Option<u32>,
+}
+
+impl Certificate {
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let signature = Signature::parse(bytes)?;
+        let data = bytes
+            .get(signature.signed_data_offset()..)
+            .ok_or_else(error::invalid_size)?;
+
+        if data.len() < 0x88 {
+            return Err(error::invalid_size());
+        }
+
+        let key_type = PublicKeyType::try_from(data.read_be::<u32>(0x40)?)
+            .map_err(|_| error::invalid_enum_value())?;
+        let public_key_size = match key_type {
+            PublicKeyType::Rsa4096 => 0x200,
+            PublicKeyType::Rsa2048 => 0x100,
+            PublicKeyType::Ecc => 0x3c,
+        };
+        let public_key = data
+            .get(0x88..0x88 + public_key_size)
+            .ok_or_else(error::invalid_size)?
+            .to_vec();
+        let exponent = match key_type {
+            PublicKeyType::Ecc => None,
+            _ => Some(data.read_be(0x88 + public_key_size)?),
+        };
+
+        Ok(Self {
+            signature,
+            issuer: read_padded_string(&data[..0x40])?,
+            key_type,
+            name: read_padded_string(&data[0x44..0x84])?,
+            expiration: data.read_be(0x84)?,
+            public_key,
+            exponent,
+        })
+    }
+
+    /// The size of the certificate in bytes.
+    pub fn size(&self) -> usize {
+        self.signature.signed_data_offset() + 0x88 + self.key_type.size()
+    }
+
+    /// The name other signatures use as their issuer, such as `Root-CA00000003-XS0000000c`.
+    pub fn full_name(&self) -> String {
+        format!("{}-{}", self.issuer, self.name)
+    }
+}
+
+/// Parses back to back certificates, such as the certificate chain of a CIA.
+pub fn parse_certificate_chain(mut bytes: &[u8]) -> CtrResult<Vec<Certificate>> {
+    let mut certificates = Vec::new();
+
+    while !bytes.is_empty() {
+        let certificate = Certificate::parse(bytes)?;
+        bytes = bytes.get(certificate.size()..).unwrap_or(&[]);
+        certificates.push(certificate);
+    }
+
+    Ok(certificates)
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use super::*;
+    use alloc::vec;
+    use no_std_io::Writer;
+
+    pub fn create_certificate(
+        signature_type: SignatureType,
+        issuer: &str,
+        name: &str,
+        key_type: PublicKeyType,
+    ) -> Vec<u8> {
+        let data_offset = signature_type.signed_data_offset();
+        let mut bytes = vec![0; data_offset + 0x88 + key_type.size()];
+
+        bytes.write_be(0, &u32::from(signature_type)).unwrap();
+        bytes[data_offset..data_offset + issuer.len()].copy_from_slice(issuer.as_bytes());
+        bytes
+            .write_be(data_offset + 0x40, &u32::from(key_type))
+            .unwrap();
+        bytes[data_offset + 0x44..data_offset + 0x44 + name.len()].copy_from_slice(name.as_bytes());
+        bytes.write_be(data_offset + 0x84, &0x12345678u32).unwrap();
+
+        if key_type != PublicKeyType::Ecc {
+            let exponent_offset = data_offset + 0x88 + key_type.size() - 0x38;
+            bytes.write_be(exponent_offset, &0x10001u32).unwrap();
+        }
+
+        bytes
+    }
+
+    mod signature {
+        use super::*;
+
+        #[test]
+        fn should_parse_signatures() {
+            let mut bytes = vec![0; 0x140];
+            bytes.write_be(0, &0x10004u32).unwrap();
+            bytes[4] = 0xaa;
+
+            let result = Signature::parse(&bytes).unwrap();
+
+            assert_eq!(result.signature_type, SignatureType::Rsa2048Sha256);
+            assert_eq!(result.signature.len(), 0x100);
+            assert_eq!(result.signature[0], 0xaa);
+            assert_eq!(result.signed_data_offset(), 0x140);
+        }
+
+        #[test]
+        fn should_error_if_the_padding_is_truncated() {
+            let mut bytes = vec![0; 0x13f];
+            bytes.write_be(0, &0x10004u32).unwrap();
+
+            let result = Signature::parse(&bytes).unwrap_err();
+
+            assert_eq!(result, error::invalid_size());
+        }
+
+        #[test]
+        fn should_error_for_unknown_signature_types() {
+            let bytes = [0, 2, 0, 0, 0, 0, 0, 0];
+            let result = Signature::parse(&bytes).unwrap_err();
+            assert_eq!(result, error::invalid_enum_value());
+        }
+    }
+
+    mod parse_certificate_chain {
+        use super::*;
+
+        #[test]
+        fn should_parse_each_certificate() {
+            let mut bytes = create_certificate(
+                SignatureType::Rsa4096Sha256,
+                "Root",
+                "CA00000003",
+                PublicKeyType::Rsa2048,
+            );
+            bytes.extend(create_certificate(
+                SignatureType::Rsa2048Sha256,
+                "Root-CA00000003",
+                "XS0000000c",
+                PublicKeyType::Ecc,
+            ));
+
+            let result = parse_certificate_chain(&bytes).unwrap();
+
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0].full_name(), "Root-CA00000003");
+            assert_eq!(result[0].key_type, PublicKeyType::Rsa2048);
+            assert_eq!(result[0].exponent, Some(0x10001));
+            assert_eq!(result[0].expiration, 0x12345678);
+            assert_eq!(result[1].full_name(), "Root-CA00000003-XS0000000c");
+            assert_eq!(result[1].exponent, None);
+            assert_eq!(result[1].public_key.len(), 0x3c);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use super::Signature;
+use crate::{
+    formats::read_padded_string,
+    res::{error, CtrResult},
+    TitleId,
+};
+use alloc::{string::String, vec::Vec};
+use core::convert::TryInto;
+use no_std_io::Reader;
+
+const CONTENT_INDEX_OFFSET: usize = 0x164;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ticket {
+    pub signature: Signature,
+    pub issuer: String,
+    pub ecc_public_key: [u8; 0x3c],
+    pub version: u8,
+    /// The title key, which is encrypted with the common key.
+    pub title_key: [u8; 0x10],
+    pub ticket_id: u64,
+    /// The console the ticket was issued to, or 0 for tickets that aren't console specific.
+    pub console_id: u32,
+    pub title_id: TitleId,
+    pub title_version: u16,
+    pub license_type: u8,
+    pub common_key_index: u8,
+    pub eshop_account_id: u32,
+    pub audit: u8,
+    pub limits: [u8; 0x40],
+    /// The raw content index, which lists the contents the ticket allows.
+    pub content_index: Vec<u8>,
+}
+
+impl Ticket {
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let signature = Signature::parse(bytes)?;
+        let data = bytes
+            .get(signature.signed_data_offset()..)
+            .ok_or_else(error::invalid_size)?;
+
+        if data.len() < CONTENT_INDEX_OFFSET + 8 {
+            return Err(error::invalid_size());
+        }
+
+        let content_index_size: u32 = data.read_be(CONTENT_INDEX_OFFSET + 4)?;
+        let content_index = data
+            .get(CONTENT_INDEX_OFFSET..CONTENT_INDEX_OFFSET + content_index_size as usize)
+            .ok_or_else(error::invalid_size)?
+            .to_vec();
+
+        Ok(Self {
+            signature,
+            issuer: read_padded_string(&data[..0x40])?,
+            ecc_public_key: data[0x40..0x7c].try_into().unwrap(),
+            version: data[0x7c],
+            title_key: data[0x7f..0x8f].try_into().unwrap(),
+            ticket_id: data.read_be(0x90)?,
+            console_id: data.read_be(0x98)?,
+            title_id: data.read_be(0x9c)?,
+            title_version: data.read_be(0xa6)?,
+            license_type: data[0xb0],
+            common_key_index: data[0xb1],
+            eshop_account_id: data.read_be(0xdc)?,
+            audit: data[0xe1],
+            limits: data[0x124..CONTENT_INDEX_OFFSET].try_into().unwrap(),
+            content_index,
+        })
+    }
+
+    /// The size of the ticket in bytes.
+    pub fn size(&self) -> usize {
+        self.signature.signed_data_offset() + CONTENT_INDEX_OFFSET + self.content_index.len()
+    }
+
+    pub fn is_console_specific(&self) -> bool {
+        self.console_id != 0
+    }
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use super::*;
+    use crate::formats::cia::SignatureType;
+    use alloc::vec;
+    use no_std_io::Writer;
+
+    pub fn create_ticket(title_id: u64, console_id: u32) -> Vec<u8> {
+        let data_offset = SignatureType::Rsa2048Sha256.signed_data_offset();
+        let mut bytes = vec![0; data_offset + CONTENT_INDEX_OFFSET + 0xac];
+        bytes.write_be(0, &0x10004u32).unwrap();
+
+        let mut data = &mut bytes[data_offset..];
+        data[..26].copy_from_slice(b"Root-CA00000003-XS0000000c");
+        data[0x7c] = 1;
+        data[0x7f..0x8f].copy_from_slice(&[0xab; 0x10]);
+        data.write_be(0x90, &0x0004000012345678u64).unwrap();
+        data.write_be(0x98, &console_id).unwrap();
+        data.write_be(0x9c, &title_id).unwrap();
+        data.write_be(0xa6, &0x0410u16).unwrap();
+        data[0xb1] = 1;
+        data.write_be(CONTENT_INDEX_OFFSET, &0x10014u32).unwrap();
+        data.write_be(CONTENT_INDEX_OFFSET + 4, &0xacu32).unwrap();
+
+        bytes
+    }
+
+    #[test]
+    fn should_parse_tickets() {
+        let bytes = create_ticket(0x0004000000123400, 0x11223344);
+        let result = Ticket::parse(&bytes).unwrap();
+
+        assert_eq!(result.issuer, "Root-CA00000003-XS0000000c");
+        assert_eq!(result.version, 1);
+        assert_eq!(result.title_key, [0xab; 0x10]);
+        assert_eq!(result.ticket_id, 0x0004000012345678);
+        assert_eq!(result.console_id, 0x11223344);
+        assert!(result.is_console_specific());
+        assert_eq!(result.title_id, TitleId::new(0x0004000000123400));
+        assert_eq!(result.title_version, 0x0410);
+        assert_eq!(result.common_key_index, 1);
+        assert_eq!(result.content_index.len(), 0xac);
+        assert_eq!(result.size(), bytes.len());
+    }
+}
@@ -0,0 +1,247 @@
+use super::Signature;
+use crate::{
+    crypto::{sha256, SHA256_HASH_SIZE},
+    formats::read_padded_string,
+    res::{error, CtrResult},
+    TitleId,
+};
+use alloc::{string::String, vec::Vec};
+use core::convert::TryInto;
+use no_std_io::Reader;
+use num_enum::IntoPrimitive;
+
+const CONTENT_INFO_RECORD_COUNT: usize = 64;
+const CONTENT_INFO_RECORD_SIZE: usize = 0x24;
+const CONTENT_INFO_RECORDS_OFFSET: usize = 0xc4;
+const CONTENT_CHUNK_RECORDS_OFFSET: usize =
+    CONTENT_INFO_RECORDS_OFFSET + CONTENT_INFO_RECORD_COUNT * CONTENT_INFO_RECORD_SIZE;
+const CONTENT_CHUNK_RECORD_SIZE: usize = 0x30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u16)]
+pub enum ContentType {
+    Encrypted = 0x1,
+    Disc = 0x2,
+    Cfm = 0x4,
+    Optional = 0x4000,
+    Shared = 0x8000,
+}
+
+/// Describes a group of content chunk records and their hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContentInfoRecord {
+    pub index_offset: u16,
+    pub command_count: u16,
+    pub hash: [u8; SHA256_HASH_SIZE],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContentChunkRecord {
+    pub id: u32,
+    pub index: u16,
+    pub content_type: u16,
+    pub size: u64,
+    pub hash: [u8; SHA256_HASH_SIZE],
+}
+
+impl ContentChunkRecord {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        Ok(Self {
+            id: bytes.read_be(0)?,
+            index: bytes.read_be(0x4)?,
+            content_type: bytes.read_be(0x6)?,
+            size: bytes.read_be(0x8)?,
+            hash: bytes[0x10..0x30].try_into().unwrap(),
+        })
+    }
+
+    pub fn has_type(&self, content_type: ContentType) -> bool {
+        self.content_type & u16::from(content_type) != 0
+    }
+}
+
+/// A title metadata file, which lists the contents of a title.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tmd {
+    pub signature: Signature,
+    pub issuer: String,
+    pub version: u8,
+    pub system_version: u64,
+    pub title_id: TitleId,
+    pub title_type: u32,
+    pub group_id: u16,
+    pub save_data_size: u32,
+    pub srl_private_save_data_size: u32,
+    pub srl_flag: u8,
+    pub access_rights: u32,
+    pub title_version: u16,
+    pub boot_content: u16,
+    pub content_info_records_hash: [u8; SHA256_HASH_SIZE],
+    pub content_info_records: Vec<ContentInfoRecord>,
+    pub content_chunk_records: Vec<ContentChunkRecord>,
+    /// The signed data, which is kept to verify hashes.
+    data: Vec<u8>,
+}
+
+impl Tmd {
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let signature = Signature::parse(bytes)?;
+        let data = bytes
+            .get(signature.signed_data_offset()..)
+            .ok_or_else(error::invalid_size)?;
+
+        if data.len() < CONTENT_CHUNK_RECORDS_OFFSET {
+            return Err(error::invalid_size());
+        }
+
+        let content_count: u16 = data.read_be(0x9e)?;
+        let data = data
+            .get(
+                ..CONTENT_CHUNK_RECORDS_OFFSET + content_count as usize * CONTENT_CHUNK_RECORD_SIZE,
+            )
+            .ok_or_else(error::invalid_size)?;
+
+        let content_info_records = data[CONTENT_INFO_RECORDS_OFFSET..CONTENT_CHUNK_RECORDS_OFFSET]
+            .chunks_exact(CONTENT_INFO_RECORD_SIZE)
+            .map(|record| {
+                Ok(ContentInfoRecord {
+                    index_offset: record.read_be(0)?,
+                    command_count: record.read_be(0x2)?,
+                    hash: record[0x4..0x24].try_into().unwrap(),
+                })
+            })
+            .collect::<CtrResult<Vec<ContentInfoRecord>>>()?;
+        let content_chunk_records = data[CONTENT_CHUNK_RECORDS_OFFSET..]
+            .chunks_exact(CONTENT_CHUNK_RECORD_SIZE)
+            .map(ContentChunkRecord::parse)
+            .collect::<CtrResult<Vec<ContentChunkRecord>>>()?;
+
+        Ok(Self {
+            signature,
+            issuer: read_padded_string(&data[..0x40])?,
+            version: data[0x40],
+            system_version: data.read_be(0x44)?,
+            title_id: data.read_be(0x4c)?,
+            title_type: data.read_be(0x54)?,
+            group_id: data.read_be(0x58)?,
+            // Unlike the rest of the TMD, these are little endian
+            save_data_size: data.read_le(0x5a)?,
+            srl_private_save_data_size: data.read_le(0x5e)?,
+            srl_flag: data[0x66],
+            access_rights: data.read_be(0x98)?,
+            title_version: data.read_be(0x9c)?,
+            boot_content: data.read_be(0xa0)?,
+            content_info_records_hash: data[0xa4..0xc4].try_into().unwrap(),
+            content_info_records,
+            content_chunk_records,
+            data: data.to_vec(),
+        })
+    }
+
+    /// The size of the TMD in bytes.
+    pub fn size(&self) -> usize {
+        self.signature.signed_data_offset() + self.data.len()
+    }
+
+    /// Checks the hash of the content info records, and the hashes of the content chunk records they describe.
+    pub fn verify_hashes(&self) -> bool {
+        let info_records = &self.data[CONTENT_INFO_RECORDS_OFFSET..CONTENT_CHUNK_RECORDS_OFFSET];
+
+        if sha256(info_records) != self.content_info_records_hash {
+            return false;
+        }
+
+        self.content_info_records
+            .iter()
+            .filter(|record| record.command_count != 0)
+            .all(|record| {
+                let start = CONTENT_CHUNK_RECORDS_OFFSET
+                    + record.index_offset as usize * CONTENT_CHUNK_RECORD_SIZE;
+                let end = start + record.command_count as usize * CONTENT_CHUNK_RECORD_SIZE;
+
+                match self.data.get(start..end) {
+                    Some(chunk_records) => sha256(chunk_records) == record.hash,
+                    None => false,
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use super::*;
+    use crate::formats::cia::SignatureType;
+    use alloc::vec;
+    use no_std_io::Writer;
+
+    /// Creates a TMD with valid hashes for contents of the given sizes.
+    pub fn create_tmd(title_id: u64, content_sizes: &[u64]) -> Vec<u8> {
+        let data_offset = SignatureType::Rsa2048Sha256.signed_data_offset();
+        let mut bytes = vec![
+            0;
+            data_offset
+                + CONTENT_CHUNK_RECORDS_OFFSET
+                + content_sizes.len() * CONTENT_CHUNK_RECORD_SIZE
+        ];
+        bytes.write_be(0, &0x10004u32).unwrap();
+
+        let mut data = &mut bytes[data_offset..];
+        data[..26].copy_from_slice(b"Root-CA00000003-CP0000000b");
+        data[0x40] = 1;
+        data.write_be(0x4c, &title_id).unwrap();
+        data.write_be(0x54, &0x40u32).unwrap();
+        data.write_le(0x5a, &0x80000u32).unwrap();
+        data.write_be(0x9c, &0x0410u16).unwrap();
+        data.write_be(0x9e, &(content_sizes.len() as u16)).unwrap();
+
+        for (index, size) in content_sizes.iter().enumerate() {
+            let offset = CONTENT_CHUNK_RECORDS_OFFSET + index * CONTENT_CHUNK_RECORD_SIZE;
+            data.write_be(offset, &(index as u32 + 0x10)).unwrap();
+            data.write_be(offset + 0x4, &(index as u16)).unwrap();
+            data.write_be(offset + 0x8, size).unwrap();
+        }
+
+        let chunk_records_hash = sha256(&data[CONTENT_CHUNK_RECORDS_OFFSET..]);
+        data.write_be(
+            CONTENT_INFO_RECORDS_OFFSET + 2,
+            &(content_sizes.len() as u16),
+        )
+        .unwrap();
+        data[CONTENT_INFO_RECORDS_OFFSET + 4..CONTENT_INFO_RECORDS_OFFSET + 0x24]
+            .copy_from_slice(&chunk_records_hash);
+        let info_records_hash =
+            sha256(&data[CONTENT_INFO_RECORDS_OFFSET..CONTENT_CHUNK_RECORDS_OFFSET]);
+        data[0xa4..0xc4].copy_from_slice(&info_records_hash);
+
+        bytes
+    }
+
+    #[test]
+    fn should_parse_tmds() {
+        let bytes = create_tmd(0x0004000000123400, &[0x1000, 0x200]);
+        let result = Tmd::parse(&bytes).unwrap();
+
+        assert_eq!(result.issuer, "Root-CA00000003-CP0000000b");
+        assert_eq!(result.title_id, TitleId::new(0x0004000000123400));
+        assert_eq!(result.save_data_size, 0x80000);
+        assert_eq!(result.title_version, 0x0410);
+        assert_eq!(result.content_info_records.len(), 64);
+        assert_eq!(result.content_chunk_records.len(), 2);
+        assert_eq!(result.content_chunk_records[1].id, 0x11);
+        assert_eq!(result.content_chunk_records[1].index, 1);
+        assert_eq!(result.content_chunk_records[1].size, 0x200);
+        assert!(!result.content_chunk_records[1].has_type(ContentType::Encrypted));
+        assert_eq!(result.size(), bytes.len());
+    }
+
+    #[test]
+    fn should_verify_hashes() {
+        let mut bytes = create_tmd(0x0004000000123400, &[0x1000, 0x200]);
+        assert!(Tmd::parse(&bytes).unwrap().verify_hashes());
+
+        // Change a content size
+        let last = bytes.len() - 0x21;
+        bytes[last] = 0xff;
+        assert!(!Tmd::parse(&bytes).unwrap().verify_hashes());
+    }
+}
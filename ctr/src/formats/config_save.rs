@@ -0,0 +1,443 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/Config_Savegame) for the documentation
+*/
+
+use super::read_exact;
+use crate::{
+    cfg::{ConfigBlock, WritableConfigBlock},
+    fs::FileOps,
+    res::{error, CtrResult},
+};
+use alloc::{vec, vec::Vec};
+use core::convert::TryInto;
+use no_std_io::{EndianRead, EndianWrite, Reader, Writer};
+use num_enum::IntoPrimitive;
+
+pub const CONFIG_SAVE_SIZE: usize = 0x8000;
+pub const MAX_BLOCK_COUNT: usize = 1479;
+
+/// The default offset of the first out of line block.
+pub const DEFAULT_DATA_OFFSET: u16 = 0x455c;
+
+const BLOCK_ENTRIES_OFFSET: usize = 0x4;
+const BLOCK_ENTRY_SIZE: usize = 0xc;
+
+/// Blocks of this size or smaller are stored in the block entry instead of the data section.
+pub const MAX_INLINE_BLOCK_SIZE: usize = 4;
+
+/// Who is allowed to access a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u16)]
+pub enum BlockAccessFlag {
+    /// Readable with GetConfigInfoBlk2 through cfg:u.
+    UserRead = 0x2,
+    /// Writable with SetConfigInfoBlk4 through cfg:s.
+    SystemWrite = 0x4,
+    /// Readable and writable with the Blk8 commands through cfg:i.
+    InternalReadWrite = 0x8,
+}
+
+#[derive(Clone, Copy, Debug, EndianRead, EndianWrite)]
+struct BlockEntry {
+    id: u32,
+    offset_or_data: u32,
+    size: u16,
+    flags: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigSaveBlock {
+    pub id: u32,
+    pub flags: u16,
+    pub data: Vec<u8>,
+}
+
+impl ConfigSaveBlock {
+    /// Whether the data is stored in the block entry.
+    pub fn is_inline(&self) -> bool {
+        self.data.len() <= MAX_INLINE_BLOCK_SIZE
+    }
+
+    pub fn has_access(&self, flag: BlockAccessFlag) -> bool {
+        self.flags & u16::from(flag) != 0
+    }
+}
+
+/// The config savegame, which cfg stores on the NAND as `/config` in its system save data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigSave {
+    data_offset: u16,
+    blocks: Vec<ConfigSaveBlock>,
+}
+
+impl Default for ConfigSave {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigSave {
+    pub fn new() -> Self {
+        Self {
+            data_offset: DEFAULT_DATA_OFFSET,
+            blocks: vec![],
+        }
+    }
+
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        if bytes.len() < CONFIG_SAVE_SIZE {
+            return Err(error::invalid_size());
+        }
+
+        let block_count: usize = bytes.read_le::<u16>(0)?.into();
+        let data_offset: u16 = bytes.read_le(2)?;
+
+        if block_count > MAX_BLOCK_COUNT {
+            return Err(error::invalid_size());
+        }
+
+        let blocks = (0..block_count)
+            .map(|index| {
+                let entry: BlockEntry =
+                    bytes.read_le(BLOCK_ENTRIES_OFFSET + index * BLOCK_ENTRY_SIZE)?;
+                let size: usize = entry.size.into();
+
+                let data = if size <= MAX_INLINE_BLOCK_SIZE {
+                    entry.offset_or_data.to_le_bytes()[..size].to_vec()
+                } else {
+                    let start: usize = entry.offset_or_data.try_into()?;
+                    let end = start.checked_add(size).ok_or_else(error::invalid_size)?;
+                    bytes
+                        .get(start..end)
+                        .ok_or_else(error::invalid_size)?
+                        .to_vec()
+                };
+
+                Ok(ConfigSaveBlock {
+                    id: entry.id,
+                    flags: entry.flags,
+                    data,
+                })
+            })
+            .collect::<CtrResult<Vec<ConfigSaveBlock>>>()?;
+
+        Ok(Self {
+            data_offset,
+            blocks,
+        })
+    }
+
+    pub fn read(file: &impl FileOps) -> CtrResult<Self> {
+        Self::parse(&read_exact(file, 0, CONFIG_SAVE_SIZE)?)
+    }
+
+    /// Serializes the savegame, storing out of line data in block order.
+    pub fn to_bytes(&self) -> CtrResult<Vec<u8>> {
+        if self.blocks.len() > MAX_BLOCK_COUNT {
+            return Err(error::invalid_size());
+        }
+
+        let mut result = vec![0u8; CONFIG_SAVE_SIZE];
+        let block_count: u16 = self.blocks.len().try_into()?;
+        result.write_le(0, &block_count)?;
+        result.write_le(2, &self.data_offset)?;
+
+        let mut data_offset: usize = self.data_offset.into();
+
+        for (index, block) in self.blocks.iter().enumerate() {
+            let offset_or_data = if block.is_inline() {
+                let mut inline_data = [0u8; 4];
+                inline_data[..block.data.len()].copy_from_slice(&block.data);
+                u32::from_le_bytes(inline_data)
+            } else {
+                let end = data_offset + block.data.len();
+                result
+                    .get_mut(data_offset..end)
+                    .ok_or_else(error::invalid_size)?
+                    .copy_from_slice(&block.data);
+
+                let offset = data_offset.try_into()?;
+                data_offset = end;
+                offset
+            };
+
+            let entry = BlockEntry {
+                id: block.id,
+                offset_or_data,
+                size: block.data.len().try_into()?,
+                flags: block.flags,
+            };
+            result.write_le(BLOCK_ENTRIES_OFFSET + index * BLOCK_ENTRY_SIZE, &entry)?;
+        }
+
+        Ok(result)
+    }
+
+    pub fn blocks(&self) -> &[ConfigSaveBlock] {
+        &self.blocks
+    }
+
+    pub fn find(&self, id: u32) -> Option<&ConfigSaveBlock> {
+        self.blocks.iter().find(|block| block.id == id)
+    }
+
+    pub fn find_mut(&mut self, id: u32) -> Option<&mut ConfigSaveBlock> {
+        self.blocks.iter_mut().find(|block| block.id == id)
+    }
+
+    /// Adds a block, or replaces the data and flags of an existing block with the same id.
+    pub fn insert(&mut self, block: ConfigSaveBlock) -> CtrResult {
+        if block.data.len() > u16::MAX.into() {
+            return Err(error::invalid_size());
+        }
+
+        if let Some(existing) = self.find_mut(block.id) {
+            *existing = block;
+            return Ok(());
+        }
+
+        if self.blocks.len() >= MAX_BLOCK_COUNT {
+            return Err(error::invalid_size());
+        }
+
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    /// Decodes a block with the same definitions `cfg` uses.
+    pub fn get_block<T: ConfigBlock>(&self) -> CtrResult<T> {
+        let block = self.find(T::ID).ok_or_else(error::not_found)?;
+        T::from_bytes(&block.data)
+    }
+
+    /// Encodes a block over its existing data, keeping any bytes the block doesn't decode.
+    pub fn set_block<T: WritableConfigBlock>(&mut self, value: &T) -> CtrResult {
+        let block = self.find_mut(T::ID).ok_or_else(error::not_found)?;
+        value.write_bytes(&mut block.data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cfg::{Birthday, SystemLanguage, Username};
+    use alloc::string::String;
+
+    fn create_config_save() -> ConfigSave {
+        let mut config_save = ConfigSave::new();
+        config_save
+            .insert(ConfigSaveBlock {
+                id: SystemLanguage::ID,
+                flags: 0xe,
+                data: vec![1],
+            })
+            .unwrap();
+        config_save
+            .insert(ConfigSaveBlock {
+                id: Username::ID,
+                flags: 0xe,
+                data: vec![
+                    0x41, 0, 0x42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                    1, 0, 0, 0,
+                ],
+            })
+            .unwrap();
+        config_save
+            .insert(ConfigSaveBlock {
+                id: Birthday::ID,
+                flags: 0xc,
+                data: vec![4, 1],
+            })
+            .unwrap();
+        config_save
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_parse_inline_and_out_of_line_blocks() {
+            let mut bytes = vec![0u8; CONFIG_SAVE_SIZE];
+            bytes.write_le(0, &2u16).unwrap();
+            bytes.write_le(2, &DEFAULT_DATA_OFFSET).unwrap();
+            bytes
+                .write_le(
+                    4,
+                    &BlockEntry {
+                        id: 0xa0001,
+                        offset_or_data: 0x0c03,
+                        size: 2,
+                        flags: 0xe,
+                    },
+                )
+                .unwrap();
+            bytes
+                .write_le(
+                    0x10,
+                    &BlockEntry {
+                        id: 0x70000,
+                        offset_or_data: 0x455c,
+                        size: 6,
+                        flags: 0x8,
+                    },
+                )
+                .unwrap();
+            bytes[0x455c..0x4562].copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+
+            let result = ConfigSave::parse(&bytes).unwrap();
+            let birthday = result.find(0xa0001).unwrap();
+            let out_of_line = result.find(0x70000).unwrap();
+
+            assert_eq!(result.blocks().len(), 2);
+            assert!(birthday.is_inline());
+            assert_eq!(birthday.data, vec![3, 0xc]);
+            assert!(birthday.has_access(BlockAccessFlag::UserRead));
+            assert!(!out_of_line.is_inline());
+            assert_eq!(out_of_line.data, vec![1, 2, 3, 4, 5, 6]);
+            assert!(out_of_line.has_access(BlockAccessFlag::InternalReadWrite));
+            assert!(!out_of_line.has_access(BlockAccessFlag::SystemWrite));
+        }
+
+        #[test]
+        fn should_error_if_the_data_is_too_small() {
+            let result = ConfigSave::parse(&[0; 0x100]).unwrap_err();
+            assert_eq!(result, error::invalid_size());
+        }
+
+        #[test]
+        fn should_error_if_block_data_is_out_of_bounds() {
+            let mut bytes = vec![0u8; CONFIG_SAVE_SIZE];
+            bytes.write_le(0, &1u16).unwrap();
+            bytes
+                .write_le(
+                    4,
+                    &BlockEntry {
+                        id: 0x70000,
+                        offset_or_data: 0x7ffe,
+                        size: 8,
+                        flags: 0x8,
+                    },
+                )
+                .unwrap();
+
+            let result = ConfigSave::parse(&bytes).unwrap_err();
+            assert_eq!(result, error::invalid_size());
+        }
+
+        #[test]
+        fn should_error_if_block_data_ends_past_the_address_space() {
+            let mut bytes = vec![0u8; CONFIG_SAVE_SIZE];
+            bytes.write_le(0, &1u16).unwrap();
+            bytes
+                .write_le(
+                    4,
+                    &BlockEntry {
+                        id: 0x70000,
+                        offset_or_data: u32::MAX,
+                        size: 8,
+                        flags: 0x8,
+                    },
+                )
+                .unwrap();
+
+            let result = ConfigSave::parse(&bytes).unwrap_err();
+            assert_eq!(result, error::invalid_size());
+        }
+    }
+
+    mod to_bytes {
+        use super::*;
+
+        #[test]
+        fn should_round_trip() {
+            let config_save = create_config_save();
+            let bytes = config_save.to_bytes().unwrap();
+
+            assert_eq!(bytes.len(), CONFIG_SAVE_SIZE);
+            assert_eq!(bytes.read_le::<u32>(0x14).unwrap(), 0x455c);
+            assert_eq!(ConfigSave::parse(&bytes).unwrap(), config_save);
+            assert_eq!(
+                ConfigSave::parse(&bytes).unwrap().to_bytes().unwrap(),
+                bytes
+            );
+        }
+
+        #[test]
+        fn should_error_if_the_data_does_not_fit() {
+            let mut config_save = ConfigSave::new();
+            config_save
+                .insert(ConfigSaveBlock {
+                    id: 1,
+                    flags: 0x8,
+                    data: vec![0; 0x4000],
+                })
+                .unwrap();
+
+            let result = config_save.to_bytes().unwrap_err();
+            assert_eq!(result, error::invalid_size());
+        }
+    }
+
+    mod blocks {
+        use super::*;
+
+        #[test]
+        fn should_get_typed_blocks() {
+            let config_save = create_config_save();
+
+            assert_eq!(
+                config_save.get_block::<SystemLanguage>().unwrap(),
+                SystemLanguage::English
+            );
+            assert_eq!(
+                config_save.get_block::<Username>().unwrap().name,
+                String::from("AB")
+            );
+        }
+
+        #[test]
+        fn should_set_typed_blocks() {
+            let mut config_save = create_config_save();
+
+            config_save
+                .set_block(&Username {
+                    name: String::from("Test"),
+                })
+                .unwrap();
+
+            let block = config_save.find(Username::ID).unwrap();
+            assert_eq!(
+                config_save.get_block::<Username>().unwrap().name,
+                String::from("Test")
+            );
+            assert_eq!(block.data[0x18..], [1, 0, 0, 0]);
+        }
+
+        #[test]
+        fn should_error_if_the_block_does_not_exist() {
+            let config_save = ConfigSave::new();
+
+            let result = config_save.get_block::<Birthday>().unwrap_err();
+            assert_eq!(result, error::not_found());
+        }
+
+        #[test]
+        fn should_replace_existing_blocks() {
+            let mut config_save = create_config_save();
+
+            config_save
+                .insert(ConfigSaveBlock {
+                    id: SystemLanguage::ID,
+                    flags: 0xe,
+                    data: vec![3],
+                })
+                .unwrap();
+
+            assert_eq!(config_save.blocks().len(), 3);
+            assert_eq!(
+                config_save.get_block::<SystemLanguage>().unwrap(),
+                SystemLanguage::German
+            );
+        }
+    }
+}
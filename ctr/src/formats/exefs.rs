@@ -0,0 +1,238 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/ExeFS) for the documentation
+*/
+
+use super::{ncch::NcchHeader, read_exact, read_padded_string};
+use crate::{
+    crypto::{sha256, SHA256_HASH_SIZE},
+    fs::{error as fs_error, FileOps},
+    res::{error, CtrResult},
+};
+use alloc::{string::String, vec::Vec};
+use core::convert::TryInto;
+use no_std_io::Reader;
+
+pub const EXEFS_HEADER_SIZE: usize = 0x200;
+
+const MAX_FILE_COUNT: usize = 10;
+const LAST_HASH_OFFSET: usize = 0x1e0;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExeFsEntry {
+    pub name: String,
+    /// The offset of the file from the end of the ExeFS header.
+    pub offset: u32,
+    pub size: u32,
+    pub hash: [u8; SHA256_HASH_SIZE],
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct ExeFsHeader {
+    pub entries: Vec<ExeFsEntry>,
+}
+
+impl ExeFsHeader {
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        if bytes.len() < EXEFS_HEADER_SIZE {
+            return Err(error::invalid_size());
+        }
+
+        let mut entries = Vec::new();
+
+        for index in 0..MAX_FILE_COUNT {
+            let entry_offset = index * 0x10;
+            let name = read_padded_string(&bytes[entry_offset..entry_offset + 8])?;
+
+            if name.is_empty() {
+                continue;
+            }
+
+            // Hashes are stored in reverse order, so the first file's hash is last
+            let hash_offset = LAST_HASH_OFFSET - index * SHA256_HASH_SIZE;
+
+            entries.push(ExeFsEntry {
+                name,
+                offset: bytes.read_le(entry_offset + 8)?,
+                size: bytes.read_le(entry_offset + 12)?,
+                hash: bytes[hash_offset..hash_offset + SHA256_HASH_SIZE]
+                    .try_into()
+                    .unwrap(),
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    pub fn find(&self, name: &str) -> Option<&ExeFsEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+}
+
+/// Reads files from a decrypted ExeFS.
+pub struct ExeFs<'a, F: FileOps> {
+    file: &'a F,
+    offset: u64,
+    header: ExeFsHeader,
+}
+
+impl<'a, F: FileOps> ExeFs<'a, F> {
+    /// Opens an ExeFS at an offset in a file.
+    pub fn open(file: &'a F, offset: u64) -> CtrResult<Self> {
+        let header = ExeFsHeader::parse(&read_exact(file, offset, EXEFS_HEADER_SIZE)?)?;
+        Ok(Self {
+            file,
+            offset,
+            header,
+        })
+    }
+
+    /// Opens the ExeFS of an NCCH that starts at an offset in a file.
+    /// Returns an error if the NCCH is encrypted or doesn't have an ExeFS.
+    pub fn open_in_ncch(
+        file: &'a F,
+        ncch_offset: u64,
+        ncch_header: &NcchHeader,
+    ) -> CtrResult<Self> {
+        if ncch_header.is_encrypted() {
+            return Err(error::not_implemented());
+        }
+
+        if ncch_header.exefs.is_empty() {
+            return Err(error::not_found());
+        }
+
+        Self::open(file, ncch_offset + ncch_header.exefs.offset)
+    }
+
+    pub fn header(&self) -> &ExeFsHeader {
+        &self.header
+    }
+
+    /// Reads a file, such as `icon` or `.code`, and verifies its hash.
+    pub fn read_file(&self, name: &str) -> CtrResult<Vec<u8>> {
+        let entry = self
+            .header
+            .find(name)
+            .ok_or_else(fs_error::file_not_found)?;
+        self.read_entry(entry)
+    }
+
+    /// Reads the file of an entry and verifies its hash.
+    /// Returns an error if the hash doesn't match.
+    pub fn read_entry(&self, entry: &ExeFsEntry) -> CtrResult<Vec<u8>> {
+        let offset = self.offset + EXEFS_HEADER_SIZE as u64 + entry.offset as u64;
+        let data = read_exact(self.file, offset, entry.size as usize)?;
+
+        if sha256(&data) != entry.hash {
+            return Err(error::invalid_value());
+        }
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fs::{
+        ArchiveId, ArchiveOps, FsPath, MemoryArchive, MemoryFile, MemoryFs, OpenFlags,
+    };
+    use alloc::vec;
+    use core::convert::TryFrom;
+    use no_std_io::Writer;
+
+    fn create_exefs() -> Vec<u8> {
+        let files: [(&[u8], &[u8]); 2] = [(b".code", b"code"), (b"icon", b"icon data")];
+        let mut bytes = vec![0; EXEFS_HEADER_SIZE];
+        let mut offset = 0;
+
+        for (index, (name, contents)) in files.iter().enumerate() {
+            let entry_offset = index * 0x10;
+            let hash_offset = LAST_HASH_OFFSET - index * SHA256_HASH_SIZE;
+
+            bytes[entry_offset..entry_offset + name.len()].copy_from_slice(name);
+            bytes.write_le(entry_offset + 8, &offset).unwrap();
+            bytes
+                .write_le(entry_offset + 12, &(contents.len() as u32))
+                .unwrap();
+            bytes[hash_offset..hash_offset + SHA256_HASH_SIZE].copy_from_slice(&sha256(contents));
+
+            // Files are aligned to the media unit size
+            bytes.resize(EXEFS_HEADER_SIZE + offset as usize, 0);
+            bytes.extend_from_slice(contents);
+            offset += 0x200;
+        }
+
+        bytes
+    }
+
+    fn create_file(contents: &[u8]) -> (MemoryArchive, MemoryFile) {
+        let fs = MemoryFs::new();
+        fs.mount_archive(ArchiveId::Sdmc, &FsPath::new_empty_path());
+        let archive = fs
+            .open_archive(ArchiveId::Sdmc, &FsPath::new_empty_path())
+            .unwrap();
+        let mut file = archive
+            .open_file(
+                &FsPath::try_from("/exefs.bin").unwrap(),
+                OpenFlags::ReadWriteCreate,
+            )
+            .unwrap();
+        file.write(contents).unwrap();
+        (archive, file)
+    }
+
+    mod exefs_header {
+        use super::*;
+
+        #[test]
+        fn should_parse_the_file_table() {
+            let header = ExeFsHeader::parse(&create_exefs()).unwrap();
+
+            assert_eq!(header.entries.len(), 2);
+            assert_eq!(
+                header.find("icon"),
+                Some(&ExeFsEntry {
+                    name: String::from("icon"),
+                    offset: 0x200,
+                    size: 9,
+                    hash: sha256(b"icon data"),
+                })
+            );
+            assert_eq!(header.find("banner"), None);
+        }
+    }
+
+    mod read_file {
+        use super::*;
+
+        #[test]
+        fn should_read_and_verify_files() {
+            let (_archive, file) = create_file(&create_exefs());
+            let exefs = ExeFs::open(&file, 0).unwrap();
+
+            assert_eq!(exefs.read_file(".code").unwrap(), b"code");
+            assert_eq!(exefs.read_file("icon").unwrap(), b"icon data");
+        }
+
+        #[test]
+        fn should_error_if_the_hash_does_not_match() {
+            let mut bytes = create_exefs();
+            bytes[EXEFS_HEADER_SIZE] = 0;
+            let (_archive, file) = create_file(&bytes);
+            let exefs = ExeFs::open(&file, 0).unwrap();
+
+            let result = exefs.read_file(".code").unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+
+        #[test]
+        fn should_error_if_the_file_does_not_exist() {
+            let (_archive, file) = create_file(&create_exefs());
+            let exefs = ExeFs::open(&file, 0).unwrap();
+
+            let result = exefs.read_file("banner").unwrap_err();
+            assert_eq!(result, fs_error::file_not_found());
+        }
+    }
+}
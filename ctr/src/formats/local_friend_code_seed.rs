@@ -0,0 +1,181 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/Nandrw/sys/LocalFriendCodeSeed_B) for the documentation
+*/
+
+use super::read_exact;
+use crate::{
+    fs::FileOps,
+    res::{error, CtrResult},
+};
+use core::convert::TryInto;
+
+pub const LOCAL_FRIEND_CODE_SEED_SIZE: usize = 0x110;
+pub const LOCAL_FRIEND_CODE_SEED_SIGNATURE_SIZE: usize = 0x100;
+
+const RESERVED_OFFSET: usize = 0x100;
+const SEED_OFFSET: usize = 0x108;
+
+/// Only the low 40 bits of the seed are used.
+const SEED_MASK: u64 = 0xff_ffff_ffff;
+/// The byte of the seed that tells which kind of console it was generated for.
+const CONSOLE_TYPE_BYTE: usize = 4;
+
+/// The console's friend code seed, which cfg stores on the NAND as `/sys/LocalFriendCodeSeed_B`.
+/// The same data is used to log in to the friends and NASC servers.
+///
+/// The signature is RSA-2048 and signed by Nintendo, so it can't be verified offline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalFriendCodeSeed {
+    raw: [u8; LOCAL_FRIEND_CODE_SEED_SIZE],
+}
+
+impl LocalFriendCodeSeed {
+    pub fn new(raw: [u8; LOCAL_FRIEND_CODE_SEED_SIZE]) -> Self {
+        Self { raw }
+    }
+
+    /// Parses and validates the seed data.
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let raw: [u8; LOCAL_FRIEND_CODE_SEED_SIZE] =
+            bytes.try_into().map_err(|_| error::invalid_size())?;
+        let result = Self { raw };
+        result.validate()?;
+        Ok(result)
+    }
+
+    pub fn read(file: &impl FileOps) -> CtrResult<Self> {
+        Self::parse(&read_exact(file, 0, LOCAL_FRIEND_CODE_SEED_SIZE)?)
+    }
+
+    /// Checks the parts of the data that can be checked without Nintendo's public key.
+    /// The reserved bytes and unused seed bits must be zero, and the signature can't be blank.
+    pub fn validate(&self) -> CtrResult {
+        if self.raw[RESERVED_OFFSET..SEED_OFFSET]
+            .iter()
+            .any(|byte| *byte != 0)
+        {
+            return Err(error::invalid_value());
+        }
+
+        if self.get_raw_seed() & !SEED_MASK != 0 {
+            return Err(error::invalid_value());
+        }
+
+        if self.get_signature().iter().all(|byte| *byte == 0) {
+            return Err(error::invalid_value());
+        }
+
+        Ok(())
+    }
+
+    pub fn get_signature(&self) -> &[u8; LOCAL_FRIEND_CODE_SEED_SIGNATURE_SIZE] {
+        self.raw[..LOCAL_FRIEND_CODE_SEED_SIGNATURE_SIZE]
+            .try_into()
+            .unwrap()
+    }
+
+    fn get_raw_seed(&self) -> u64 {
+        u64::from_le_bytes(self.raw[SEED_OFFSET..].try_into().unwrap())
+    }
+
+    /// The seed, which is unique to the console.
+    pub fn get_seed(&self) -> u64 {
+        self.get_raw_seed() & SEED_MASK
+    }
+
+    /// The seed as it's stored, which is the format NASC and movable.sed use.
+    pub fn get_seed_bytes(&self) -> [u8; 8] {
+        self.raw[SEED_OFFSET..].try_into().unwrap()
+    }
+
+    /// The seed without the console type, which is the part that varies between consoles.
+    pub fn get_console_seed(&self) -> u32 {
+        self.get_seed() as u32
+    }
+
+    /// Seeds generated on a New 3DS have a console type of 2, and seeds generated on an Old 3DS have 0.
+    pub fn get_console_type(&self) -> u8 {
+        self.raw[SEED_OFFSET + CONSOLE_TYPE_BYTE]
+    }
+
+    pub fn is_new_3ds(&self) -> bool {
+        self.get_console_type() == 2
+    }
+
+    pub fn as_bytes(&self) -> &[u8; LOCAL_FRIEND_CODE_SEED_SIZE] {
+        &self.raw
+    }
+}
+
+impl From<[u8; LOCAL_FRIEND_CODE_SEED_SIZE]> for LocalFriendCodeSeed {
+    fn from(raw: [u8; LOCAL_FRIEND_CODE_SEED_SIZE]) -> Self {
+        Self::new(raw)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_seed_bytes() -> [u8; LOCAL_FRIEND_CODE_SEED_SIZE] {
+        let mut raw = [0xa5; LOCAL_FRIEND_CODE_SEED_SIZE];
+        raw[RESERVED_OFFSET..SEED_OFFSET].fill(0);
+        raw[SEED_OFFSET..].copy_from_slice(&[0x78, 0x56, 0x34, 0x12, 0x02, 0, 0, 0]);
+        raw
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_parse_the_seed() {
+            let result = LocalFriendCodeSeed::parse(&create_seed_bytes()).unwrap();
+
+            assert_eq!(result.get_seed(), 0x212345678);
+            assert_eq!(result.get_console_seed(), 0x12345678);
+            assert_eq!(result.get_console_type(), 2);
+            assert!(result.is_new_3ds());
+            assert_eq!(
+                result.get_seed_bytes(),
+                [0x78, 0x56, 0x34, 0x12, 0x02, 0, 0, 0]
+            );
+            assert_eq!(result.get_signature(), &[0xa5; 0x100]);
+        }
+
+        #[test]
+        fn should_return_an_error_for_the_wrong_size() {
+            let result = LocalFriendCodeSeed::parse(&create_seed_bytes()[..0x10f]);
+            assert_eq!(result, Err(error::invalid_size()));
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_reserved_bytes_are_not_zero() {
+            let mut bytes = create_seed_bytes();
+            bytes[RESERVED_OFFSET] = 1;
+
+            let result = LocalFriendCodeSeed::parse(&bytes);
+
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+
+        #[test]
+        fn should_return_an_error_if_unused_seed_bits_are_set() {
+            let mut bytes = create_seed_bytes();
+            bytes[LOCAL_FRIEND_CODE_SEED_SIZE - 1] = 1;
+
+            let result = LocalFriendCodeSeed::parse(&bytes);
+
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_signature_is_blank() {
+            let mut bytes = create_seed_bytes();
+            bytes[..LOCAL_FRIEND_CODE_SEED_SIGNATURE_SIZE].fill(0);
+
+            let result = LocalFriendCodeSeed::parse(&bytes);
+
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    fs::FileOps,
+    res::{error, CtrResult},
+};
+use alloc::{string::String, vec, vec::Vec};
+
+pub mod cia;
+pub mod config_save;
+pub mod exefs;
+pub mod local_friend_code_seed;
+pub mod ncch;
+pub mod romfs;
+pub mod smdh;
+
+/// Converts a nul padded ascii field to a string.
+fn read_padded_string(bytes: &[u8]) -> CtrResult<String> {
+    let end = bytes
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+    let result = core::str::from_utf8(&bytes[..end])?;
+    Ok(String::from(result))
+}
+
+/// Reads exactly `size` bytes at an offset in a file.
+/// Returns an error if the file ends first.
+pub(crate) fn read_exact(file: &impl FileOps, offset: u64, size: usize) -> CtrResult<Vec<u8>> {
+    let mut result = vec![0; size];
+    let mut bytes_read = 0;
+
+    while bytes_read < size {
+        let read_size = file.read_into(offset + bytes_read as u64, &mut result[bytes_read..])?;
+
+        if read_size == 0 {
+            return Err(error::invalid_size());
+        }
+
+        bytes_read += read_size;
+    }
+
+    Ok(result)
+}
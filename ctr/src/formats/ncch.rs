@@ -0,0 +1,731 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/NCCH and https://www.3dbrew.org/wiki/NCCH/Extended_Header)
+    for the documentation
+*/
+
+use super::read_padded_string;
+use crate::{
+    fs::FileOps,
+    res::{error, CtrResult},
+    TitleId,
+};
+use alloc::{string::String, vec, vec::Vec};
+use core::convert::TryInto;
+use no_std_io::{EndianRead, EndianWrite, Reader};
+use num_enum::IntoPrimitive;
+
+pub const NCCH_MAGIC: [u8; 4] = *b"NCCH";
+pub const NCCH_HEADER_SIZE: usize = 0x200;
+pub const EXHEADER_SIZE: usize = 0x800;
+
+const MEDIA_UNIT_SIZE: u64 = 0x200;
+/// The largest content unit size exponent that keeps the unit size in a u64.
+const MAX_CONTENT_UNIT_EXPONENT: u8 = 54;
+
+fn read_array<const SIZE: usize>(bytes: &[u8], offset: usize) -> CtrResult<[u8; SIZE]> {
+    bytes
+        .get(offset..offset + SIZE)
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or_else(error::invalid_size)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u8)]
+pub enum NcchContentType {
+    Data = 0x1,
+    Executable = 0x2,
+    SystemUpdate = 0x4,
+    Manual = 0x8,
+    Child = 0x10,
+    Trial = 0x20,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u8)]
+pub enum NcchFlag {
+    FixedCryptoKey = 0x1,
+    NoMountRomFs = 0x2,
+    NoCrypto = 0x4,
+    SeedCrypto = 0x20,
+}
+
+/// A region of an NCCH, in bytes from the start of the NCCH.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct NcchRegion {
+    pub offset: u64,
+    pub size: u64,
+}
+
+/// Converts a number of media units to bytes, returning an error if the result doesn't fit in a u64.
+fn units_to_bytes(units: u32, unit_size: u64) -> CtrResult<u64> {
+    (units as u64)
+        .checked_mul(unit_size)
+        .ok_or_else(error::invalid_value)
+}
+
+impl NcchRegion {
+    fn new(offset_units: u32, size_units: u32, unit_size: u64) -> CtrResult<Self> {
+        Ok(Self {
+            offset: units_to_bytes(offset_units, unit_size)?,
+            size: units_to_bytes(size_units, unit_size)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NcchHeader {
+    pub signature: [u8; 0x100],
+    /// The size of the NCCH in bytes.
+    pub content_size: u64,
+    pub partition_id: u64,
+    pub maker_code: String,
+    pub version: u16,
+    pub seed_check: u32,
+    pub program_id: TitleId,
+    pub logo_hash: [u8; 0x20],
+    pub product_code: String,
+    pub exheader_hash: [u8; 0x20],
+    pub exheader_size: u32,
+    pub flags: [u8; 8],
+    pub plain_region: NcchRegion,
+    pub logo_region: NcchRegion,
+    pub exefs: NcchRegion,
+    pub exefs_hash_region_size: u64,
+    pub romfs: NcchRegion,
+    pub romfs_hash_region_size: u64,
+    pub exefs_superblock_hash: [u8; 0x20],
+    pub romfs_superblock_hash: [u8; 0x20],
+}
+
+impl NcchHeader {
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        if bytes.len() < NCCH_HEADER_SIZE {
+            return Err(error::invalid_size());
+        }
+
+        if bytes[0x100..0x104] != NCCH_MAGIC {
+            return Err(error::invalid_value());
+        }
+
+        let flags: [u8; 8] = read_array(bytes, 0x188)?;
+
+        if flags[6] > MAX_CONTENT_UNIT_EXPONENT {
+            return Err(error::invalid_value());
+        }
+
+        let unit_size = MEDIA_UNIT_SIZE
+            .checked_shl(flags[6] as u32)
+            .ok_or_else(error::invalid_value)?;
+        let read_region = |offset: usize| -> CtrResult<NcchRegion> {
+            NcchRegion::new(
+                bytes.read_le(offset)?,
+                bytes.read_le(offset + 4)?,
+                unit_size,
+            )
+        };
+
+        Ok(Self {
+            signature: read_array(bytes, 0)?,
+            content_size: units_to_bytes(bytes.read_le(0x104)?, unit_size)?,
+            partition_id: bytes.read_le(0x108)?,
+            maker_code: read_padded_string(&bytes[0x110..0x112])?,
+            version: bytes.read_le(0x112)?,
+            seed_check: bytes.read_le(0x114)?,
+            program_id: bytes.read_le(0x118)?,
+            logo_hash: read_array(bytes, 0x130)?,
+            product_code: read_padded_string(&bytes[0x150..0x160])?,
+            exheader_hash: read_array(bytes, 0x160)?,
+            exheader_size: bytes.read_le(0x180)?,
+            flags,
+            plain_region: read_region(0x190)?,
+            logo_region: read_region(0x198)?,
+            exefs: read_region(0x1a0)?,
+            exefs_hash_region_size: units_to_bytes(bytes.read_le(0x1a8)?, unit_size)?,
+            romfs: read_region(0x1b0)?,
+            romfs_hash_region_size: units_to_bytes(bytes.read_le(0x1b8)?, unit_size)?,
+            exefs_superblock_hash: read_array(bytes, 0x1c0)?,
+            romfs_superblock_hash: read_array(bytes, 0x1e0)?,
+        })
+    }
+
+    /// The title id of the program, which is the same as the program id.
+    pub fn title_id(&self) -> TitleId {
+        self.program_id
+    }
+
+    pub fn crypto_method(&self) -> u8 {
+        self.flags[3]
+    }
+
+    pub fn content_platform(&self) -> u8 {
+        self.flags[4]
+    }
+
+    pub fn has_content_type(&self, content_type: NcchContentType) -> bool {
+        self.flags[5] & u8::from(content_type) != 0
+    }
+
+    pub fn has_flag(&self, flag: NcchFlag) -> bool {
+        self.flags[7] & u8::from(flag) != 0
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        !self.has_flag(NcchFlag::NoCrypto)
+    }
+
+    pub fn has_exheader(&self) -> bool {
+        self.exheader_size != 0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, EndianRead, EndianWrite)]
+pub struct CodeSetInfo {
+    pub address: u32,
+    /// The size of the code set in pages.
+    pub physical_region_size: u32,
+    /// The size of the code set in bytes.
+    pub size: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct SystemControlInfo {
+    pub application_title: String,
+    pub flags: u8,
+    pub remaster_version: u16,
+    pub text_code_set: CodeSetInfo,
+    pub stack_size: u32,
+    pub read_only_code_set: CodeSetInfo,
+    pub data_code_set: CodeSetInfo,
+    pub bss_size: u32,
+    /// The program ids of modules this program depends on.
+    pub dependencies: Vec<TitleId>,
+    pub save_data_size: u64,
+    pub jump_id: u64,
+}
+
+impl SystemControlInfo {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let dependencies = (0..48)
+            .map(|index| bytes.read_le::<TitleId>(0x40 + index * 8))
+            .filter(|dependency| !matches!(dependency, Ok(title_id) if title_id.raw() == 0))
+            .collect::<Result<Vec<TitleId>, no_std_io::Error>>()?;
+
+        Ok(Self {
+            application_title: read_padded_string(&bytes[0..8])?,
+            flags: bytes.read_le(0xd)?,
+            remaster_version: bytes.read_le(0xe)?,
+            text_code_set: bytes.read_le(0x10)?,
+            stack_size: bytes.read_le(0x1c)?,
+            read_only_code_set: bytes.read_le(0x20)?,
+            data_code_set: bytes.read_le(0x30)?,
+            bss_size: bytes.read_le(0x3c)?,
+            dependencies,
+            save_data_size: bytes.read_le(0x1c0)?,
+            jump_id: bytes.read_le(0x1c8)?,
+        })
+    }
+
+    pub fn is_code_compressed(&self) -> bool {
+        self.flags & 0x1 != 0
+    }
+
+    pub fn is_sd_application(&self) -> bool {
+        self.flags & 0x2 != 0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct StorageInfo {
+    pub extdata_id: u64,
+    pub system_save_data_ids: [u32; 2],
+    pub storage_accessible_unique_ids: u64,
+    /// The filesystem access flags, which are the lower 56 bits of the access info.
+    pub fs_access_info: u64,
+    pub other_attributes: u8,
+}
+
+impl StorageInfo {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let access_info: u64 = bytes.read_le(0x18)?;
+
+        Ok(Self {
+            extdata_id: bytes.read_le(0)?,
+            system_save_data_ids: [bytes.read_le(0x8)?, bytes.read_le(0xc)?],
+            storage_accessible_unique_ids: bytes.read_le(0x10)?,
+            fs_access_info: access_info & 0x00ff_ffff_ffff_ffff,
+            other_attributes: (access_info >> 56) as u8,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Arm11LocalCapabilities {
+    pub program_id: TitleId,
+    pub core_version: u32,
+    pub flag0: u8,
+    pub flag1: u8,
+    pub flag2: u8,
+    pub priority: u8,
+    pub resource_limit_descriptors: [u16; 16],
+    pub storage_info: StorageInfo,
+    /// The services the program can access, including the extended service access control.
+    pub service_access_list: Vec<String>,
+    pub resource_limit_category: u8,
+}
+
+impl Arm11LocalCapabilities {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let mut resource_limit_descriptors = [0; 16];
+
+        for (index, descriptor) in resource_limit_descriptors.iter_mut().enumerate() {
+            *descriptor = bytes.read_le(0x10 + index * 2)?;
+        }
+
+        // 32 services, followed by 2 extended services
+        let service_access_list = bytes[0x50..0x160]
+            .chunks_exact(8)
+            .map(read_padded_string)
+            .filter(|service| !matches!(service.as_deref(), Ok("")))
+            .collect::<CtrResult<Vec<String>>>()?;
+
+        Ok(Self {
+            program_id: bytes.read_le(0)?,
+            core_version: bytes.read_le(0x8)?,
+            flag1: bytes.read_le(0xc)?,
+            flag2: bytes.read_le(0xd)?,
+            flag0: bytes.read_le(0xe)?,
+            priority: bytes.read_le(0xf)?,
+            resource_limit_descriptors,
+            storage_info: StorageInfo::parse(&bytes[0x30..0x50])?,
+            service_access_list,
+            resource_limit_category: bytes.read_le(0x16f)?,
+        })
+    }
+
+    pub fn ideal_processor(&self) -> u8 {
+        self.flag0 & 0x3
+    }
+
+    pub fn affinity_mask(&self) -> u8 {
+        (self.flag0 >> 2) & 0x3
+    }
+
+    pub fn old_3ds_system_mode(&self) -> u8 {
+        self.flag0 >> 4
+    }
+
+    pub fn new_3ds_system_mode(&self) -> u8 {
+        self.flag2 & 0xf
+    }
+
+    pub fn can_access_service(&self, service_name: &str) -> bool {
+        self.service_access_list
+            .iter()
+            .any(|service| service == service_name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Arm11KernelCapabilities {
+    pub descriptors: Vec<u32>,
+}
+
+impl Arm11KernelCapabilities {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let descriptors = (0..28)
+            .map(|index| bytes.read_le::<u32>(index * 4))
+            .collect::<Result<Vec<u32>, no_std_io::Error>>()?;
+
+        Ok(Self { descriptors })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u32)]
+pub enum Arm9Permission {
+    FsMountNand = 0x1,
+    FsMountNandReadOnlyWrite = 0x2,
+    FsMountTwln = 0x4,
+    FsMountWnand = 0x8,
+    FsMountCardSpi = 0x10,
+    UseSdif3 = 0x20,
+    CreateSeed = 0x40,
+    UseCardSpi = 0x80,
+    SdApplication = 0x100,
+    FsMountSdmcWrite = 0x200,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, EndianRead, EndianWrite)]
+pub struct Arm9AccessControl {
+    pub descriptors: [u8; 15],
+    pub descriptor_version: u8,
+}
+
+impl Arm9AccessControl {
+    pub fn has_permission(&self, permission: Arm9Permission) -> bool {
+        let descriptors = u32::from_le_bytes([
+            self.descriptors[0],
+            self.descriptors[1],
+            self.descriptors[2],
+            self.descriptors[3],
+        ]);
+        descriptors & u32::from(permission) != 0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct AccessControlInfo {
+    pub arm11_local_capabilities: Arm11LocalCapabilities,
+    pub arm11_kernel_capabilities: Arm11KernelCapabilities,
+    pub arm9_access_control: Arm9AccessControl,
+}
+
+impl AccessControlInfo {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        Ok(Self {
+            arm11_local_capabilities: Arm11LocalCapabilities::parse(&bytes[..0x170])?,
+            arm11_kernel_capabilities: Arm11KernelCapabilities::parse(&bytes[0x170..0x1f0])?,
+            arm9_access_control: bytes.read_le(0x1f0)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExHeader {
+    pub system_control_info: SystemControlInfo,
+    pub access_control_info: AccessControlInfo,
+    pub access_descriptor_signature: [u8; 0x100],
+    pub ncch_public_key: [u8; 0x100],
+    /// The access control limits, which are signed.
+    pub access_descriptor: AccessControlInfo,
+}
+
+impl ExHeader {
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        if bytes.len() < EXHEADER_SIZE {
+            return Err(error::invalid_size());
+        }
+
+        Ok(Self {
+            system_control_info: SystemControlInfo::parse(&bytes[..0x200])?,
+            access_control_info: AccessControlInfo::parse(&bytes[0x200..0x400])?,
+            access_descriptor_signature: read_array(bytes, 0x400)?,
+            ncch_public_key: read_array(bytes, 0x500)?,
+            access_descriptor: AccessControlInfo::parse(&bytes[0x600..0x800])?,
+        })
+    }
+}
+
+/// A decrypted NCCH.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ncch {
+    pub header: NcchHeader,
+    pub exheader: Option<ExHeader>,
+}
+
+impl Ncch {
+    /// Parses the header and exheader of an NCCH.
+    /// Returns an error if the NCCH is encrypted.
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let header = NcchHeader::parse(bytes)?;
+
+        if header.is_encrypted() {
+            return Err(error::not_implemented());
+        }
+
+        let exheader = match header.has_exheader() {
+            true => Some(ExHeader::parse(
+                bytes
+                    .get(NCCH_HEADER_SIZE..)
+                    .ok_or_else(error::invalid_size)?,
+            )?),
+            false => None,
+        };
+
+        Ok(Self { header, exheader })
+    }
+
+    /// Reads the header and exheader of an NCCH from the start of a file.
+    /// Returns an error if the NCCH is encrypted.
+    pub fn read(file: &impl FileOps) -> CtrResult<Self> {
+        Self::read_at(file, 0)
+    }
+
+    /// Reads the header and exheader of an NCCH at an offset in a file, such as a partition in a CIA.
+    pub fn read_at(file: &impl FileOps, offset: u64) -> CtrResult<Self> {
+        let mut bytes = vec![0; NCCH_HEADER_SIZE + EXHEADER_SIZE];
+        let bytes_read = file.read_into(offset, &mut bytes)?;
+        bytes.truncate(bytes_read);
+
+        // NCCHs without an exheader can be smaller than the buffer
+        Self::parse(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use no_std_io::Writer;
+
+    fn create_ncch() -> Vec<u8> {
+        let mut bytes = vec![0; NCCH_HEADER_SIZE + EXHEADER_SIZE];
+
+        // Header
+        bytes[0x100..0x104].copy_from_slice(&NCCH_MAGIC);
+        bytes.write_le(0x104, &0x100u32).unwrap();
+        bytes.write_le(0x108, &0x0004000000123400u64).unwrap();
+        bytes[0x110..0x112].copy_from_slice(b"01");
+        bytes.write_le(0x112, &2u16).unwrap();
+        bytes.write_le(0x118, &0x0004000000123400u64).unwrap();
+        bytes[0x150..0x15a].copy_from_slice(b"CTR-P-TEST");
+        bytes.write_le(0x180, &0x400u32).unwrap();
+        bytes[0x18d] = u8::from(NcchContentType::Data) | u8::from(NcchContentType::Executable);
+        bytes[0x18f] = u8::from(NcchFlag::NoCrypto);
+        bytes.write_le(0x1a0, &0x10u32).unwrap();
+        bytes.write_le(0x1a4, &0x20u32).unwrap();
+        bytes.write_le(0x1a8, &0x1u32).unwrap();
+        bytes.write_le(0x1b0, &0x30u32).unwrap();
+        bytes.write_le(0x1b4, &0x40u32).unwrap();
+
+        // System control info
+        let exheader = NCCH_HEADER_SIZE;
+        bytes[exheader..exheader + 4].copy_from_slice(b"test");
+        bytes[exheader + 0xd] = 0x3;
+        bytes.write_le(exheader + 0xe, &1u16).unwrap();
+        let text_code_set = CodeSetInfo {
+            address: 0x100000,
+            physical_region_size: 2,
+            size: 0x1800,
+        };
+        bytes.write_le(exheader + 0x10, &text_code_set).unwrap();
+        bytes.write_le(exheader + 0x1c, &0x4000u32).unwrap();
+        bytes.write_le(exheader + 0x3c, &0x200u32).unwrap();
+        bytes
+            .write_le(exheader + 0x40, &0x0004013000001002u64)
+            .unwrap();
+        bytes
+            .write_le(exheader + 0x48, &0x0004013000003202u64)
+            .unwrap();
+        bytes.write_le(exheader + 0x1c0, &0x80000u64).unwrap();
+
+        // Access control info
+        let aci = exheader + 0x200;
+        bytes.write_le(aci, &0x0004000000123400u64).unwrap();
+        bytes.write_le(aci + 0x8, &2u32).unwrap();
+        bytes[aci + 0xe] = 0x1 | (0x2 << 2) | (0x3 << 4);
+        bytes[aci + 0xf] = 0x30;
+        bytes.write_le(aci + 0x10, &0x9eu16).unwrap();
+        bytes.write_le(aci + 0x30, &0x1234u64).unwrap();
+        bytes.write_le(aci + 0x38, &0x10032u32).unwrap();
+        bytes
+            .write_le(aci + 0x48, &0x0100_0000_0000_0003u64)
+            .unwrap();
+        bytes[aci + 0x50..aci + 0x54].copy_from_slice(b"fs:U");
+        bytes[aci + 0x58..aci + 0x5f].copy_from_slice(b"frd:u\0\0");
+        bytes[aci + 0x150..aci + 0x156].copy_from_slice(b"http:C");
+        bytes[aci + 0x16f] = 1;
+        bytes.write_le(aci + 0x170, &0xfc00022cu32).unwrap();
+        bytes.write_le(aci + 0x1f0, &0x280u32).unwrap();
+        bytes[aci + 0x1ff] = 2;
+
+        bytes
+    }
+
+    mod ncch_header {
+        use super::*;
+
+        #[test]
+        fn should_parse_the_header() {
+            let header = NcchHeader::parse(&create_ncch()).unwrap();
+
+            assert_eq!(header.content_size, 0x20000);
+            assert_eq!(header.title_id(), TitleId::new(0x0004000000123400));
+            assert_eq!(header.maker_code, "01");
+            assert_eq!(header.version, 2);
+            assert_eq!(header.product_code, "CTR-P-TEST");
+            assert_eq!(header.exheader_size, 0x400);
+            assert!(header.has_content_type(NcchContentType::Executable));
+            assert!(!header.has_content_type(NcchContentType::Manual));
+            assert!(!header.is_encrypted());
+            assert_eq!(
+                header.exefs,
+                NcchRegion {
+                    offset: 0x2000,
+                    size: 0x4000
+                }
+            );
+            assert_eq!(header.exefs_hash_region_size, 0x200);
+            assert_eq!(
+                header.romfs,
+                NcchRegion {
+                    offset: 0x6000,
+                    size: 0x8000
+                }
+            );
+            assert!(header.logo_region.is_empty());
+        }
+
+        #[test]
+        fn should_use_the_content_unit_size() {
+            let mut bytes = create_ncch();
+            bytes[0x18e] = 1;
+
+            let header = NcchHeader::parse(&bytes).unwrap();
+            assert_eq!(header.exefs.offset, 0x4000);
+        }
+
+        #[test]
+        fn should_error_if_the_content_unit_size_is_too_large() {
+            let mut bytes = create_ncch();
+            bytes[0x18e] = 64;
+
+            let result = NcchHeader::parse(&bytes).unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+
+        #[test]
+        fn should_error_if_a_region_does_not_fit_in_a_u64() {
+            let mut bytes = create_ncch();
+            bytes[0x18e] = MAX_CONTENT_UNIT_EXPONENT;
+
+            let result = NcchHeader::parse(&bytes).unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+
+        #[test]
+        fn should_error_if_the_magic_is_invalid() {
+            let mut bytes = create_ncch();
+            bytes[0x100] = 0;
+
+            let result = NcchHeader::parse(&bytes).unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+    }
+
+    mod exheader {
+        use super::*;
+
+        #[test]
+        fn should_parse_the_system_control_info() {
+            let ncch = Ncch::parse(&create_ncch()).unwrap();
+            let info = ncch.exheader.unwrap().system_control_info;
+
+            assert_eq!(info.application_title, "test");
+            assert!(info.is_code_compressed());
+            assert!(info.is_sd_application());
+            assert_eq!(info.remaster_version, 1);
+            assert_eq!(info.text_code_set.address, 0x100000);
+            assert_eq!(info.text_code_set.size, 0x1800);
+            assert_eq!(info.stack_size, 0x4000);
+            assert_eq!(info.bss_size, 0x200);
+            assert_eq!(
+                info.dependencies,
+                vec![
+                    TitleId::new(0x0004013000001002),
+                    TitleId::new(0x0004013000003202)
+                ]
+            );
+            assert_eq!(info.save_data_size, 0x80000);
+        }
+
+        #[test]
+        fn should_parse_the_arm11_local_capabilities() {
+            let ncch = Ncch::parse(&create_ncch()).unwrap();
+            let capabilities = ncch
+                .exheader
+                .unwrap()
+                .access_control_info
+                .arm11_local_capabilities;
+
+            assert_eq!(capabilities.program_id, TitleId::new(0x0004000000123400));
+            assert_eq!(capabilities.core_version, 2);
+            assert_eq!(capabilities.ideal_processor(), 1);
+            assert_eq!(capabilities.affinity_mask(), 2);
+            assert_eq!(capabilities.old_3ds_system_mode(), 3);
+            assert_eq!(capabilities.priority, 0x30);
+            assert_eq!(capabilities.resource_limit_descriptors[0], 0x9e);
+            assert_eq!(capabilities.storage_info.extdata_id, 0x1234);
+            assert_eq!(capabilities.storage_info.system_save_data_ids, [0x10032, 0]);
+            assert_eq!(capabilities.storage_info.fs_access_info, 0x3);
+            assert_eq!(capabilities.storage_info.other_attributes, 0x1);
+            assert_eq!(
+                capabilities.service_access_list,
+                vec!["fs:U", "frd:u", "http:C"]
+            );
+            assert!(capabilities.can_access_service("frd:u"));
+            assert!(!capabilities.can_access_service("frd:a"));
+            assert_eq!(capabilities.resource_limit_category, 1);
+        }
+
+        #[test]
+        fn should_parse_the_kernel_capabilities_and_arm9_access_control() {
+            let ncch = Ncch::parse(&create_ncch()).unwrap();
+            let access_control_info = ncch.exheader.unwrap().access_control_info;
+            let arm9_access_control = access_control_info.arm9_access_control;
+
+            assert_eq!(
+                access_control_info.arm11_kernel_capabilities.descriptors[0],
+                0xfc00022c
+            );
+            assert_eq!(
+                access_control_info
+                    .arm11_kernel_capabilities
+                    .descriptors
+                    .len(),
+                28
+            );
+            assert!(arm9_access_control.has_permission(Arm9Permission::UseCardSpi));
+            assert!(arm9_access_control.has_permission(Arm9Permission::FsMountSdmcWrite));
+            assert!(!arm9_access_control.has_permission(Arm9Permission::FsMountNand));
+            assert_eq!(arm9_access_control.descriptor_version, 2);
+        }
+
+        #[test]
+        fn should_error_if_the_ncch_is_encrypted() {
+            let mut bytes = create_ncch();
+            bytes[0x18f] = 0;
+
+            let result = Ncch::parse(&bytes).unwrap_err();
+            assert_eq!(result, error::not_implemented());
+        }
+
+        #[test]
+        fn should_skip_a_missing_exheader() {
+            let mut bytes = create_ncch();
+            bytes.write_le(0x180, &0u32).unwrap();
+
+            let ncch = Ncch::parse(&bytes[..NCCH_HEADER_SIZE]).unwrap();
+            assert_eq!(ncch.exheader, None);
+        }
+    }
+
+    mod read {
+        use super::*;
+        use crate::fs::{ArchiveId, ArchiveOps, FsPath, MemoryFs, OpenFlags};
+        use core::convert::TryFrom;
+
+        #[test]
+        fn should_read_an_ncch_from_a_file() {
+            let fs = MemoryFs::new();
+            fs.mount_archive(ArchiveId::Sdmc, &FsPath::new_empty_path());
+            let archive = fs
+                .open_archive(ArchiveId::Sdmc, &FsPath::new_empty_path())
+                .unwrap();
+            let mut file = archive
+                .open_file(
+                    &FsPath::try_from("/test.cxi").unwrap(),
+                    OpenFlags::ReadWriteCreate,
+                )
+                .unwrap();
+            file.write(&[0; 0x10]).unwrap();
+            file.write(&create_ncch()).unwrap();
+
+            let result = Ncch::read_at(&file, 0x10).unwrap();
+            assert_eq!(result, Ncch::parse(&create_ncch()).unwrap());
+        }
+    }
+}
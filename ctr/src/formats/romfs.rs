@@ -0,0 +1,758 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/RomFS) for the documentation
+*/
+
+use super::{ncch::NcchHeader, read_exact};
+use crate::{
+    fs::{error as fs_error, FileOps},
+    res::{error, CtrResult},
+};
+use alloc::{string::String, vec::Vec};
+use core::convert::TryInto;
+use no_std_io::{EndianRead, EndianWrite, Reader};
+
+pub const IVFC_MAGIC: [u8; 4] = *b"IVFC";
+
+const IVFC_HEADER_SIZE: usize = 0x5c;
+// The master hash follows the IVFC header, aligned to 0x10 bytes
+const MASTER_HASH_OFFSET: u64 = 0x60;
+const LEVEL3_HEADER_SIZE: usize = 0x28;
+const DIRECTORY_METADATA_SIZE: usize = 0x18;
+const FILE_METADATA_SIZE: usize = 0x20;
+const EMPTY_ENTRY: u32 = 0xffffffff;
+
+/// The header of the level 3 IVFC partition, which contains the RomFS files.
+/// Offsets are relative to the start of the level 3 partition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, EndianRead, EndianWrite)]
+pub struct RomFsLevel3Header {
+    pub header_size: u32,
+    pub directory_hash_table_offset: u32,
+    pub directory_hash_table_size: u32,
+    pub directory_metadata_offset: u32,
+    pub directory_metadata_size: u32,
+    pub file_hash_table_offset: u32,
+    pub file_hash_table_size: u32,
+    pub file_metadata_offset: u32,
+    pub file_metadata_size: u32,
+    pub file_data_offset: u32,
+}
+
+/// The hash used to find an entry's bucket in a hash table.
+fn hash_name(parent_offset: u32, name: &[u16]) -> u32 {
+    name.iter()
+        .fold(parent_offset ^ 123456789, |hash, character| {
+            hash.rotate_right(5) ^ *character as u32
+        })
+}
+
+fn read_name(metadata: &[u8], offset: usize, size: usize) -> CtrResult<Vec<u16>> {
+    let name = metadata
+        .get(offset..offset + size)
+        .ok_or_else(error::invalid_size)?
+        .chunks_exact(2)
+        .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]))
+        .collect();
+    Ok(name)
+}
+
+struct DirectoryMetadata {
+    parent: u32,
+    next_sibling: u32,
+    first_child: u32,
+    first_file: u32,
+    next_in_bucket: u32,
+    name: Vec<u16>,
+}
+
+impl DirectoryMetadata {
+    fn parse(metadata: &[u8], offset: u32) -> CtrResult<Self> {
+        let offset = offset as usize;
+        let name_size: u32 = metadata.read_le(offset + 0x14)?;
+
+        Ok(Self {
+            parent: metadata.read_le(offset)?,
+            next_sibling: metadata.read_le(offset + 0x4)?,
+            first_child: metadata.read_le(offset + 0x8)?,
+            first_file: metadata.read_le(offset + 0xc)?,
+            next_in_bucket: metadata.read_le(offset + 0x10)?,
+            name: read_name(
+                metadata,
+                offset + DIRECTORY_METADATA_SIZE,
+                name_size as usize,
+            )?,
+        })
+    }
+}
+
+struct FileMetadata {
+    parent: u32,
+    next_sibling: u32,
+    data_offset: u64,
+    data_size: u64,
+    next_in_bucket: u32,
+    name: Vec<u16>,
+}
+
+impl FileMetadata {
+    fn parse(metadata: &[u8], offset: u32) -> CtrResult<Self> {
+        let offset = offset as usize;
+        let name_size: u32 = metadata.read_le(offset + 0x1c)?;
+
+        Ok(Self {
+            parent: metadata.read_le(offset)?,
+            next_sibling: metadata.read_le(offset + 0x4)?,
+            data_offset: metadata.read_le(offset + 0x8)?,
+            data_size: metadata.read_le(offset + 0x10)?,
+            next_in_bucket: metadata.read_le(offset + 0x18)?,
+            name: read_name(metadata, offset + FILE_METADATA_SIZE, name_size as usize)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RomFsDirEntry {
+    name: String,
+    file_size: u64,
+    is_directory: bool,
+}
+
+impl RomFsDirEntry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The size of the file, or 0 for directories.
+    pub fn size(&self) -> u64 {
+        self.file_size
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.is_directory
+    }
+}
+
+/// The location of a file's data in the file containing the RomFS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RomFsFile {
+    pub offset: u64,
+    pub size: u64,
+}
+
+/// Reads files from a decrypted RomFS.
+pub struct RomFs<'a, F: FileOps> {
+    file: &'a F,
+    level3_offset: u64,
+    header: RomFsLevel3Header,
+    directory_hash_table: Vec<u8>,
+    directory_metadata: Vec<u8>,
+    file_hash_table: Vec<u8>,
+    file_metadata: Vec<u8>,
+}
+
+impl<'a, F: FileOps> RomFs<'a, F> {
+    /// Opens a RomFS that starts with an IVFC header at an offset in a file.
+    pub fn open(file: &'a F, offset: u64) -> CtrResult<Self> {
+        let ivfc_header = read_exact(file, offset, IVFC_HEADER_SIZE)?;
+
+        if ivfc_header[0..4] != IVFC_MAGIC {
+            return Err(error::invalid_value());
+        }
+
+        let master_hash_size: u32 = ivfc_header.read_le(0x8)?;
+        let level3_block_size_log2: u32 = ivfc_header.read_le(0x4c)?;
+
+        if level3_block_size_log2 >= 32 {
+            return Err(error::invalid_value());
+        }
+
+        // Level 3 comes first, aligned to its block size
+        let block_size = 1u64 << level3_block_size_log2;
+        let level3_offset =
+            (MASTER_HASH_OFFSET + master_hash_size as u64 + block_size - 1) & !(block_size - 1);
+
+        Self::open_level3(file, offset + level3_offset)
+    }
+
+    /// Opens a level 3 partition without an IVFC header at an offset in a file,
+    /// such as a RomFS extracted by other tools.
+    pub fn open_level3(file: &'a F, offset: u64) -> CtrResult<Self> {
+        let header: RomFsLevel3Header = read_exact(file, offset, LEVEL3_HEADER_SIZE)?.read_le(0)?;
+        let read_table = |table_offset: u32, size: u32| {
+            read_exact(file, offset + table_offset as u64, size as usize)
+        };
+
+        Ok(Self {
+            file,
+            level3_offset: offset,
+            header,
+            directory_hash_table: read_table(
+                header.directory_hash_table_offset,
+                header.directory_hash_table_size,
+            )?,
+            directory_metadata: read_table(
+                header.directory_metadata_offset,
+                header.directory_metadata_size,
+            )?,
+            file_hash_table: read_table(
+                header.file_hash_table_offset,
+                header.file_hash_table_size,
+            )?,
+            file_metadata: read_table(header.file_metadata_offset, header.file_metadata_size)?,
+        })
+    }
+
+    /// Opens the RomFS of an NCCH that starts at an offset in a file.
+    /// Returns an error if the NCCH is encrypted or doesn't have a RomFS.
+    pub fn open_in_ncch(
+        file: &'a F,
+        ncch_offset: u64,
+        ncch_header: &NcchHeader,
+    ) -> CtrResult<Self> {
+        if ncch_header.is_encrypted() {
+            return Err(error::not_implemented());
+        }
+
+        if ncch_header.romfs.is_empty() {
+            return Err(error::not_found());
+        }
+
+        Self::open(file, ncch_offset + ncch_header.romfs.offset)
+    }
+
+    pub fn header(&self) -> &RomFsLevel3Header {
+        &self.header
+    }
+
+    /// Finds the location of a file, such as `/data/file.bin`.
+    pub fn open_file(&self, path: &str) -> CtrResult<RomFsFile> {
+        let (directory_path, file_name) = match path.rfind('/') {
+            Some(index) => (&path[..index], &path[index + 1..]),
+            None => ("", path),
+        };
+
+        let parent = self.find_directory(directory_path)?;
+        let file_name = file_name.encode_utf16().collect::<Vec<u16>>();
+        let file = self
+            .find_child_file(parent, &file_name)?
+            .ok_or_else(fs_error::file_not_found)?;
+
+        Ok(RomFsFile {
+            offset: self.level3_offset + self.header.file_data_offset as u64 + file.data_offset,
+            size: file.data_size,
+        })
+    }
+
+    pub fn read_file(&self, path: &str) -> CtrResult<Vec<u8>> {
+        let file = self.open_file(path)?;
+        read_exact(self.file, file.offset, file.size.try_into()?)
+    }
+
+    /// Returns an iterator over the entries of a directory, such as `/` or `/data`.
+    /// Subdirectories are listed before files.
+    pub fn read_dir(&self, path: &str) -> CtrResult<RomFsReadDir<'_, 'a, F>> {
+        let directory_offset = self.find_directory(path)?;
+        let directory = DirectoryMetadata::parse(&self.directory_metadata, directory_offset)?;
+
+        Ok(RomFsReadDir {
+            romfs: self,
+            next_directory: directory.first_child,
+            next_file: directory.first_file,
+            remaining_directories: max_entry_count(
+                &self.directory_metadata,
+                DIRECTORY_METADATA_SIZE,
+            ),
+            remaining_files: max_entry_count(&self.file_metadata, FILE_METADATA_SIZE),
+        })
+    }
+
+    fn find_directory(&self, path: &str) -> CtrResult<u32> {
+        // The root directory is always the first directory
+        let mut directory_offset = 0;
+
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            let name = name.encode_utf16().collect::<Vec<u16>>();
+            directory_offset = self
+                .find_child_directory(directory_offset, &name)?
+                .ok_or_else(fs_error::path_not_found)?;
+        }
+
+        Ok(directory_offset)
+    }
+
+    fn find_child_directory(&self, parent: u32, name: &[u16]) -> CtrResult<Option<u32>> {
+        let mut offset = match get_bucket(&self.directory_hash_table, parent, name)? {
+            Some(offset) => offset,
+            None => return Ok(None),
+        };
+
+        for _ in 0..max_entry_count(&self.directory_metadata, DIRECTORY_METADATA_SIZE) {
+            if offset == EMPTY_ENTRY {
+                return Ok(None);
+            }
+
+            let directory = DirectoryMetadata::parse(&self.directory_metadata, offset)?;
+
+            if directory.parent == parent && directory.name == name {
+                return Ok(Some(offset));
+            }
+
+            offset = directory.next_in_bucket;
+        }
+
+        // A chain longer than the table must loop back on itself
+        Err(error::invalid_value())
+    }
+
+    fn find_child_file(&self, parent: u32, name: &[u16]) -> CtrResult<Option<FileMetadata>> {
+        let mut offset = match get_bucket(&self.file_hash_table, parent, name)? {
+            Some(offset) => offset,
+            None => return Ok(None),
+        };
+
+        for _ in 0..max_entry_count(&self.file_metadata, FILE_METADATA_SIZE) {
+            if offset == EMPTY_ENTRY {
+                return Ok(None);
+            }
+
+            let file = FileMetadata::parse(&self.file_metadata, offset)?;
+
+            if file.parent == parent && file.name == name {
+                return Ok(Some(file));
+            }
+
+            offset = file.next_in_bucket;
+        }
+
+        // A chain longer than the table must loop back on itself
+        Err(error::invalid_value())
+    }
+}
+
+/// The most entries a metadata table can hold, which bounds how long a valid chain of entries can be.
+/// The extra step allows for reaching the empty entry at the end of a chain.
+fn max_entry_count(metadata: &[u8], entry_size: usize) -> usize {
+    metadata.len() / entry_size + 1
+}
+
+/// Returns the first entry in the hash bucket for a name.
+fn get_bucket(hash_table: &[u8], parent: u32, name: &[u16]) -> CtrResult<Option<u32>> {
+    let bucket_count = (hash_table.len() / 4) as u32;
+
+    if bucket_count == 0 {
+        return Ok(None);
+    }
+
+    let bucket = hash_name(parent, name) % bucket_count;
+    Ok(Some(hash_table.read_le(bucket as usize * 4)?))
+}
+
+pub struct RomFsReadDir<'r, 'a, F: FileOps> {
+    romfs: &'r RomFs<'a, F>,
+    next_directory: u32,
+    next_file: u32,
+    /// Limits how many siblings are read, so a sibling chain that loops back on itself ends.
+    remaining_directories: usize,
+    remaining_files: usize,
+}
+
+impl<'r, 'a, F: FileOps> RomFsReadDir<'r, 'a, F> {
+    fn read_next(&mut self) -> CtrResult<Option<RomFsDirEntry>> {
+        if self.next_directory != EMPTY_ENTRY {
+            self.remaining_directories = self
+                .remaining_directories
+                .checked_sub(1)
+                .ok_or_else(error::invalid_value)?;
+            let directory =
+                DirectoryMetadata::parse(&self.romfs.directory_metadata, self.next_directory)?;
+            self.next_directory = directory.next_sibling;
+
+            return Ok(Some(RomFsDirEntry {
+                name: String::from_utf16(&directory.name)?,
+                file_size: 0,
+                is_directory: true,
+            }));
+        }
+
+        if self.next_file != EMPTY_ENTRY {
+            self.remaining_files = self
+                .remaining_files
+                .checked_sub(1)
+                .ok_or_else(error::invalid_value)?;
+            let file = FileMetadata::parse(&self.romfs.file_metadata, self.next_file)?;
+            self.next_file = file.next_sibling;
+
+            return Ok(Some(RomFsDirEntry {
+                name: String::from_utf16(&file.name)?,
+                file_size: file.data_size,
+                is_directory: false,
+            }));
+        }
+
+        Ok(None)
+    }
+}
+
+impl<'r, 'a, F: FileOps> Iterator for RomFsReadDir<'r, 'a, F> {
+    type Item = RomFsDirEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.read_next().ok().flatten();
+
+        // Stop iterating if the metadata is invalid
+        if result.is_none() {
+            self.next_directory = EMPTY_ENTRY;
+            self.next_file = EMPTY_ENTRY;
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fs::{
+        ArchiveId, ArchiveOps, FsPath, MemoryArchive, MemoryFile, MemoryFs, OpenFlags,
+    };
+    use alloc::{vec, vec::Vec};
+    use core::convert::TryFrom;
+    use no_std_io::Writer;
+
+    const BUCKET_COUNT: usize = 3;
+
+    fn align(value: usize, alignment: usize) -> usize {
+        (value + alignment - 1) & !(alignment - 1)
+    }
+
+    fn encode_name(name: &str) -> Vec<u16> {
+        name.encode_utf16().collect()
+    }
+
+    /// Builds a level 3 partition.
+    /// Directories are (name, parent index) with the root first, and files are (name, parent index, contents).
+    fn create_level3(directories: &[(&str, usize)], files: &[(&str, usize, &[u8])]) -> Vec<u8> {
+        let directory_offsets = directories
+            .iter()
+            .scan(0, |offset, (name, _)| {
+                let result = *offset;
+                *offset += DIRECTORY_METADATA_SIZE + align(name.len() * 2, 4);
+                Some(result)
+            })
+            .collect::<Vec<usize>>();
+        let file_offsets = files
+            .iter()
+            .scan(0, |offset, (name, _, _)| {
+                let result = *offset;
+                *offset += FILE_METADATA_SIZE + align(name.len() * 2, 4);
+                Some(result)
+            })
+            .collect::<Vec<usize>>();
+        let data_offsets = files
+            .iter()
+            .scan(0, |offset, (_, _, contents)| {
+                let result = *offset;
+                *offset += align(contents.len(), 0x10);
+                Some(result)
+            })
+            .collect::<Vec<usize>>();
+
+        let hash_table_size = BUCKET_COUNT * 4;
+        let directory_hash_table_offset = LEVEL3_HEADER_SIZE;
+        let directory_metadata_offset = directory_hash_table_offset + hash_table_size;
+        let directory_metadata_size = directories
+            .iter()
+            .map(|(name, _)| DIRECTORY_METADATA_SIZE + align(name.len() * 2, 4))
+            .sum::<usize>();
+        let file_hash_table_offset = directory_metadata_offset + directory_metadata_size;
+        let file_metadata_offset = file_hash_table_offset + hash_table_size;
+        let file_metadata_size = files
+            .iter()
+            .map(|(name, _, _)| FILE_METADATA_SIZE + align(name.len() * 2, 4))
+            .sum::<usize>();
+        let file_data_offset = align(file_metadata_offset + file_metadata_size, 0x10);
+        let file_data_size = files
+            .iter()
+            .map(|(_, _, contents)| align(contents.len(), 0x10))
+            .sum::<usize>();
+
+        let mut bytes = vec![0; file_data_offset + file_data_size];
+        let header = RomFsLevel3Header {
+            header_size: LEVEL3_HEADER_SIZE as u32,
+            directory_hash_table_offset: directory_hash_table_offset as u32,
+            directory_hash_table_size: hash_table_size as u32,
+            directory_metadata_offset: directory_metadata_offset as u32,
+            directory_metadata_size: directory_metadata_size as u32,
+            file_hash_table_offset: file_hash_table_offset as u32,
+            file_hash_table_size: hash_table_size as u32,
+            file_metadata_offset: file_metadata_offset as u32,
+            file_metadata_size: file_metadata_size as u32,
+            file_data_offset: file_data_offset as u32,
+        };
+        bytes.write_le(0, &header).unwrap();
+
+        let mut directory_buckets = [EMPTY_ENTRY; BUCKET_COUNT];
+        for (index, (name, parent)) in directories.iter().enumerate() {
+            let parent_offset = directory_offsets[*parent] as u32;
+            let next_sibling = (index + 1..directories.len())
+                .find(|sibling| directories[*sibling].1 == *parent)
+                .filter(|_| index != 0)
+                .map_or(EMPTY_ENTRY, |sibling| directory_offsets[sibling] as u32);
+            let first_child = (1..directories.len())
+                .find(|child| directories[*child].1 == index)
+                .map_or(EMPTY_ENTRY, |child| directory_offsets[child] as u32);
+            let first_file = (0..files.len())
+                .find(|file| files[*file].1 == index)
+                .map_or(EMPTY_ENTRY, |file| file_offsets[file] as u32);
+            let name = encode_name(name);
+            let bucket = hash_name(parent_offset, &name) as usize % BUCKET_COUNT;
+            let offset = directory_metadata_offset + directory_offsets[index];
+
+            bytes.write_le(offset, &parent_offset).unwrap();
+            bytes.write_le(offset + 0x4, &next_sibling).unwrap();
+            bytes.write_le(offset + 0x8, &first_child).unwrap();
+            bytes.write_le(offset + 0xc, &first_file).unwrap();
+            bytes
+                .write_le(offset + 0x10, &directory_buckets[bucket])
+                .unwrap();
+            bytes
+                .write_le(offset + 0x14, &(name.len() as u32 * 2))
+                .unwrap();
+            for (char_index, character) in name.iter().enumerate() {
+                bytes
+                    .write_le(offset + DIRECTORY_METADATA_SIZE + char_index * 2, character)
+                    .unwrap();
+            }
+
+            directory_buckets[bucket] = directory_offsets[index] as u32;
+        }
+
+        let mut file_buckets = [EMPTY_ENTRY; BUCKET_COUNT];
+        for (index, (name, parent, contents)) in files.iter().enumerate() {
+            let parent_offset = directory_offsets[*parent] as u32;
+            let next_sibling = (index + 1..files.len())
+                .find(|sibling| files[*sibling].1 == *parent)
+                .map_or(EMPTY_ENTRY, |sibling| file_offsets[sibling] as u32);
+            let name = encode_name(name);
+            let bucket = hash_name(parent_offset, &name) as usize % BUCKET_COUNT;
+            let offset = file_metadata_offset + file_offsets[index];
+
+            bytes.write_le(offset, &parent_offset).unwrap();
+            bytes.write_le(offset + 0x4, &next_sibling).unwrap();
+            bytes
+                .write_le(offset + 0x8, &(data_offsets[index] as u64))
+                .unwrap();
+            bytes
+                .write_le(offset + 0x10, &(contents.len() as u64))
+                .unwrap();
+            bytes
+                .write_le(offset + 0x18, &file_buckets[bucket])
+                .unwrap();
+            bytes
+                .write_le(offset + 0x1c, &(name.len() as u32 * 2))
+                .unwrap();
+            for (char_index, character) in name.iter().enumerate() {
+                bytes
+                    .write_le(offset + FILE_METADATA_SIZE + char_index * 2, character)
+                    .unwrap();
+            }
+
+            let data_offset = file_data_offset + data_offsets[index];
+            bytes[data_offset..data_offset + contents.len()].copy_from_slice(contents);
+            file_buckets[bucket] = file_offsets[index] as u32;
+        }
+
+        for (bucket, offset) in directory_buckets.iter().enumerate() {
+            bytes
+                .write_le(directory_hash_table_offset + bucket * 4, offset)
+                .unwrap();
+        }
+
+        for (bucket, offset) in file_buckets.iter().enumerate() {
+            bytes
+                .write_le(file_hash_table_offset + bucket * 4, offset)
+                .unwrap();
+        }
+
+        bytes
+    }
+
+    fn create_romfs() -> Vec<u8> {
+        let level3 = create_level3(
+            &[("", 0), ("data", 0), ("empty", 0), ("sub", 1)],
+            &[
+                ("icon.bin", 0, b"icon"),
+                ("a.txt", 1, b"file a"),
+                ("b.txt", 1, b"file b"),
+                ("c.txt", 3, b"file c"),
+            ],
+        );
+
+        // Level 3 is aligned to its 0x1000 byte block size
+        let mut bytes = vec![0; 0x1000];
+        bytes[0..4].copy_from_slice(&IVFC_MAGIC);
+        bytes.write_le(0x4, &0x10000u32).unwrap();
+        bytes.write_le(0x8, &0x20u32).unwrap();
+        bytes.write_le(0x4c, &12u32).unwrap();
+        bytes.extend_from_slice(&level3);
+        bytes
+    }
+
+    fn create_file(contents: &[u8]) -> (MemoryArchive, MemoryFile) {
+        let fs = MemoryFs::new();
+        fs.mount_archive(ArchiveId::Sdmc, &FsPath::new_empty_path());
+        let archive = fs
+            .open_archive(ArchiveId::Sdmc, &FsPath::new_empty_path())
+            .unwrap();
+        let mut file = archive
+            .open_file(
+                &FsPath::try_from("/romfs.bin").unwrap(),
+                OpenFlags::ReadWriteCreate,
+            )
+            .unwrap();
+        file.write(contents).unwrap();
+        (archive, file)
+    }
+
+    mod open {
+        use super::*;
+
+        #[test]
+        fn should_error_if_the_magic_is_invalid() {
+            let mut bytes = create_romfs();
+            bytes[0] = 0;
+            let (_archive, file) = create_file(&bytes);
+
+            let result = RomFs::open(&file, 0).err().unwrap();
+            assert_eq!(result, error::invalid_value());
+        }
+
+        #[test]
+        fn should_open_a_level3_partition() {
+            let (_archive, file) = create_file(&create_romfs());
+            let romfs = RomFs::open_level3(&file, 0x1000).unwrap();
+
+            assert_eq!(romfs.read_file("/icon.bin").unwrap(), b"icon");
+        }
+    }
+
+    mod read_file {
+        use super::*;
+
+        #[test]
+        fn should_read_files() {
+            let (_archive, file) = create_file(&create_romfs());
+            let romfs = RomFs::open(&file, 0).unwrap();
+
+            assert_eq!(romfs.read_file("/icon.bin").unwrap(), b"icon");
+            assert_eq!(romfs.read_file("/data/a.txt").unwrap(), b"file a");
+            assert_eq!(romfs.read_file("/data/b.txt").unwrap(), b"file b");
+            assert_eq!(romfs.read_file("data/sub/c.txt").unwrap(), b"file c");
+        }
+
+        #[test]
+        fn should_error_if_the_file_does_not_exist() {
+            let (_archive, file) = create_file(&create_romfs());
+            let romfs = RomFs::open(&file, 0).unwrap();
+
+            let result = romfs.read_file("/data/c.txt").unwrap_err();
+            assert_eq!(result, fs_error::file_not_found());
+        }
+
+        #[test]
+        fn should_error_if_the_directory_does_not_exist() {
+            let (_archive, file) = create_file(&create_romfs());
+            let romfs = RomFs::open(&file, 0).unwrap();
+
+            let result = romfs.read_file("/missing/a.txt").unwrap_err();
+            assert_eq!(result, fs_error::path_not_found());
+        }
+
+        #[test]
+        fn should_error_if_a_hash_bucket_loops() {
+            let (_archive, file) = create_file(&create_romfs());
+            let mut romfs = RomFs::open(&file, 0).unwrap();
+            // Every bucket starts at the first file, which points back to itself
+            romfs.file_hash_table.fill(0);
+            romfs.file_metadata.write_le(0x18, &0u32).unwrap();
+
+            let result = romfs.read_file("/missing.txt").unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+
+        #[test]
+        fn should_error_if_a_directory_hash_bucket_loops() {
+            let (_archive, file) = create_file(&create_romfs());
+            let mut romfs = RomFs::open(&file, 0).unwrap();
+            romfs.directory_hash_table.fill(0);
+            romfs.directory_metadata.write_le(0x10, &0u32).unwrap();
+
+            let result = romfs.read_file("/missing/a.txt").unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+    }
+
+    mod read_dir {
+        use super::*;
+
+        fn get_entries(romfs: &RomFs<MemoryFile>, path: &str) -> Vec<(String, u64, bool)> {
+            romfs
+                .read_dir(path)
+                .unwrap()
+                .map(|entry| {
+                    (
+                        String::from(entry.name()),
+                        entry.size(),
+                        entry.is_directory(),
+                    )
+                })
+                .collect()
+        }
+
+        #[test]
+        fn should_list_directories_then_files() {
+            let (_archive, file) = create_file(&create_romfs());
+            let romfs = RomFs::open(&file, 0).unwrap();
+
+            assert_eq!(
+                get_entries(&romfs, "/"),
+                vec![
+                    (String::from("data"), 0, true),
+                    (String::from("empty"), 0, true),
+                    (String::from("icon.bin"), 4, false),
+                ]
+            );
+            assert_eq!(
+                get_entries(&romfs, "/data"),
+                vec![
+                    (String::from("sub"), 0, true),
+                    (String::from("a.txt"), 6, false),
+                    (String::from("b.txt"), 6, false),
+                ]
+            );
+        }
+
+        #[test]
+        fn should_list_empty_directories() {
+            let (_archive, file) = create_file(&create_romfs());
+            let romfs = RomFs::open(&file, 0).unwrap();
+
+            assert_eq!(get_entries(&romfs, "/empty"), vec![]);
+        }
+
+        #[test]
+        fn should_stop_if_a_sibling_chain_loops() {
+            let (_archive, file) = create_file(&create_romfs());
+            let mut romfs = RomFs::open(&file, 0).unwrap();
+            // The root directory's first child points back to itself
+            let first_child: u32 = romfs.directory_metadata.read_le(0x8).unwrap();
+            romfs
+                .directory_metadata
+                .write_le(first_child as usize + 0x4, &first_child)
+                .unwrap();
+
+            let result = romfs.read_dir("/").unwrap().count();
+
+            assert!(result <= romfs.directory_metadata.len() / DIRECTORY_METADATA_SIZE + 1);
+        }
+    }
+}
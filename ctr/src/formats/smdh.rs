@@ -0,0 +1,447 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/SMDH) for the documentation
+*/
+
+use crate::{
+    res::{error, CtrResult},
+    utils::convert::bytes_to_utf16le_string,
+};
+use alloc::{string::String, vec, vec::Vec};
+use no_std_io::{EndianRead, EndianWrite, Reader};
+use num_enum::IntoPrimitive;
+
+pub const SMDH_MAGIC: [u8; 4] = *b"SMDH";
+pub const SMDH_SIZE: usize = 0x36c0;
+pub const APPLICATION_TITLE_COUNT: usize = 16;
+
+const APPLICATION_TITLES_OFFSET: usize = 0x8;
+const APPLICATION_TITLE_SIZE: usize = 0x200;
+const SETTINGS_OFFSET: usize = 0x2008;
+const SMALL_ICON_OFFSET: usize = 0x2040;
+const LARGE_ICON_OFFSET: usize = 0x24c0;
+
+pub const SMALL_ICON_SIZE: usize = 24;
+pub const LARGE_ICON_SIZE: usize = 48;
+
+/// The languages used to index application titles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u8)]
+pub enum TitleLanguage {
+    Japanese = 0,
+    English = 1,
+    French = 2,
+    German = 3,
+    Italian = 4,
+    Spanish = 5,
+    SimplifiedChinese = 6,
+    Korean = 7,
+    Dutch = 8,
+    Portuguese = 9,
+    Russian = 10,
+    TraditionalChinese = 11,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct ApplicationTitle {
+    pub short_description: String,
+    pub long_description: String,
+    pub publisher: String,
+}
+
+impl ApplicationTitle {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        Ok(Self {
+            short_description: bytes_to_utf16le_string(&bytes[..0x80])?,
+            long_description: bytes_to_utf16le_string(&bytes[0x80..0x180])?,
+            publisher: bytes_to_utf16le_string(&bytes[0x180..0x200])?,
+        })
+    }
+}
+
+/// The organizations used to index age ratings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u8)]
+pub enum RatingOrganization {
+    Cero = 0,
+    Esrb = 1,
+    Usk = 3,
+    PegiGen = 4,
+    PegiPrt = 6,
+    Bbfc = 7,
+    Cob = 8,
+    Grb = 9,
+    Cgsrr = 10,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct AgeRating {
+    raw: u8,
+}
+
+impl AgeRating {
+    pub fn new(raw: u8) -> Self {
+        Self { raw }
+    }
+
+    /// Whether the rating is used.
+    pub fn is_active(&self) -> bool {
+        self.raw & 0x80 != 0
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.raw & 0x40 != 0
+    }
+
+    pub fn has_no_age_restriction(&self) -> bool {
+        self.raw & 0x20 != 0
+    }
+
+    pub fn age(&self) -> u8 {
+        self.raw & 0x1f
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u32)]
+pub enum RegionLockout {
+    Japan = 0x1,
+    NorthAmerica = 0x2,
+    Europe = 0x4,
+    Australia = 0x8,
+    China = 0x10,
+    Korea = 0x20,
+    Taiwan = 0x40,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u32)]
+pub enum SmdhFlag {
+    Visible = 0x1,
+    AutoBoot = 0x2,
+    Allow3d = 0x4,
+    RequireEula = 0x8,
+    AutoSaveOnExit = 0x10,
+    ExtendedBanner = 0x20,
+    RatingRequired = 0x40,
+    SaveData = 0x80,
+    RecordUsage = 0x100,
+    DisableSaveBackups = 0x400,
+    New3ds = 0x1000,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, EndianRead, EndianWrite)]
+pub struct SmdhSettings {
+    pub age_ratings: [u8; 16],
+    pub region_lockout: u32,
+    pub match_maker_id: u32,
+    pub match_maker_bit_id: u64,
+    pub flags: u32,
+    pub eula_minor_version: u8,
+    pub eula_major_version: u8,
+    pub reserved: u16,
+    /// The raw bits of an f32.
+    pub optimal_animation_default_frame: u32,
+    pub cec_id: u32,
+}
+
+impl SmdhSettings {
+    pub fn age_rating(&self, organization: RatingOrganization) -> AgeRating {
+        AgeRating::new(self.age_ratings[u8::from(organization) as usize])
+    }
+
+    pub fn is_region_free(&self) -> bool {
+        self.region_lockout == 0x7fffffff
+    }
+
+    pub fn is_region_allowed(&self, region: RegionLockout) -> bool {
+        self.region_lockout & u32::from(region) != 0
+    }
+
+    pub fn has_flag(&self, flag: SmdhFlag) -> bool {
+        self.flags & u32::from(flag) != 0
+    }
+
+    pub fn get_optimal_animation_default_frame(&self) -> f32 {
+        f32::from_bits(self.optimal_animation_default_frame)
+    }
+}
+
+/// A decoded icon with RGBA pixels in rows from the top left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Icon {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+impl Icon {
+    /// Returns the RGBA value of a pixel.
+    pub fn get_pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let index = (y * self.width + x) * 4;
+        [
+            self.rgba[index],
+            self.rgba[index + 1],
+            self.rgba[index + 2],
+            self.rgba[index + 3],
+        ]
+    }
+}
+
+fn rgb565_to_rgba(pixel: u16) -> [u8; 4] {
+    let red = ((pixel >> 11) & 0x1f) as u8;
+    let green = ((pixel >> 5) & 0x3f) as u8;
+    let blue = (pixel & 0x1f) as u8;
+
+    [
+        (red << 3) | (red >> 2),
+        (green << 2) | (green >> 4),
+        (blue << 3) | (blue >> 2),
+        0xff,
+    ]
+}
+
+/// Decodes RGB565 pixels stored in 8x8 tiles, with the pixels in each tile in Morton (Z-order) order.
+/// The width and height need to be multiples of 8.
+pub fn decode_tiled_rgb565(data: &[u8], width: usize, height: usize) -> CtrResult<Icon> {
+    if width & 0x7 != 0 || height & 0x7 != 0 {
+        return Err(error::invalid_value());
+    }
+
+    let pixel_count = width * height;
+
+    if data.len() < pixel_count * 2 {
+        return Err(error::invalid_size());
+    }
+
+    let tiles_per_row = width / 8;
+    let mut rgba = vec![0; pixel_count * 4];
+
+    for (index, pixel) in data.chunks_exact(2).take(pixel_count).enumerate() {
+        let tile = index / 64;
+        let morton_index = index % 64;
+
+        // Even bits are the x coordinate and odd bits are the y coordinate
+        let tile_x = (morton_index & 1) | ((morton_index >> 1) & 2) | ((morton_index >> 2) & 4);
+        let tile_y =
+            ((morton_index >> 1) & 1) | ((morton_index >> 2) & 2) | ((morton_index >> 3) & 4);
+
+        let x = (tile % tiles_per_row) * 8 + tile_x;
+        let y = (tile / tiles_per_row) * 8 + tile_y;
+
+        let output_index = (y * width + x) * 4;
+        let color = rgb565_to_rgba(u16::from_le_bytes([pixel[0], pixel[1]]));
+        rgba[output_index..output_index + 4].copy_from_slice(&color);
+    }
+
+    Ok(Icon {
+        width,
+        height,
+        rgba,
+    })
+}
+
+/// The metadata of a title, such as its names and icons.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Smdh {
+    pub version: u16,
+    pub titles: Vec<ApplicationTitle>,
+    pub settings: SmdhSettings,
+    small_icon: Vec<u8>,
+    large_icon: Vec<u8>,
+}
+
+impl Smdh {
+    pub fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        if bytes.len() < SMDH_SIZE {
+            return Err(error::invalid_size());
+        }
+
+        if bytes[..4] != SMDH_MAGIC {
+            return Err(error::invalid_value());
+        }
+
+        let titles = bytes[APPLICATION_TITLES_OFFSET..SETTINGS_OFFSET]
+            .chunks_exact(APPLICATION_TITLE_SIZE)
+            .map(ApplicationTitle::parse)
+            .collect::<CtrResult<Vec<ApplicationTitle>>>()?;
+
+        Ok(Self {
+            version: bytes.read_le(4)?,
+            titles,
+            settings: bytes.read_le(SETTINGS_OFFSET)?,
+            small_icon: bytes[SMALL_ICON_OFFSET..LARGE_ICON_OFFSET].to_vec(),
+            large_icon: bytes[LARGE_ICON_OFFSET..SMDH_SIZE].to_vec(),
+        })
+    }
+
+    pub fn title(&self, language: TitleLanguage) -> &ApplicationTitle {
+        &self.titles[u8::from(language) as usize]
+    }
+
+    /// Decodes the 24x24 icon.
+    pub fn small_icon(&self) -> Icon {
+        // The size is checked when parsing, so this can't fail
+        decode_tiled_rgb565(&self.small_icon, SMALL_ICON_SIZE, SMALL_ICON_SIZE).unwrap()
+    }
+
+    /// Decodes the 48x48 icon.
+    pub fn large_icon(&self) -> Icon {
+        // The size is checked when parsing, so this can't fail
+        decode_tiled_rgb565(&self.large_icon, LARGE_ICON_SIZE, LARGE_ICON_SIZE).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use no_std_io::Writer;
+
+    fn write_utf16(bytes: &mut [u8], text: &str) {
+        for (index, short) in text.encode_utf16().enumerate() {
+            bytes[index * 2..index * 2 + 2].copy_from_slice(&short.to_le_bytes());
+        }
+    }
+
+    fn write_pixel(bytes: &mut [u8], offset: usize, index: usize, pixel: u16) {
+        let offset = offset + index * 2;
+        bytes[offset..offset + 2].copy_from_slice(&pixel.to_le_bytes());
+    }
+
+    fn create_smdh() -> Vec<u8> {
+        let mut bytes = vec![0; SMDH_SIZE];
+        bytes[..4].copy_from_slice(&SMDH_MAGIC);
+        bytes[4] = 3;
+
+        let english_title = APPLICATION_TITLES_OFFSET + APPLICATION_TITLE_SIZE;
+        write_utf16(&mut bytes[english_title..], "Test");
+        write_utf16(&mut bytes[english_title + 0x80..], "Test Application");
+        write_utf16(&mut bytes[english_title + 0x180..], "Publisher");
+
+        let japanese_title = APPLICATION_TITLES_OFFSET;
+        write_utf16(&mut bytes[japanese_title..], "テスト");
+
+        let settings = SmdhSettings {
+            age_ratings: [0x80 | 12, 0xa0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            region_lockout: 0x7fffffff,
+            match_maker_id: 0x1234,
+            match_maker_bit_id: 0x5678,
+            flags: 0x1 | 0x4 | 0x80,
+            eula_minor_version: 1,
+            eula_major_version: 2,
+            reserved: 0,
+            optimal_animation_default_frame: 1.5f32.to_bits(),
+            cec_id: 0xabcd,
+        };
+        bytes.write_le(SETTINGS_OFFSET, &settings).unwrap();
+
+        write_pixel(&mut bytes, SMALL_ICON_OFFSET, 1, 0xf800);
+        write_pixel(&mut bytes, SMALL_ICON_OFFSET, 2, 0x07e0);
+        write_pixel(&mut bytes, SMALL_ICON_OFFSET, 64, 0x001f);
+        write_pixel(&mut bytes, LARGE_ICON_OFFSET, 63, 0xffff);
+        write_pixel(&mut bytes, LARGE_ICON_OFFSET, 6 * 64, 0xf800);
+
+        bytes
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_parse_the_version_and_titles() {
+            let smdh = Smdh::parse(&create_smdh()).unwrap();
+            let english_title = smdh.title(TitleLanguage::English);
+
+            assert_eq!(smdh.version, 3);
+            assert_eq!(smdh.titles.len(), APPLICATION_TITLE_COUNT);
+            assert_eq!(english_title.short_description, "Test");
+            assert_eq!(english_title.long_description, "Test Application");
+            assert_eq!(english_title.publisher, "Publisher");
+            assert_eq!(
+                smdh.title(TitleLanguage::Japanese).short_description,
+                "テスト"
+            );
+            assert_eq!(smdh.title(TitleLanguage::French), &Default::default());
+        }
+
+        #[test]
+        fn should_parse_the_settings() {
+            let settings = Smdh::parse(&create_smdh()).unwrap().settings;
+
+            let cero_rating = settings.age_rating(RatingOrganization::Cero);
+            assert!(cero_rating.is_active());
+            assert_eq!(cero_rating.age(), 12);
+
+            let esrb_rating = settings.age_rating(RatingOrganization::Esrb);
+            assert!(esrb_rating.is_active());
+            assert!(esrb_rating.has_no_age_restriction());
+            assert!(!settings.age_rating(RatingOrganization::Usk).is_active());
+
+            assert!(settings.is_region_free());
+            assert!(settings.is_region_allowed(RegionLockout::Europe));
+            assert!(settings.has_flag(SmdhFlag::Visible));
+            assert!(settings.has_flag(SmdhFlag::Allow3d));
+            assert!(settings.has_flag(SmdhFlag::SaveData));
+            assert!(!settings.has_flag(SmdhFlag::AutoBoot));
+            assert_eq!(settings.match_maker_id, 0x1234);
+            assert_eq!(settings.match_maker_bit_id, 0x5678);
+            assert_eq!(settings.eula_major_version, 2);
+            assert_eq!(settings.eula_minor_version, 1);
+            assert_eq!(settings.get_optimal_animation_default_frame(), 1.5);
+            assert_eq!(settings.cec_id, 0xabcd);
+        }
+
+        #[test]
+        fn should_error_if_the_magic_is_invalid() {
+            let mut bytes = create_smdh();
+            bytes[0] = b'X';
+
+            let result = Smdh::parse(&bytes).unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+
+        #[test]
+        fn should_error_if_the_data_is_too_small() {
+            let bytes = create_smdh();
+            let result = Smdh::parse(&bytes[..SMDH_SIZE - 1]).unwrap_err();
+            assert_eq!(result, error::invalid_size());
+        }
+    }
+
+    mod icons {
+        use super::*;
+
+        #[test]
+        fn should_decode_the_small_icon() {
+            let icon = Smdh::parse(&create_smdh()).unwrap().small_icon();
+
+            assert_eq!(icon.width, 24);
+            assert_eq!(icon.height, 24);
+            assert_eq!(icon.rgba.len(), 24 * 24 * 4);
+            assert_eq!(icon.get_pixel(0, 0), [0, 0, 0, 0xff]);
+            assert_eq!(icon.get_pixel(1, 0), [0xff, 0, 0, 0xff]);
+            assert_eq!(icon.get_pixel(0, 1), [0, 0xff, 0, 0xff]);
+            assert_eq!(icon.get_pixel(8, 0), [0, 0, 0xff, 0xff]);
+        }
+
+        #[test]
+        fn should_decode_the_large_icon() {
+            let icon = Smdh::parse(&create_smdh()).unwrap().large_icon();
+
+            assert_eq!(icon.width, 48);
+            assert_eq!(icon.height, 48);
+            assert_eq!(icon.get_pixel(7, 7), [0xff, 0xff, 0xff, 0xff]);
+            assert_eq!(icon.get_pixel(0, 8), [0xff, 0, 0, 0xff]);
+        }
+
+        #[test]
+        fn should_expand_rgb565_colors() {
+            assert_eq!(rgb565_to_rgba(0x8410), [0x84, 0x82, 0x84, 0xff]);
+        }
+
+        #[test]
+        fn should_error_if_the_size_is_not_a_multiple_of_8() {
+            let result = decode_tiled_rgb565(&[0; 200], 10, 10).unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+    }
+}
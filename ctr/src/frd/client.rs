@@ -0,0 +1,224 @@
+use super::{
+    server::{
+        FrdUCommand, FriendKeyListIn, GetEventNotificationOut, GetFriendKeyListIn, GetFriendMiiIn,
+        GetFriendScreenNameIn,
+    },
+    ExpandedFriendPresence, FriendComment, FriendKey, FriendPresence, FriendProfile, Mii,
+    NotificationEvent, ScreenName,
+};
+use crate::{
+    ipc::{Command, Handles, PermissionBuffer, StaticBuffer},
+    res::{CtrResult, ResultCode},
+    service_session::{create_session_manager, session},
+    srv::get_service_handle_direct,
+    Handle,
+};
+use alloc::{vec, vec::Vec};
+use core::{convert::TryInto, mem};
+use no_std_io::{EndianRead, Reader, Writer};
+
+create_session_manager!({
+    get_service_handle_direct("frd:a").or_else(|_| get_service_handle_direct("frd:u"))?
+});
+
+/// Reads `count` fixed size items from an output buffer.
+fn read_list<T: EndianRead>(bytes: &[u8], count: usize) -> CtrResult<Vec<T>> {
+    (0..count)
+        .map(|index| Ok(bytes.read_le(index * mem::size_of::<T>())?))
+        .collect()
+}
+
+fn write_friend_keys(friend_keys: &[FriendKey]) -> CtrResult<Vec<u8>> {
+    let mut result = vec![0; mem::size_of_val(friend_keys)];
+
+    for (index, friend_key) in friend_keys.iter().enumerate() {
+        result.write_le(index * mem::size_of::<FriendKey>(), friend_key)?;
+    }
+
+    Ok(result)
+}
+
+pub fn get_my_friend_key() -> CtrResult<FriendKey> {
+    session!(frd);
+    Command::new(FrdUCommand::GetMyFriendKey.request_header(), ()).send(get_handle())
+}
+
+pub fn get_my_presence() -> CtrResult<ExpandedFriendPresence> {
+    session!(frd);
+
+    let mut presence_bytes = [0u8; mem::size_of::<ExpandedFriendPresence>()];
+    let presence_out = StaticBuffer::new_mut(&mut presence_bytes, 0);
+    Command::new_with_static_out(
+        FrdUCommand::GetMyPresence.request_header(),
+        (),
+        presence_out,
+    )
+    .send::<()>(get_handle())?;
+
+    Ok(presence_bytes.read_le(0)?)
+}
+
+pub fn get_my_screen_name() -> CtrResult<ScreenName> {
+    session!(frd);
+    Command::new(FrdUCommand::GetMyScreenName.request_header(), ()).send(get_handle())
+}
+
+pub fn get_my_mii() -> CtrResult<Mii> {
+    session!(frd);
+    Command::new(FrdUCommand::GetMyMii.request_header(), ()).send(get_handle())
+}
+
+pub fn get_my_comment() -> CtrResult<FriendComment> {
+    session!(frd);
+    Command::new(FrdUCommand::GetMyComment.request_header(), ()).send(get_handle())
+}
+
+/// Gets up to `max_count` friend keys, starting at `offset` in the friend list.
+pub fn get_friend_key_list(offset: u32, max_count: usize) -> CtrResult<Vec<FriendKey>> {
+    session!(frd);
+
+    let mut friend_key_bytes = vec![0u8; max_count * mem::size_of::<FriendKey>()];
+    let input = GetFriendKeyListIn {
+        offset,
+        max_count: max_count.try_into()?,
+    };
+    let friend_keys_out = StaticBuffer::new_mut(&mut friend_key_bytes, 0);
+    let count: u32 = Command::new_with_static_out(
+        FrdUCommand::GetFriendKeyList.request_header(),
+        input,
+        friend_keys_out,
+    )
+    .send(get_handle())?;
+
+    read_list(&friend_key_bytes, count.try_into()?)
+}
+
+pub fn get_friend_presence(friend_keys: &[FriendKey]) -> CtrResult<Vec<FriendPresence>> {
+    session!(frd);
+
+    let friend_key_bytes = write_friend_keys(friend_keys)?;
+    let mut presence_bytes = vec![0u8; friend_keys.len() * mem::size_of::<FriendPresence>()];
+    let input = FriendKeyListIn::new(&friend_key_bytes)?;
+    let presence_out = StaticBuffer::new_mut(&mut presence_bytes, 0);
+    Command::new_with_static_out(
+        FrdUCommand::GetFriendPresence.request_header(),
+        input,
+        presence_out,
+    )
+    .send::<()>(get_handle())?;
+
+    read_list(&presence_bytes, friend_keys.len())
+}
+
+pub fn get_friend_screen_name(friend_keys: &[FriendKey]) -> CtrResult<Vec<ScreenName>> {
+    session!(frd);
+
+    let friend_key_bytes = write_friend_keys(friend_keys)?;
+    let mut screen_name_bytes = vec![0u8; friend_keys.len() * mem::size_of::<ScreenName>()];
+    let input = GetFriendScreenNameIn {
+        max_screen_name_count: friend_keys.len().try_into()?,
+        max_screen_name_length: (mem::size_of::<ScreenName>() / 2).try_into()?,
+        unk1: 0,
+        unk2: 0,
+        friend_keys: FriendKeyListIn::new(&friend_key_bytes)?,
+    };
+    let screen_names_out = StaticBuffer::new_mut(&mut screen_name_bytes, 0);
+    Command::new_with_static_out(
+        FrdUCommand::GetFriendScreenName.request_header(),
+        input,
+        screen_names_out,
+    )
+    .send::<()>(get_handle())?;
+
+    read_list(&screen_name_bytes, friend_keys.len())
+}
+
+pub fn get_friend_mii(friend_keys: &[FriendKey]) -> CtrResult<Vec<Mii>> {
+    session!(frd);
+
+    let friend_key_bytes = write_friend_keys(friend_keys)?;
+    let mut mii_bytes = vec![0u8; friend_keys.len() * mem::size_of::<Mii>()];
+    let input = GetFriendMiiIn {
+        friend_keys: FriendKeyListIn::new(&friend_key_bytes)?,
+        out: PermissionBuffer::new_write(&mut mii_bytes),
+    };
+    Command::new(FrdUCommand::GetFriendMii.request_header(), input).send::<()>(get_handle())?;
+
+    read_list(&mii_bytes, friend_keys.len())
+}
+
+pub fn get_friend_profile(friend_keys: &[FriendKey]) -> CtrResult<Vec<FriendProfile>> {
+    session!(frd);
+
+    let friend_key_bytes = write_friend_keys(friend_keys)?;
+    let mut profile_bytes = vec![0u8; friend_keys.len() * mem::size_of::<FriendProfile>()];
+    let input = FriendKeyListIn::new(&friend_key_bytes)?;
+    let profiles_out = StaticBuffer::new_mut(&mut profile_bytes, 0);
+    Command::new_with_static_out(
+        FrdUCommand::GetFriendProfile.request_header(),
+        input,
+        profiles_out,
+    )
+    .send::<()>(get_handle())?;
+
+    read_list(&profile_bytes, friend_keys.len())
+}
+
+/// Sets an event that's signaled when a notification is available from [get_event_notification].
+pub fn attach_to_event_notification(event: &Handle) -> CtrResult {
+    session!(frd);
+
+    let input = unsafe { Handles::new(vec![event.get_raw()]) };
+    Command::new(
+        FrdUCommand::AttachToEventNotification.request_header(),
+        input,
+    )
+    .send(get_handle())
+}
+
+/// Gets up to `max_count` pending notifications.
+pub fn get_event_notification(max_count: usize) -> CtrResult<Vec<NotificationEvent>> {
+    session!(frd);
+
+    let mut event_bytes = vec![0u8; max_count * mem::size_of::<NotificationEvent>()];
+    let max_count: u32 = max_count.try_into()?;
+    let events_out = StaticBuffer::new_mut(&mut event_bytes, 0);
+    let header = FrdUCommand::GetEventNotification.request_header();
+    let out: GetEventNotificationOut =
+        Command::new_with_static_out(header, max_count, events_out).send(get_handle())?;
+
+    read_list(&event_bytes, out.count.try_into()?)
+}
+
+/// Gets the result of the last request frd made to the friends server.
+pub fn get_last_response_result() -> CtrResult<ResultCode> {
+    session!(frd);
+    Command::new(FrdUCommand::GetLastResponseResult.request_header(), ()).send(get_handle())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_write_and_read_friend_key_lists() {
+        let friend_keys = [
+            FriendKey {
+                principal_id: 1,
+                padding: 0,
+                local_friend_code: 2,
+            },
+            FriendKey {
+                principal_id: 3,
+                padding: 0,
+                local_friend_code: 4,
+            },
+        ];
+
+        let bytes = write_friend_keys(&friend_keys).unwrap();
+
+        assert_eq!(bytes.len(), 0x20);
+        assert_eq!(bytes[0x10..0x14], [3, 0, 0, 0]);
+        assert_eq!(read_list::<FriendKey>(&bytes, 2).unwrap(), friend_keys);
+    }
+}
@@ -0,0 +1,706 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/Mii#Mii_format) for the documentation
+*/
+
+use super::Mii;
+use crate::{
+    crypto::{aes_128_ccm_decrypt, aes_128_ccm_encrypt, AES_128_KEY_SIZE, AES_BLOCK_SIZE},
+    res::{error, CtrResult},
+    utils::convert::bytes_to_utf16le_string,
+};
+use alloc::{string::String, vec::Vec};
+use core::convert::TryInto;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+/// The maximum number of UTF-16 code units in a Mii name or creator name.
+pub const MAX_MII_NAME_LENGTH: usize = 10;
+
+const NAME_OFFSET: usize = 0x1a;
+const CREATOR_NAME_OFFSET: usize = 0x48;
+const CHECKSUM_OFFSET: usize = 0x5e;
+
+/// The size of the encrypted Mii data in a QR code.
+pub const MII_QR_PAYLOAD_SIZE: usize = 0x70;
+
+const MII_QR_KEY: [u8; AES_128_KEY_SIZE] = [
+    0x59, 0xfc, 0x81, 0x7e, 0x64, 0x46, 0xea, 0x61, 0x90, 0x34, 0x7b, 0x20, 0xe9, 0xbd, 0xce, 0x52,
+];
+/// The first 8 bytes of the payload are the Mii bytes at this offset, which double as the nonce.
+const MII_QR_NONCE_OFFSET: usize = 0xc;
+const MII_QR_NONCE_SIZE: usize = 8;
+const MII_QR_MAC_OFFSET: usize = MII_QR_PAYLOAD_SIZE - AES_BLOCK_SIZE;
+
+/// The CCM nonce is the payload nonce padded with zeros.
+fn create_qr_nonce(payload_nonce: &[u8]) -> [u8; 12] {
+    let mut nonce = [0; 12];
+    nonce[..MII_QR_NONCE_SIZE].copy_from_slice(payload_nonce);
+    nonce
+}
+
+/// CRC-16/XMODEM, which is what Miis use for their checksum.
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ ((*byte as u16) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
+fn get_bits(value: u32, shift: u32, width: u32) -> u8 {
+    ((value >> shift) & ((1 << width) - 1)) as u8
+}
+
+fn set_bits(target: &mut u32, shift: u32, width: u32, value: u8) -> CtrResult {
+    let mask = (1 << width) - 1;
+    let value = value as u32;
+
+    if value > mask {
+        return Err(error::invalid_value());
+    }
+
+    *target = (*target & !(mask << shift)) | (value << shift);
+    Ok(())
+}
+
+fn set_flag(target: &mut u32, shift: u32, value: bool) -> CtrResult {
+    set_bits(target, shift, 1, value as u8)
+}
+
+impl Mii {
+    /// Returns the checksum stored at the end of the Mii.
+    pub fn get_checksum(&self) -> u16 {
+        u16::from_be_bytes([self.raw[CHECKSUM_OFFSET], self.raw[CHECKSUM_OFFSET + 1]])
+    }
+
+    /// Calculates the checksum of the Mii's data.
+    pub fn calculate_checksum(&self) -> u16 {
+        crc16(&self.raw[..CHECKSUM_OFFSET])
+    }
+
+    pub fn has_valid_checksum(&self) -> bool {
+        self.get_checksum() == self.calculate_checksum()
+    }
+
+    pub fn update_checksum(&mut self) {
+        let checksum = self.calculate_checksum();
+        self.raw[CHECKSUM_OFFSET..].copy_from_slice(&checksum.to_be_bytes());
+    }
+
+    /// Decrypts the payload of a Mii QR code.
+    pub fn from_qr_payload(payload: &[u8]) -> CtrResult<Self> {
+        if payload.len() != MII_QR_PAYLOAD_SIZE {
+            return Err(error::invalid_size());
+        }
+
+        let payload_nonce = &payload[..MII_QR_NONCE_SIZE];
+        let mut data = [0u8; MII_QR_MAC_OFFSET - MII_QR_NONCE_SIZE];
+        data.copy_from_slice(&payload[MII_QR_NONCE_SIZE..MII_QR_MAC_OFFSET]);
+        aes_128_ccm_decrypt(
+            &MII_QR_KEY,
+            &create_qr_nonce(payload_nonce),
+            &mut data,
+            &payload[MII_QR_MAC_OFFSET..],
+        )?;
+
+        let nonce_end = MII_QR_NONCE_OFFSET + MII_QR_NONCE_SIZE;
+        let mut raw = [0u8; 96];
+        raw[..MII_QR_NONCE_OFFSET].copy_from_slice(&data[..MII_QR_NONCE_OFFSET]);
+        raw[MII_QR_NONCE_OFFSET..nonce_end].copy_from_slice(payload_nonce);
+        raw[nonce_end..].copy_from_slice(&data[MII_QR_NONCE_OFFSET..]);
+
+        Ok(Self::new(raw))
+    }
+
+    /// Encrypts the Mii into the payload of a Mii QR code.
+    pub fn to_qr_payload(&self) -> CtrResult<[u8; MII_QR_PAYLOAD_SIZE]> {
+        let nonce_end = MII_QR_NONCE_OFFSET + MII_QR_NONCE_SIZE;
+        let mut payload = [0u8; MII_QR_PAYLOAD_SIZE];
+        payload[..MII_QR_NONCE_SIZE].copy_from_slice(&self.raw[MII_QR_NONCE_OFFSET..nonce_end]);
+        payload[MII_QR_NONCE_SIZE..MII_QR_NONCE_SIZE + MII_QR_NONCE_OFFSET]
+            .copy_from_slice(&self.raw[..MII_QR_NONCE_OFFSET]);
+        payload[MII_QR_NONCE_SIZE + MII_QR_NONCE_OFFSET..MII_QR_MAC_OFFSET]
+            .copy_from_slice(&self.raw[nonce_end..]);
+
+        let nonce = create_qr_nonce(&payload[..MII_QR_NONCE_SIZE]);
+        let (data, mac) =
+            payload[MII_QR_NONCE_SIZE..].split_at_mut(MII_QR_MAC_OFFSET - MII_QR_NONCE_SIZE);
+        aes_128_ccm_encrypt(&MII_QR_KEY, &nonce, data, mac)?;
+
+        Ok(payload)
+    }
+
+    fn read_u16(&self, offset: usize) -> u32 {
+        u16::from_le_bytes([self.raw[offset], self.raw[offset + 1]]) as u32
+    }
+
+    fn write_u16(&mut self, offset: usize, value: u32) {
+        self.raw[offset..offset + 2].copy_from_slice(&(value as u16).to_le_bytes());
+    }
+
+    fn read_u32(&self, offset: usize) -> u32 {
+        u32::from_le_bytes(self.raw[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn write_u32(&mut self, offset: usize, value: u32) {
+        self.raw[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn read_name(&self, offset: usize) -> CtrResult<String> {
+        bytes_to_utf16le_string(&self.raw[offset..offset + MAX_MII_NAME_LENGTH * 2])
+    }
+
+    /// Names are only rewritten when they change so padding after the null terminator is kept.
+    fn write_name(&mut self, offset: usize, name: &str) -> CtrResult {
+        if self.read_name(offset).ok().as_deref() == Some(name) {
+            return Ok(());
+        }
+
+        let name = name.encode_utf16().collect::<Vec<u16>>();
+
+        if name.contains(&0) {
+            return Err(error::invalid_value());
+        }
+
+        if name.len() > MAX_MII_NAME_LENGTH {
+            return Err(error::invalid_size());
+        }
+
+        let field = &mut self.raw[offset..offset + MAX_MII_NAME_LENGTH * 2];
+        field.fill(0);
+
+        for (index, code_unit) in name.iter().enumerate() {
+            field[index * 2..index * 2 + 2].copy_from_slice(&code_unit.to_le_bytes());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive, TryFromPrimitive)]
+#[repr(u8)]
+pub enum MiiGender {
+    Male = 0,
+    Female = 1,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MiiFace {
+    pub shape: u8,
+    pub skin_color: u8,
+    pub wrinkles: u8,
+    pub makeup: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MiiHair {
+    pub style: u8,
+    pub color: u8,
+    pub flipped: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MiiEyes {
+    pub style: u8,
+    pub color: u8,
+    pub scale: u8,
+    pub y_scale: u8,
+    pub rotation: u8,
+    pub x_spacing: u8,
+    pub y_position: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MiiEyebrows {
+    pub style: u8,
+    pub color: u8,
+    pub scale: u8,
+    pub y_scale: u8,
+    pub rotation: u8,
+    pub x_spacing: u8,
+    pub y_position: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MiiNose {
+    pub style: u8,
+    pub scale: u8,
+    pub y_position: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MiiMouth {
+    pub style: u8,
+    pub color: u8,
+    pub scale: u8,
+    pub y_scale: u8,
+    pub y_position: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MiiMustache {
+    pub style: u8,
+    pub scale: u8,
+    pub y_position: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MiiBeard {
+    pub style: u8,
+    pub color: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MiiGlasses {
+    pub style: u8,
+    pub color: u8,
+    pub scale: u8,
+    pub y_position: u8,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MiiMole {
+    pub enabled: bool,
+    pub scale: u8,
+    pub x_position: u8,
+    pub y_position: u8,
+}
+
+/// A decoded [Mii].
+/// Encoding writes the fields over the Mii this was decoded from,
+/// so undocumented bits are preserved and an unchanged Mii round-trips exactly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MiiData {
+    pub version: u8,
+    pub copyable: bool,
+    pub profanity: bool,
+    pub region_lock: u8,
+    pub character_set: u8,
+    pub page_index: u8,
+    pub slot_index: u8,
+    pub device_origin: u8,
+    pub system_id: [u8; 8],
+    /// Seconds since 2010-01-01, divided by 2.
+    pub creation_timestamp: u32,
+    pub special: bool,
+    pub creator_mac: [u8; 6],
+    pub gender: MiiGender,
+    pub birth_month: u8,
+    pub birth_day: u8,
+    pub favorite_color: u8,
+    pub favorite: bool,
+    pub name: String,
+    pub height: u8,
+    pub build: u8,
+    pub sharing_disabled: bool,
+    pub face: MiiFace,
+    pub hair: MiiHair,
+    pub eyes: MiiEyes,
+    pub eyebrows: MiiEyebrows,
+    pub nose: MiiNose,
+    pub mouth: MiiMouth,
+    pub mustache: MiiMustache,
+    pub beard: MiiBeard,
+    pub glasses: MiiGlasses,
+    pub mole: MiiMole,
+    pub creator_name: String,
+    original: Mii,
+}
+
+impl MiiData {
+    /// Decodes a Mii, returning an error if its checksum is invalid.
+    pub fn decode(mii: &Mii) -> CtrResult<Self> {
+        if !mii.has_valid_checksum() {
+            return Err(error::invalid_value());
+        }
+
+        let raw = &mii.raw;
+        let mii_id = u32::from_be_bytes(raw[0xc..0x10].try_into().unwrap());
+        let profile = mii.read_u16(0x18);
+        let eyes = mii.read_u32(0x34);
+        let eyebrows = mii.read_u32(0x38);
+        let nose = mii.read_u16(0x3c);
+        let mouth = mii.read_u16(0x3e);
+        let mouth_and_mustache = mii.read_u16(0x40);
+        let beard = mii.read_u16(0x42);
+        let glasses = mii.read_u16(0x44);
+        let mole = mii.read_u16(0x46);
+
+        Ok(Self {
+            version: raw[0],
+            copyable: get_bits(raw[1].into(), 0, 1) != 0,
+            profanity: get_bits(raw[1].into(), 1, 1) != 0,
+            region_lock: get_bits(raw[1].into(), 2, 2),
+            character_set: get_bits(raw[1].into(), 4, 2),
+            page_index: get_bits(raw[2].into(), 0, 4),
+            slot_index: get_bits(raw[2].into(), 4, 4),
+            device_origin: get_bits(raw[3].into(), 4, 3),
+            system_id: raw[0x4..0xc].try_into().unwrap(),
+            creation_timestamp: mii_id & 0xfffffff,
+            special: mii_id >> 31 == 0,
+            creator_mac: raw[0x10..0x16].try_into().unwrap(),
+            gender: get_bits(profile, 0, 1).try_into().unwrap(),
+            birth_month: get_bits(profile, 1, 4),
+            birth_day: get_bits(profile, 5, 5),
+            favorite_color: get_bits(profile, 10, 4),
+            favorite: get_bits(profile, 14, 1) != 0,
+            name: mii.read_name(NAME_OFFSET)?,
+            height: raw[0x2e],
+            build: raw[0x2f],
+            sharing_disabled: get_bits(raw[0x30].into(), 0, 1) != 0,
+            face: MiiFace {
+                shape: get_bits(raw[0x30].into(), 1, 4),
+                skin_color: get_bits(raw[0x30].into(), 5, 3),
+                wrinkles: get_bits(raw[0x31].into(), 0, 4),
+                makeup: get_bits(raw[0x31].into(), 4, 4),
+            },
+            hair: MiiHair {
+                style: raw[0x32],
+                color: get_bits(raw[0x33].into(), 0, 3),
+                flipped: get_bits(raw[0x33].into(), 3, 1) != 0,
+            },
+            eyes: MiiEyes {
+                style: get_bits(eyes, 0, 6),
+                color: get_bits(eyes, 6, 3),
+                scale: get_bits(eyes, 9, 4),
+                y_scale: get_bits(eyes, 13, 3),
+                rotation: get_bits(eyes, 16, 5),
+                x_spacing: get_bits(eyes, 21, 4),
+                y_position: get_bits(eyes, 25, 5),
+            },
+            eyebrows: MiiEyebrows {
+                style: get_bits(eyebrows, 0, 5),
+                color: get_bits(eyebrows, 5, 3),
+                scale: get_bits(eyebrows, 8, 4),
+                y_scale: get_bits(eyebrows, 12, 3),
+                rotation: get_bits(eyebrows, 16, 4),
+                x_spacing: get_bits(eyebrows, 21, 4),
+                y_position: get_bits(eyebrows, 25, 5),
+            },
+            nose: MiiNose {
+                style: get_bits(nose, 0, 5),
+                scale: get_bits(nose, 5, 4),
+                y_position: get_bits(nose, 9, 5),
+            },
+            mouth: MiiMouth {
+                style: get_bits(mouth, 0, 6),
+                color: get_bits(mouth, 6, 3),
+                scale: get_bits(mouth, 9, 4),
+                y_scale: get_bits(mouth, 13, 3),
+                y_position: get_bits(mouth_and_mustache, 0, 5),
+            },
+            mustache: MiiMustache {
+                style: get_bits(mouth_and_mustache, 5, 3),
+                scale: get_bits(beard, 6, 4),
+                y_position: get_bits(beard, 10, 5),
+            },
+            beard: MiiBeard {
+                style: get_bits(beard, 0, 3),
+                color: get_bits(beard, 3, 3),
+            },
+            glasses: MiiGlasses {
+                style: get_bits(glasses, 0, 4),
+                color: get_bits(glasses, 4, 3),
+                scale: get_bits(glasses, 7, 4),
+                y_position: get_bits(glasses, 11, 5),
+            },
+            mole: MiiMole {
+                enabled: get_bits(mole, 0, 1) != 0,
+                scale: get_bits(mole, 1, 4),
+                x_position: get_bits(mole, 5, 5),
+                y_position: get_bits(mole, 10, 5),
+            },
+            creator_name: mii.read_name(CREATOR_NAME_OFFSET)?,
+            original: *mii,
+        })
+    }
+
+    /// Encodes the Mii and regenerates its checksum.
+    /// Returns an error if a field doesn't fit in its bits or a name is invalid.
+    pub fn encode(&self) -> CtrResult<Mii> {
+        let mut mii = self.original;
+
+        let mut flags = mii.raw[1].into();
+        set_flag(&mut flags, 0, self.copyable)?;
+        set_flag(&mut flags, 1, self.profanity)?;
+        set_bits(&mut flags, 2, 2, self.region_lock)?;
+        set_bits(&mut flags, 4, 2, self.character_set)?;
+
+        let mut slot = mii.raw[2].into();
+        set_bits(&mut slot, 0, 4, self.page_index)?;
+        set_bits(&mut slot, 4, 4, self.slot_index)?;
+
+        let mut origin = mii.raw[3].into();
+        set_bits(&mut origin, 4, 3, self.device_origin)?;
+
+        if self.creation_timestamp > 0xfffffff {
+            return Err(error::invalid_value());
+        }
+
+        let mut mii_id = u32::from_be_bytes(mii.raw[0xc..0x10].try_into().unwrap());
+        mii_id = (mii_id & !0xfffffff) | self.creation_timestamp;
+        set_flag(&mut mii_id, 31, !self.special)?;
+
+        let mut profile = mii.read_u16(0x18);
+        set_bits(&mut profile, 0, 1, self.gender.into())?;
+        set_bits(&mut profile, 1, 4, self.birth_month)?;
+        set_bits(&mut profile, 5, 5, self.birth_day)?;
+        set_bits(&mut profile, 10, 4, self.favorite_color)?;
+        set_flag(&mut profile, 14, self.favorite)?;
+
+        let mut face = mii.raw[0x30].into();
+        set_flag(&mut face, 0, self.sharing_disabled)?;
+        set_bits(&mut face, 1, 4, self.face.shape)?;
+        set_bits(&mut face, 5, 3, self.face.skin_color)?;
+
+        let mut face_details = mii.raw[0x31].into();
+        set_bits(&mut face_details, 0, 4, self.face.wrinkles)?;
+        set_bits(&mut face_details, 4, 4, self.face.makeup)?;
+
+        let mut hair = mii.raw[0x33].into();
+        set_bits(&mut hair, 0, 3, self.hair.color)?;
+        set_flag(&mut hair, 3, self.hair.flipped)?;
+
+        let mut eyes = mii.read_u32(0x34);
+        set_bits(&mut eyes, 0, 6, self.eyes.style)?;
+        set_bits(&mut eyes, 6, 3, self.eyes.color)?;
+        set_bits(&mut eyes, 9, 4, self.eyes.scale)?;
+        set_bits(&mut eyes, 13, 3, self.eyes.y_scale)?;
+        set_bits(&mut eyes, 16, 5, self.eyes.rotation)?;
+        set_bits(&mut eyes, 21, 4, self.eyes.x_spacing)?;
+        set_bits(&mut eyes, 25, 5, self.eyes.y_position)?;
+
+        let mut eyebrows = mii.read_u32(0x38);
+        set_bits(&mut eyebrows, 0, 5, self.eyebrows.style)?;
+        set_bits(&mut eyebrows, 5, 3, self.eyebrows.color)?;
+        set_bits(&mut eyebrows, 8, 4, self.eyebrows.scale)?;
+        set_bits(&mut eyebrows, 12, 3, self.eyebrows.y_scale)?;
+        set_bits(&mut eyebrows, 16, 4, self.eyebrows.rotation)?;
+        set_bits(&mut eyebrows, 21, 4, self.eyebrows.x_spacing)?;
+        set_bits(&mut eyebrows, 25, 5, self.eyebrows.y_position)?;
+
+        let mut nose = mii.read_u16(0x3c);
+        set_bits(&mut nose, 0, 5, self.nose.style)?;
+        set_bits(&mut nose, 5, 4, self.nose.scale)?;
+        set_bits(&mut nose, 9, 5, self.nose.y_position)?;
+
+        let mut mouth = mii.read_u16(0x3e);
+        set_bits(&mut mouth, 0, 6, self.mouth.style)?;
+        set_bits(&mut mouth, 6, 3, self.mouth.color)?;
+        set_bits(&mut mouth, 9, 4, self.mouth.scale)?;
+        set_bits(&mut mouth, 13, 3, self.mouth.y_scale)?;
+
+        let mut mouth_and_mustache = mii.read_u16(0x40);
+        set_bits(&mut mouth_and_mustache, 0, 5, self.mouth.y_position)?;
+        set_bits(&mut mouth_and_mustache, 5, 3, self.mustache.style)?;
+
+        let mut beard = mii.read_u16(0x42);
+        set_bits(&mut beard, 0, 3, self.beard.style)?;
+        set_bits(&mut beard, 3, 3, self.beard.color)?;
+        set_bits(&mut beard, 6, 4, self.mustache.scale)?;
+        set_bits(&mut beard, 10, 5, self.mustache.y_position)?;
+
+        let mut glasses = mii.read_u16(0x44);
+        set_bits(&mut glasses, 0, 4, self.glasses.style)?;
+        set_bits(&mut glasses, 4, 3, self.glasses.color)?;
+        set_bits(&mut glasses, 7, 4, self.glasses.scale)?;
+        set_bits(&mut glasses, 11, 5, self.glasses.y_position)?;
+
+        let mut mole = mii.read_u16(0x46);
+        set_flag(&mut mole, 0, self.mole.enabled)?;
+        set_bits(&mut mole, 1, 4, self.mole.scale)?;
+        set_bits(&mut mole, 5, 5, self.mole.x_position)?;
+        set_bits(&mut mole, 10, 5, self.mole.y_position)?;
+
+        mii.raw[0] = self.version;
+        mii.raw[1] = flags as u8;
+        mii.raw[2] = slot as u8;
+        mii.raw[3] = origin as u8;
+        mii.raw[0x4..0xc].copy_from_slice(&self.system_id);
+        mii.raw[0xc..0x10].copy_from_slice(&mii_id.to_be_bytes());
+        mii.raw[0x10..0x16].copy_from_slice(&self.creator_mac);
+        mii.write_u16(0x18, profile);
+        mii.write_name(NAME_OFFSET, &self.name)?;
+        mii.raw[0x2e] = self.height;
+        mii.raw[0x2f] = self.build;
+        mii.raw[0x30] = face as u8;
+        mii.raw[0x31] = face_details as u8;
+        mii.raw[0x32] = self.hair.style;
+        mii.raw[0x33] = hair as u8;
+        mii.write_u32(0x34, eyes);
+        mii.write_u32(0x38, eyebrows);
+        mii.write_u16(0x3c, nose);
+        mii.write_u16(0x3e, mouth);
+        mii.write_u16(0x40, mouth_and_mustache);
+        mii.write_u16(0x42, beard);
+        mii.write_u16(0x44, glasses);
+        mii.write_u16(0x46, mole);
+        mii.write_name(CREATOR_NAME_OFFSET, &self.creator_name)?;
+        mii.update_checksum();
+
+        Ok(mii)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_mii() -> Mii {
+        let mut raw = [0u8; 96];
+
+        for (index, byte) in raw.iter_mut().enumerate() {
+            *byte = (index as u8).wrapping_mul(0x9d).wrapping_add(0x3b);
+        }
+
+        for (index, character) in "Test".encode_utf16().enumerate() {
+            raw[NAME_OFFSET + index * 2..NAME_OFFSET + index * 2 + 2]
+                .copy_from_slice(&character.to_le_bytes());
+        }
+        raw[NAME_OFFSET + 8..NAME_OFFSET + 10].fill(0);
+
+        for (index, character) in "Maker".encode_utf16().enumerate() {
+            raw[CREATOR_NAME_OFFSET + index * 2..CREATOR_NAME_OFFSET + index * 2 + 2]
+                .copy_from_slice(&character.to_le_bytes());
+        }
+        raw[CREATOR_NAME_OFFSET + 10..CREATOR_NAME_OFFSET + 12].fill(0);
+
+        let mut mii = Mii::new(raw);
+        mii.update_checksum();
+        mii
+    }
+
+    const QR_PAYLOAD: [u8; MII_QR_PAYLOAD_SIZE] = [
+        0x97, 0x34, 0xd1, 0x6e, 0x0b, 0xa8, 0x45, 0xe2, 0x4c, 0x57, 0x8c, 0xce, 0x10, 0x48, 0x6d,
+        0x68, 0x71, 0x74, 0x91, 0xad, 0x73, 0x16, 0xb4, 0x19, 0x97, 0xa9, 0x96, 0x5e, 0xe3, 0x51,
+        0xaa, 0xf4, 0x5f, 0xce, 0xa5, 0x61, 0x95, 0x00, 0x11, 0x45, 0x3f, 0x07, 0x30, 0xde, 0x90,
+        0xd2, 0x50, 0x6b, 0x68, 0x3e, 0x7b, 0x2f, 0x80, 0x0f, 0x88, 0x17, 0x11, 0x66, 0x00, 0x4a,
+        0x9f, 0xc9, 0x31, 0x60, 0x73, 0x7e, 0x32, 0x91, 0xec, 0x2b, 0x58, 0x09, 0xf7, 0xd2, 0xb7,
+        0x87, 0x48, 0x4c, 0x94, 0x91, 0x4d, 0x4e, 0x7c, 0xf7, 0x3c, 0x0f, 0x0b, 0x0f, 0xd1, 0x37,
+        0x75, 0x47, 0xa0, 0xb8, 0x5f, 0x5f, 0xff, 0x65, 0x34, 0x49, 0x02, 0x96, 0x30, 0xbf, 0x01,
+        0x72, 0x21, 0x58, 0x60, 0xbe, 0xd4, 0x9a,
+    ];
+
+    mod crc16 {
+        use super::*;
+
+        #[test]
+        fn should_calculate_the_checksum() {
+            assert_eq!(crc16(b"123456789"), 0x31c3);
+        }
+    }
+
+    mod from_qr_payload {
+        use super::*;
+
+        #[test]
+        fn should_decrypt_the_mii() {
+            let result = Mii::from_qr_payload(&QR_PAYLOAD).unwrap();
+            assert_eq!(result, create_mii());
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_payload_was_modified() {
+            let mut payload = QR_PAYLOAD;
+            payload[0x20] ^= 1;
+
+            let result = Mii::from_qr_payload(&payload);
+
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_payload_is_the_wrong_size() {
+            let result = Mii::from_qr_payload(&QR_PAYLOAD[..0x6f]);
+            assert_eq!(result, Err(error::invalid_size()));
+        }
+    }
+
+    mod to_qr_payload {
+        use super::*;
+
+        #[test]
+        fn should_encrypt_the_mii() {
+            let result = create_mii().to_qr_payload().unwrap();
+            assert_eq!(result, QR_PAYLOAD);
+        }
+    }
+
+    mod decode {
+        use super::*;
+
+        #[test]
+        fn should_decode_the_mii() {
+            let mii_data = MiiData::decode(&create_mii()).unwrap();
+
+            assert_eq!(mii_data.version, 0x3b);
+            assert!(!mii_data.copyable);
+            assert!(!mii_data.profanity);
+            assert_eq!(mii_data.region_lock, 0x2);
+            assert_eq!(mii_data.character_set, 0x1);
+            assert_eq!(mii_data.page_index, 0x5);
+            assert_eq!(mii_data.slot_index, 0x7);
+            assert_eq!(mii_data.name, "Test");
+            assert_eq!(mii_data.creator_name, "Maker");
+            assert_eq!(mii_data.height, 0x71);
+            assert_eq!(mii_data.build, 0x0e);
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_checksum_is_invalid() {
+            let mut raw = create_mii().as_bytes();
+            raw[0x2e] ^= 1;
+
+            let result = MiiData::decode(&Mii::new(raw));
+
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+    }
+
+    mod encode {
+        use super::*;
+
+        #[test]
+        fn should_round_trip_the_mii() {
+            let mii = create_mii();
+            let result = MiiData::decode(&mii).unwrap().encode().unwrap();
+            assert_eq!(result.as_bytes(), mii.as_bytes());
+        }
+
+        #[test]
+        fn should_update_fields_and_regenerate_the_checksum() {
+            let mut mii_data = MiiData::decode(&create_mii()).unwrap();
+            mii_data.name = String::from("Renamed");
+            mii_data.eyes.rotation = 0x1f;
+            mii_data.favorite = true;
+
+            let mii = mii_data.encode().unwrap();
+            let result = MiiData::decode(&mii).unwrap();
+
+            assert!(mii.has_valid_checksum());
+            assert_eq!(result.name, "Renamed");
+            assert_eq!(result.eyes.rotation, 0x1f);
+            assert!(result.favorite);
+            assert_eq!(result.eyes.style, mii_data.eyes.style);
+        }
+
+        #[test]
+        fn should_return_an_error_if_a_field_is_too_large() {
+            let mut mii_data = MiiData::decode(&create_mii()).unwrap();
+            mii_data.birth_month = 0x10;
+
+            assert_eq!(mii_data.encode(), Err(error::invalid_value()));
+        }
+
+        #[test]
+        fn should_return_an_error_if_a_name_is_too_long() {
+            let mut mii_data = MiiData::decode(&create_mii()).unwrap();
+            mii_data.creator_name = String::from("Much too long");
+
+            assert_eq!(mii_data.encode(), Err(error::invalid_size()));
+        }
+    }
+}
@@ -0,0 +1,545 @@
+mod client;
+pub use client::*;
+
+mod mii;
+pub use mii::*;
+
+mod utf16_string;
+pub use utf16_string::*;
+
+pub mod save;
+pub mod server;
+
+use crate::{
+    crypto::sha1,
+    res::{error, CtrResult, ResultCode},
+    time::SystemTimestamp,
+    TitleId,
+};
+use alloc::{format, str, string::String, vec::Vec};
+use core::{
+    convert::{TryFrom, TryInto},
+    mem,
+};
+use no_std_io::{
+    Cursor, EndianRead, EndianWrite, ReadOutput, StreamContainer, StreamReader, StreamWriter,
+};
+use num_enum::IntoPrimitive;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(C)]
+pub struct NatProperties {
+    unk1: u8,
+    unk2: u8,
+    unk3: u32,
+}
+
+impl NatProperties {
+    pub fn new(unk1: u8, unk2: u8, unk3: u32) -> Self {
+        Self { unk1, unk2, unk3 }
+    }
+
+    pub fn get_unk1(&self) -> u8 {
+        if self.unk1 < 3 {
+            self.unk1
+        } else {
+            0
+        }
+    }
+
+    pub fn get_unk2(&self) -> u8 {
+        if self.unk2 < 3 {
+            self.unk2
+        } else {
+            0
+        }
+    }
+
+    pub fn get_unk3(&self) -> u32 {
+        self.unk3
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, EndianRead, EndianWrite)]
+#[repr(C)]
+pub struct ScrambledFriendCode {
+    pub friend_code: u64,
+    pub xor_key: u16,
+    pub unk: u16,
+}
+
+impl ScrambledFriendCode {
+    pub fn new(friend_code: u64, xor_key: u16) -> Self {
+        Self {
+            friend_code: Self::xor_friend_code(friend_code, xor_key),
+            xor_key,
+            unk: 0,
+        }
+    }
+
+    fn expand_xor_key(xor_key: u16) -> u64 {
+        let _xor_key: u64 = xor_key as u64;
+        _xor_key | (_xor_key << 16) | (_xor_key << 32) | (_xor_key << 48)
+    }
+
+    fn xor_friend_code(friend_code: u64, xor_key: u16) -> u64 {
+        friend_code ^ Self::expand_xor_key(xor_key)
+    }
+
+    pub fn get_unscrambled_friend_code(&self) -> u64 {
+        Self::xor_friend_code(self.friend_code, self.xor_key)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, EndianRead, EndianWrite)]
+#[repr(C)]
+pub struct FriendPresence {
+    pub join_availability_flag: u32,
+    pub match_make_system_type: u32,
+    pub join_game_id: u32,
+    pub join_game_mode: u32,
+    pub owner_principal_id: u32,
+    pub join_group_id: u32,
+    pub application_arg: [u8; 20],
+    pub unk: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EndianRead, EndianWrite)]
+#[repr(C)]
+pub struct ExpandedFriendPresence {
+    pub join_availability_flag: u32,
+    pub match_make_system_type: u32,
+    pub join_game_id: u32,
+    pub join_game_mode: u32,
+    pub owner_principal_id: u32,
+    pub join_group_id: u32,
+    pub application_arg: [u8; 20],
+    pub game_description: GameDescription,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EndianRead, EndianWrite)]
+#[repr(C)]
+pub struct Mii {
+    raw: [u8; 96],
+}
+
+impl Default for Mii {
+    fn default() -> Self {
+        Mii { raw: [0; 96] }
+    }
+}
+
+impl Mii {
+    pub fn new(bytes: [u8; 96]) -> Self {
+        Self { raw: bytes }
+    }
+
+    pub fn as_bytes(&self) -> [u8; 96] {
+        self.raw
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CharacterSet {
+    JapanUsaEuropeAustralia = 0,
+    Korea = 1,
+    China = 2,
+    Taiwan = 3,
+    None = 0xff,
+}
+
+impl CharacterSet {
+    /// Returns true if the character set's system font can display the character.
+    /// This approximates the font coverage with unicode blocks.
+    /// [CharacterSet::None] doesn't restrict characters.
+    pub fn supports(&self, character: char) -> bool {
+        let code_point = character as u32;
+
+        if code_point == 0 {
+            return false;
+        }
+
+        if *self == CharacterSet::None {
+            return true;
+        }
+
+        let is_common = matches!(
+            code_point,
+            0x20..=0x7e
+                | 0xa0..=0x17f
+                | 0x370..=0x4ff
+                | 0x2000..=0x206f
+                | 0x2100..=0x21ff
+                | 0x2460..=0x24ff
+                | 0x25a0..=0x266f
+                | 0x3000..=0x303f
+                | 0xe000..=0xf8ff
+                | 0xff00..=0xffef
+        );
+        let is_kana = matches!(code_point, 0x3040..=0x30ff);
+        let is_cjk = matches!(code_point, 0x4e00..=0x9fff);
+
+        is_common
+            || match self {
+                CharacterSet::JapanUsaEuropeAustralia => is_kana || is_cjk,
+                CharacterSet::Korea => {
+                    matches!(code_point, 0x1100..=0x11ff | 0x3130..=0x318f | 0xac00..=0xd7a3)
+                }
+                CharacterSet::China => is_cjk,
+                CharacterSet::Taiwan => is_cjk || matches!(code_point, 0x3100..=0x312f),
+                CharacterSet::None => true,
+            }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct TrivialCharacterSet {
+    raw: u8,
+}
+
+impl TrivialCharacterSet {
+    pub fn get_character_set(&self) -> CharacterSet {
+        match self.raw {
+            0 => CharacterSet::JapanUsaEuropeAustralia,
+            1 => CharacterSet::Korea,
+            2 => CharacterSet::China,
+            3 => CharacterSet::Taiwan,
+            _ => CharacterSet::None,
+        }
+    }
+}
+
+impl Default for TrivialCharacterSet {
+    fn default() -> Self {
+        Self {
+            raw: CharacterSet::None as u8,
+        }
+    }
+}
+
+impl From<CharacterSet> for TrivialCharacterSet {
+    fn from(character_set: CharacterSet) -> Self {
+        Self {
+            raw: character_set as u8,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, EndianRead, EndianWrite)]
+#[repr(C)]
+pub struct FriendKey {
+    pub principal_id: u32,
+    pub padding: u32,
+    pub local_friend_code: u64,
+}
+
+impl FriendKey {
+    /// Friend codes are 12 decimal digits, which leaves 7 bits for the checksum above the principal id.
+    const MAX_FRIEND_CODE: u64 = 999_999_999_999;
+
+    /// Creates a friend key without a local friend code.
+    /// The local friend code comes from the friend's [LocalFriendCodeSeed](crate::formats::local_friend_code_seed::LocalFriendCodeSeed),
+    /// so it can't be derived from the principal id.  Use [get_friend_code](Self::get_friend_code) for the friend code.
+    pub fn from_principal_id(principal_id: u32) -> Self {
+        Self {
+            principal_id,
+            padding: 0,
+            local_friend_code: 0,
+        }
+    }
+
+    /// Creates a friend key from a friend code, returning an error if the friend code is invalid.
+    /// The local friend code is left as 0, like [from_principal_id](Self::from_principal_id).
+    pub fn from_friend_code(friend_code: u64) -> CtrResult<Self> {
+        let principal_id = Self::principal_id_from_friend_code(friend_code)?;
+        Ok(Self::from_principal_id(principal_id))
+    }
+
+    /// Computes the friend code for a principal id.
+    /// The checksum is the top 7 bits of the first byte of the principal id's SHA-1 hash.
+    pub fn friend_code_from_principal_id(principal_id: u32) -> u64 {
+        let checksum = sha1(&principal_id.to_le_bytes())[0] >> 1;
+        ((checksum as u64) << 32) | principal_id as u64
+    }
+
+    /// Validates a friend code's checksum and returns its principal id.
+    pub fn principal_id_from_friend_code(friend_code: u64) -> CtrResult<u32> {
+        let principal_id = friend_code as u32;
+
+        if friend_code > Self::MAX_FRIEND_CODE
+            || Self::friend_code_from_principal_id(principal_id) != friend_code
+        {
+            return Err(error::invalid_value());
+        }
+
+        Ok(principal_id)
+    }
+
+    /// Parses a user entered friend code, such as `1288-4901-8881` or `128849018881`.
+    /// Dashes and spaces between digits are ignored.
+    pub fn parse_friend_code(text: &str) -> CtrResult<Self> {
+        let mut digit_count = 0;
+        let mut friend_code: u64 = 0;
+
+        for character in text.trim().chars() {
+            match character {
+                '-' | ' ' => continue,
+                '0'..='9' => {
+                    digit_count += 1;
+                    if digit_count > 12 {
+                        return Err(error::invalid_value());
+                    }
+                    friend_code = friend_code * 10 + (character as u64 - '0' as u64);
+                }
+                _ => return Err(error::invalid_value()),
+            }
+        }
+
+        if digit_count != 12 {
+            return Err(error::invalid_value());
+        }
+
+        Self::from_friend_code(friend_code)
+    }
+
+    pub fn get_friend_code(&self) -> u64 {
+        Self::friend_code_from_principal_id(self.principal_id)
+    }
+
+    /// Formats the friend code as `XXXX-XXXX-XXXX`.
+    pub fn get_formatted_friend_code(&self) -> String {
+        let friend_code = self.get_friend_code();
+        format!(
+            "{:04}-{:04}-{:04}",
+            friend_code / 100_000_000,
+            (friend_code / 10_000) % 10_000,
+            friend_code % 10_000
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, EndianRead, EndianWrite)]
+#[repr(C)]
+pub struct GameKey {
+    pub title_id: TitleId,
+    pub version: u32,
+    pub unk: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, EndianRead, EndianWrite)]
+#[repr(C)]
+pub struct FriendProfile {
+    pub region: u8,
+    pub country: u8,
+    pub area: u8,
+    pub language: u8,
+    pub platform: u8,
+    pub padding: [u8; 3],
+}
+
+impl From<FriendProfile> for [u8; 5] {
+    fn from(profile: FriendProfile) -> Self {
+        [
+            profile.region,
+            profile.country,
+            profile.area,
+            profile.language,
+            profile.platform,
+        ]
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EndianRead, EndianWrite)]
+#[repr(C)]
+pub struct SomeFriendThing {
+    pub friend_profile: FriendProfile,
+    pub favorite_game: GameKey,
+    pub unk2: u32,
+    pub comment: FriendComment,
+    pub unk3: u16, // padding?
+    pub last_online: SystemTimestamp,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EndianRead, EndianWrite)]
+#[repr(C)]
+pub struct FriendInfo {
+    pub friend_key: FriendKey,
+    pub some_timestamp: SystemTimestamp,
+    pub friend_relationship: u8,
+    pub unk1: [u8; 3], // padding?
+    pub unk2: u32,
+    pub unk3: SomeFriendThing,
+    pub screen_name: ScreenName,
+    pub character_set: TrivialCharacterSet,
+    pub unk4: u8, // padding?
+    pub mii: Mii,
+}
+
+#[derive(IntoPrimitive)]
+#[repr(u8)]
+pub enum NotificationType {
+    UserWentOnline = 1,
+    UserWentOffline = 2,
+    FriendWentOnline = 3,
+    FriendUpdatedPresence = 4,
+    FriendUpdatedMii = 5,
+    FriendUpdatedProfile = 6,
+    FriendWentOffline = 7,
+    FriendRegisteredUser = 8,
+    FriendSentInvitation = 9,
+}
+
+impl TryFrom<u8> for NotificationType {
+    type Error = ResultCode;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::UserWentOnline),
+            2 => Ok(Self::UserWentOffline),
+            3 => Ok(Self::FriendWentOnline),
+            4 => Ok(Self::FriendUpdatedPresence),
+            5 => Ok(Self::FriendUpdatedMii),
+            6 => Ok(Self::FriendUpdatedProfile),
+            7 => Ok(Self::FriendWentOffline),
+            8 => Ok(Self::FriendRegisteredUser),
+            9 => Ok(Self::FriendSentInvitation),
+            _ => Err(error::invalid_enum_value()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, EndianRead, EndianWrite)]
+#[repr(C)]
+pub struct NotificationEvent {
+    notification_type: u8,
+    padding: [u8; 7],
+    friend_key: FriendKey,
+}
+
+impl NotificationEvent {
+    pub fn new(notification_type: NotificationType, friend_key: FriendKey) -> Self {
+        Self {
+            notification_type: notification_type.into(),
+            padding: [0; 7],
+            friend_key,
+        }
+    }
+
+    /// Returns the notification type if it's a recognized notification type, otherwise returns None.
+    pub fn get_known_notification_type(&self) -> Option<NotificationType> {
+        NotificationType::try_from(self.notification_type).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod friend_code_from_principal_id {
+        use super::*;
+
+        #[test]
+        fn should_compute_friend_codes() {
+            assert_eq!(FriendKey::friend_code_from_principal_id(1), 128849018881);
+            assert_eq!(
+                FriendKey::friend_code_from_principal_id(0x12345678),
+                412622280312
+            );
+            assert_eq!(
+                FriendKey::friend_code_from_principal_id(0xffffffff),
+                468151435263
+            );
+        }
+    }
+
+    mod principal_id_from_friend_code {
+        use super::*;
+
+        #[test]
+        fn should_return_the_principal_id() {
+            assert_eq!(
+                FriendKey::principal_id_from_friend_code(412622280312),
+                Ok(0x12345678)
+            );
+        }
+
+        #[test]
+        fn should_return_an_error_for_a_bad_checksum() {
+            assert_eq!(
+                FriendKey::principal_id_from_friend_code(412622280313),
+                Err(error::invalid_value())
+            );
+        }
+
+        #[test]
+        fn should_return_an_error_for_too_many_digits() {
+            assert_eq!(
+                FriendKey::principal_id_from_friend_code(1_000_000_000_000),
+                Err(error::invalid_value())
+            );
+        }
+    }
+
+    mod parse_friend_code {
+        use super::*;
+
+        #[test]
+        fn should_parse_formatted_friend_codes() {
+            let friend_key = FriendKey::parse_friend_code("1288-4901-8881").unwrap();
+            assert_eq!(friend_key, FriendKey::from_principal_id(1));
+        }
+
+        #[test]
+        fn should_parse_unformatted_friend_codes() {
+            let friend_key = FriendKey::parse_friend_code(" 412622280312 ").unwrap();
+            assert_eq!(friend_key.principal_id, 0x12345678);
+            assert_eq!(friend_key.local_friend_code, 0);
+            assert_eq!(friend_key.get_friend_code(), 412622280312);
+        }
+
+        #[test]
+        fn should_return_an_error_for_the_wrong_number_of_digits() {
+            assert_eq!(
+                FriendKey::parse_friend_code("1288-4901-888"),
+                Err(error::invalid_value())
+            );
+            assert_eq!(
+                FriendKey::parse_friend_code("1288-4901-88810"),
+                Err(error::invalid_value())
+            );
+        }
+
+        #[test]
+        fn should_return_an_error_for_invalid_characters() {
+            assert_eq!(
+                FriendKey::parse_friend_code("1288-4901-888a"),
+                Err(error::invalid_value())
+            );
+        }
+
+        #[test]
+        fn should_return_an_error_for_a_bad_checksum() {
+            assert_eq!(
+                FriendKey::parse_friend_code("1288-4901-8882"),
+                Err(error::invalid_value())
+            );
+        }
+    }
+
+    mod get_formatted_friend_code {
+        use super::*;
+
+        #[test]
+        fn should_format_the_friend_code() {
+            assert_eq!(
+                FriendKey::from_principal_id(1).get_formatted_friend_code(),
+                "1288-4901-8881"
+            );
+            assert_eq!(
+                FriendKey::from_principal_id(0xffffffff).get_formatted_friend_code(),
+                "4681-5143-5263"
+            );
+        }
+    }
+}
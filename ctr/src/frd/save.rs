@@ -0,0 +1,608 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/Friend_Services) for the documentation
+*/
+
+//! Parsers and writers for the friends module's system save (0x00010032).
+//!
+//! Every file starts with a 0x10 byte [SaveHeader], which is kept as-is.
+//! The files store the same structures frd:u and frd:a use, at these offsets:
+//!
+//! | File         | Offset | Size  | Field                                      |
+//! |--------------|--------|-------|--------------------------------------------|
+//! | `mydata`     | 0x10   | 0x8   | [FriendProfile]                            |
+//! |              | 0x18   | 0x10  | Favorite game [GameKey]                    |
+//! |              | 0x28   | 0x22  | Personal comment                           |
+//! |              | 0x4a   | 0x16  | Screen name                                |
+//! |              | 0x60   | 0x60  | [Mii]                                      |
+//! | `friendlist` | 0x10   | 0xe0  | [FriendInfo], repeated up to 100 times     |
+//! | `config`     | 0x10   | 0x1   | Current local account id                   |
+//! |              | 0x11   | 0x3   | Privacy settings, in frd:a's order         |
+//! | `account`    | 0x10   | 0x4   | Local account id                           |
+//! |              | 0x14   | 0x10  | [FriendKey]                                |
+//! |              | 0x24   | 0x4   | NC principal id                            |
+//!
+//! Anything after the known fields is kept as raw bytes, so a parsed file is written back exactly as it was read.
+
+use super::{FriendComment, FriendInfo, FriendKey, FriendProfile, GameKey, Mii, ScreenName};
+use crate::{
+    formats::read_exact,
+    fs::FileOps,
+    res::{error, CtrResult},
+};
+use alloc::{vec, vec::Vec};
+use core::mem;
+use no_std_io::{EndianRead, EndianWrite, Reader, Writer};
+
+pub const MYDATA_PATH: &str = "/1/mydata";
+pub const FRIENDLIST_PATH: &str = "/1/friendlist";
+pub const CONFIG_PATH: &str = "/1/config";
+pub const ACCOUNT_PATH: &str = "/1/account";
+
+pub const MAX_FRIEND_COUNT: usize = 100;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct SaveHeader {
+    pub magic: u32,
+    pub version: u32,
+    pub unk: [u8; 8],
+}
+
+const SAVE_HEADER_SIZE: usize = mem::size_of::<SaveHeader>();
+
+const MY_DATA_PROFILE_OFFSET: usize = 0x10;
+const MY_DATA_FAVORITE_GAME_OFFSET: usize = 0x18;
+const MY_DATA_COMMENT_OFFSET: usize = 0x28;
+const MY_DATA_SCREEN_NAME_OFFSET: usize = 0x4a;
+const MY_DATA_MII_OFFSET: usize = 0x60;
+const MY_DATA_SIZE: usize = 0xc0;
+
+const FRIEND_LIST_ENTRIES_OFFSET: usize = SAVE_HEADER_SIZE;
+
+const CONFIG_LOCAL_ACCOUNT_ID_OFFSET: usize = 0x10;
+const CONFIG_IS_PUBLIC_MODE_OFFSET: usize = 0x11;
+const CONFIG_IS_SHOW_GAME_NAME_OFFSET: usize = 0x12;
+const CONFIG_IS_SHOW_PLAYED_GAME_OFFSET: usize = 0x13;
+const CONFIG_SIZE: usize = 0x14;
+
+const ACCOUNT_LOCAL_ACCOUNT_ID_OFFSET: usize = 0x10;
+const ACCOUNT_FRIEND_KEY_OFFSET: usize = 0x14;
+const ACCOUNT_NC_PRINCIPAL_ID_OFFSET: usize = 0x24;
+const ACCOUNT_SIZE: usize = 0x28;
+
+/// Returns the bytes after a file's known fields, or an error if the known fields don't fit.
+fn get_extra(bytes: &[u8], known_size: usize) -> CtrResult<Vec<u8>> {
+    bytes
+        .get(known_size..)
+        .map(|extra| extra.to_vec())
+        .ok_or_else(error::invalid_size)
+}
+
+/// A file in the friends save.
+pub trait FriendSaveFile: Sized {
+    fn parse(bytes: &[u8]) -> CtrResult<Self>;
+
+    fn to_bytes(&self) -> CtrResult<Vec<u8>>;
+
+    fn read(file: &impl FileOps) -> CtrResult<Self> {
+        Self::parse(&read_exact(file, 0, file.size()?)?)
+    }
+}
+
+/// The current user's data.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MyData {
+    pub header: SaveHeader,
+    pub profile: FriendProfile,
+    pub favorite_game: GameKey,
+    pub comment: FriendComment,
+    pub screen_name: ScreenName,
+    pub mii: Mii,
+    pub extra: Vec<u8>,
+}
+
+impl FriendSaveFile for MyData {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let extra = get_extra(bytes, MY_DATA_SIZE)?;
+
+        Ok(Self {
+            header: bytes.read_le(0)?,
+            profile: bytes.read_le(MY_DATA_PROFILE_OFFSET)?,
+            favorite_game: bytes.read_le(MY_DATA_FAVORITE_GAME_OFFSET)?,
+            comment: bytes.read_le(MY_DATA_COMMENT_OFFSET)?,
+            screen_name: bytes.read_le(MY_DATA_SCREEN_NAME_OFFSET)?,
+            mii: bytes.read_le(MY_DATA_MII_OFFSET)?,
+            extra,
+        })
+    }
+
+    fn to_bytes(&self) -> CtrResult<Vec<u8>> {
+        let mut result = vec![0; MY_DATA_SIZE];
+        result.write_le(0, &self.header)?;
+        result.write_le(MY_DATA_PROFILE_OFFSET, &self.profile)?;
+        result.write_le(MY_DATA_FAVORITE_GAME_OFFSET, &self.favorite_game)?;
+        result.write_le(MY_DATA_COMMENT_OFFSET, &self.comment)?;
+        result.write_le(MY_DATA_SCREEN_NAME_OFFSET, &self.screen_name)?;
+        result.write_le(MY_DATA_MII_OFFSET, &self.mii)?;
+
+        result.extend_from_slice(&self.extra);
+        Ok(result)
+    }
+}
+
+/// The friend list, which is a header followed by up to [MAX_FRIEND_COUNT] entries.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FriendList {
+    pub header: SaveHeader,
+    pub friends: Vec<FriendInfo>,
+}
+
+impl FriendList {
+    pub fn find(&self, principal_id: u32) -> Option<&FriendInfo> {
+        self.friends
+            .iter()
+            .find(|friend| friend.friend_key.principal_id == principal_id)
+    }
+
+    /// Adds a friend, replacing any friend with the same principal id.
+    pub fn insert(&mut self, friend: FriendInfo) -> CtrResult {
+        let principal_id = friend.friend_key.principal_id;
+
+        if let Some(existing) = self
+            .friends
+            .iter_mut()
+            .find(|existing| existing.friend_key.principal_id == principal_id)
+        {
+            *existing = friend;
+            return Ok(());
+        }
+
+        if self.friends.len() >= MAX_FRIEND_COUNT {
+            return Err(error::too_large());
+        }
+
+        self.friends.push(friend);
+        Ok(())
+    }
+
+    /// Removes a friend, returning the removed entry if it existed.
+    pub fn remove(&mut self, principal_id: u32) -> Option<FriendInfo> {
+        let index = self
+            .friends
+            .iter()
+            .position(|friend| friend.friend_key.principal_id == principal_id)?;
+        Some(self.friends.remove(index))
+    }
+
+    /// Returns the keys of every friend, in list order.
+    pub fn friend_keys(&self) -> Vec<FriendKey> {
+        self.friends
+            .iter()
+            .map(|friend| friend.friend_key)
+            .collect()
+    }
+}
+
+impl FriendSaveFile for FriendList {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let entry_size = FriendInfo::default().get_size();
+        let entries = bytes
+            .get(FRIEND_LIST_ENTRIES_OFFSET..)
+            .ok_or_else(error::invalid_size)?;
+
+        if entries.len() % entry_size != 0 || entries.len() / entry_size > MAX_FRIEND_COUNT {
+            return Err(error::invalid_size());
+        }
+
+        let friends = entries
+            .chunks_exact(entry_size)
+            .map(|entry| Ok(entry.read_le(0)?))
+            .collect::<CtrResult<Vec<FriendInfo>>>()?;
+
+        Ok(Self {
+            header: bytes.read_le(0)?,
+            friends,
+        })
+    }
+
+    fn to_bytes(&self) -> CtrResult<Vec<u8>> {
+        if self.friends.len() > MAX_FRIEND_COUNT {
+            return Err(error::too_large());
+        }
+
+        let entry_size = FriendInfo::default().get_size();
+        let mut result = vec![0; FRIEND_LIST_ENTRIES_OFFSET + self.friends.len() * entry_size];
+        result.write_le(0, &self.header)?;
+
+        for (index, friend) in self.friends.iter().enumerate() {
+            result.write_le(FRIEND_LIST_ENTRIES_OFFSET + index * entry_size, friend)?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// The friends module's settings.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FriendConfig {
+    pub header: SaveHeader,
+    /// The local account that's loaded when the friends module starts.
+    pub local_account_id: u8,
+    pub is_public_mode: bool,
+    pub is_show_game_name: bool,
+    pub is_show_played_game: bool,
+    pub extra: Vec<u8>,
+}
+
+impl FriendSaveFile for FriendConfig {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let extra = get_extra(bytes, CONFIG_SIZE)?;
+
+        Ok(Self {
+            header: bytes.read_le(0)?,
+            local_account_id: bytes.read_le(CONFIG_LOCAL_ACCOUNT_ID_OFFSET)?,
+            is_public_mode: bytes.read_le::<u8>(CONFIG_IS_PUBLIC_MODE_OFFSET)? != 0,
+            is_show_game_name: bytes.read_le::<u8>(CONFIG_IS_SHOW_GAME_NAME_OFFSET)? != 0,
+            is_show_played_game: bytes.read_le::<u8>(CONFIG_IS_SHOW_PLAYED_GAME_OFFSET)? != 0,
+            extra,
+        })
+    }
+
+    fn to_bytes(&self) -> CtrResult<Vec<u8>> {
+        let mut result = vec![0; CONFIG_SIZE];
+        result.write_le(0, &self.header)?;
+        result.write_le(CONFIG_LOCAL_ACCOUNT_ID_OFFSET, &self.local_account_id)?;
+        result.write_le(CONFIG_IS_PUBLIC_MODE_OFFSET, &(self.is_public_mode as u8))?;
+        result.write_le(
+            CONFIG_IS_SHOW_GAME_NAME_OFFSET,
+            &(self.is_show_game_name as u8),
+        )?;
+        result.write_le(
+            CONFIG_IS_SHOW_PLAYED_GAME_OFFSET,
+            &(self.is_show_played_game as u8),
+        )?;
+
+        result.extend_from_slice(&self.extra);
+        Ok(result)
+    }
+}
+
+/// The account used to log in to the friends server.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FriendAccount {
+    pub header: SaveHeader,
+    pub local_account_id: u32,
+    pub friend_key: FriendKey,
+    pub nc_principal_id: u32,
+    pub extra: Vec<u8>,
+}
+
+impl FriendSaveFile for FriendAccount {
+    fn parse(bytes: &[u8]) -> CtrResult<Self> {
+        let extra = get_extra(bytes, ACCOUNT_SIZE)?;
+
+        Ok(Self {
+            header: bytes.read_le(0)?,
+            local_account_id: bytes.read_le(ACCOUNT_LOCAL_ACCOUNT_ID_OFFSET)?,
+            friend_key: bytes.read_le(ACCOUNT_FRIEND_KEY_OFFSET)?,
+            nc_principal_id: bytes.read_le(ACCOUNT_NC_PRINCIPAL_ID_OFFSET)?,
+            extra,
+        })
+    }
+
+    fn to_bytes(&self) -> CtrResult<Vec<u8>> {
+        let mut result = vec![0; ACCOUNT_SIZE];
+        result.write_le(0, &self.header)?;
+        result.write_le(ACCOUNT_LOCAL_ACCOUNT_ID_OFFSET, &self.local_account_id)?;
+        result.write_le(ACCOUNT_FRIEND_KEY_OFFSET, &self.friend_key)?;
+        result.write_le(ACCOUNT_NC_PRINCIPAL_ID_OFFSET, &self.nc_principal_id)?;
+
+        result.extend_from_slice(&self.extra);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::convert::TryFrom;
+
+    fn create_header() -> SaveHeader {
+        SaveHeader {
+            magic: 0x12345678,
+            version: 1,
+            unk: [1, 2, 3, 4, 5, 6, 7, 8],
+        }
+    }
+
+    fn create_friend(principal_id: u32) -> FriendInfo {
+        let mut friend = FriendInfo {
+            friend_key: FriendKey::from_principal_id(principal_id),
+            screen_name: ScreenName::try_from("Friend").unwrap(),
+            ..Default::default()
+        };
+        friend.unk3.comment = FriendComment::try_from("Hi").unwrap();
+        friend
+    }
+
+    mod my_data {
+        use super::*;
+        use crate::TitleId;
+
+        /// A mydata file with every field written at its documented offset.
+        fn create_my_data_image() -> Vec<u8> {
+            let mut image = vec![0u8; 0xc4];
+            image[..0x10].copy_from_slice(&[
+                0x78, 0x56, 0x34, 0x12, 0x01, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+                0x07, 0x08,
+            ]);
+            // Profile: region, country, area, language and platform
+            image[0x10..0x15].copy_from_slice(&[0x01, 0x31, 0x02, 0x01, 0x02]);
+            // Favorite game: title id, version and unk
+            image[0x18..0x28].copy_from_slice(&[
+                0x00, 0x5d, 0x05, 0x00, 0x00, 0x00, 0x04, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00,
+            ]);
+            // Comment
+            image[0x28..0x2c].copy_from_slice(&[b'H', 0x00, b'i', 0x00]);
+            // Screen name
+            image[0x4a..0x4e].copy_from_slice(&[b'M', 0x00, b'e', 0x00]);
+            // Mii
+            image[0x60..0xc0].fill(0x5a);
+            // Unknown trailing data
+            image[0xc0..].fill(0xaa);
+            image
+        }
+
+        #[test]
+        fn should_parse_the_documented_layout() {
+            let result = MyData::parse(&create_my_data_image()).unwrap();
+
+            assert_eq!(result.header, create_header());
+            assert_eq!(
+                <[u8; 5]>::from(result.profile),
+                [0x01, 0x31, 0x02, 0x01, 0x02]
+            );
+            assert_eq!(
+                result.favorite_game,
+                GameKey {
+                    title_id: TitleId::new(0x0004000000055d00),
+                    version: 0x10,
+                    unk: 0,
+                }
+            );
+            assert_eq!(result.comment, FriendComment::try_from("Hi").unwrap());
+            assert_eq!(result.screen_name, ScreenName::try_from("Me").unwrap());
+            assert_eq!(result.mii, Mii::new([0x5a; 0x60]));
+            assert_eq!(result.extra, [0xaa; 4]);
+        }
+
+        #[test]
+        fn should_round_trip() {
+            let image = create_my_data_image();
+            let result = MyData::parse(&image).unwrap().to_bytes().unwrap();
+
+            assert_eq!(result, image);
+        }
+
+        #[test]
+        fn should_write_the_fields() {
+            let my_data = MyData {
+                header: create_header(),
+                screen_name: ScreenName::try_from("Me").unwrap(),
+                extra: vec![0xaa; 4],
+                ..Default::default()
+            };
+
+            let bytes = my_data.to_bytes().unwrap();
+            let result = MyData::parse(&bytes).unwrap();
+
+            assert_eq!(bytes[..4], [0x78, 0x56, 0x34, 0x12]);
+            assert_eq!(bytes[0x4a..0x4e], [b'M', 0x00, b'e', 0x00]);
+            assert_eq!(result, my_data);
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_file_is_too_small() {
+            let result = MyData::parse(&[0; 0xbf]);
+            assert_eq!(result, Err(error::invalid_size()));
+        }
+    }
+
+    mod friend_list {
+        use super::*;
+
+        #[test]
+        fn should_round_trip() {
+            let friend_list = FriendList {
+                header: create_header(),
+                friends: vec![create_friend(1), create_friend(2)],
+            };
+
+            let bytes = friend_list.to_bytes().unwrap();
+            let result = FriendList::parse(&bytes).unwrap();
+
+            assert_eq!(result, friend_list);
+            assert_eq!(result.to_bytes().unwrap(), bytes);
+        }
+
+        #[test]
+        fn should_parse_the_documented_layout() {
+            let mut image = vec![0u8; 0x10 + 2 * 0xe0];
+            image[..0x10].copy_from_slice(&[
+                0x78, 0x56, 0x34, 0x12, 0x01, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+                0x07, 0x08,
+            ]);
+            // Principal ids and relationships of each entry
+            image[0x10..0x14].copy_from_slice(&[0x01, 0x00, 0x00, 0x00]);
+            image[0x28] = 3;
+            image[0xf0..0xf4].copy_from_slice(&[0x02, 0x00, 0x00, 0x00]);
+            image[0x108] = 1;
+
+            let result = FriendList::parse(&image).unwrap();
+
+            assert_eq!(result.header, create_header());
+            assert_eq!(result.friends[0].friend_key.principal_id, 1);
+            assert_eq!(result.friends[1].friend_key.principal_id, 2);
+            assert_eq!(result.friends[0].friend_relationship, 3);
+            assert_eq!(result.friends[1].friend_relationship, 1);
+            assert_eq!(result.to_bytes().unwrap(), image);
+        }
+
+        #[test]
+        fn should_parse_an_empty_list() {
+            let bytes = FriendList::default().to_bytes().unwrap();
+            let result = FriendList::parse(&bytes).unwrap();
+
+            assert_eq!(bytes.len(), SAVE_HEADER_SIZE);
+            assert!(result.friends.is_empty());
+        }
+
+        #[test]
+        fn should_return_an_error_for_partial_entries() {
+            let mut bytes = FriendList {
+                header: create_header(),
+                friends: vec![create_friend(1)],
+            }
+            .to_bytes()
+            .unwrap();
+            bytes.pop();
+
+            let result = FriendList::parse(&bytes);
+
+            assert_eq!(result, Err(error::invalid_size()));
+        }
+
+        #[test]
+        fn should_replace_friends_with_the_same_principal_id() {
+            let mut friend_list = FriendList::default();
+            friend_list.insert(create_friend(1)).unwrap();
+            friend_list.insert(create_friend(2)).unwrap();
+
+            let mut friend = create_friend(1);
+            friend.friend_relationship = 3;
+            friend_list.insert(friend).unwrap();
+
+            assert_eq!(friend_list.friends.len(), 2);
+            assert_eq!(friend_list.find(1).unwrap().friend_relationship, 3);
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_list_is_full() {
+            let mut friend_list = FriendList::default();
+
+            for principal_id in 0..MAX_FRIEND_COUNT {
+                friend_list
+                    .insert(create_friend(principal_id as u32 + 1))
+                    .unwrap();
+            }
+
+            let result = friend_list.insert(create_friend(1000));
+
+            assert_eq!(result, Err(error::too_large()));
+        }
+
+        #[test]
+        fn should_remove_friends() {
+            let mut friend_list = FriendList::default();
+            friend_list.insert(create_friend(1)).unwrap();
+            friend_list.insert(create_friend(2)).unwrap();
+
+            let result = friend_list.remove(1).unwrap();
+
+            assert_eq!(result.friend_key.principal_id, 1);
+            assert_eq!(friend_list.friend_keys(), [FriendKey::from_principal_id(2)]);
+            assert!(friend_list.remove(1).is_none());
+        }
+    }
+
+    mod friend_config {
+        use super::*;
+
+        /// A config file with every field written at its documented offset.
+        const CONFIG_IMAGE: [u8; 0x16] = [
+            0x78, 0x56, 0x34, 0x12, 0x01, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+            0x07, 0x08, // Header
+            0x02, // Local account id
+            0x01, 0x00, 0x01, // Public mode, show game name and show played game
+            0xaa, 0xbb, // Unknown trailing data
+        ];
+
+        #[test]
+        fn should_parse_the_documented_layout() {
+            let result = FriendConfig::parse(&CONFIG_IMAGE).unwrap();
+
+            assert_eq!(
+                result,
+                FriendConfig {
+                    header: create_header(),
+                    local_account_id: 2,
+                    is_public_mode: true,
+                    is_show_game_name: false,
+                    is_show_played_game: true,
+                    extra: vec![0xaa, 0xbb],
+                }
+            );
+        }
+
+        #[test]
+        fn should_round_trip() {
+            let result = FriendConfig::parse(&CONFIG_IMAGE)
+                .unwrap()
+                .to_bytes()
+                .unwrap();
+
+            assert_eq!(result, CONFIG_IMAGE);
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_file_is_too_small() {
+            let result = FriendConfig::parse(&CONFIG_IMAGE[..0x13]);
+            assert_eq!(result, Err(error::invalid_size()));
+        }
+    }
+
+    mod friend_account {
+        use super::*;
+
+        /// An account file with every field written at its documented offset.
+        const ACCOUNT_IMAGE: [u8; 0x2a] = [
+            0x78, 0x56, 0x34, 0x12, 0x01, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+            0x07, 0x08, // Header
+            0x01, 0x00, 0x00, 0x00, // Local account id
+            0x78, 0x56, 0x34, 0x12, 0x00, 0x00, 0x00, 0x00, 0x21, 0x43, 0x65, 0x87, 0x09, 0x00,
+            0x00, 0x00, // Friend key
+            0xef, 0xbe, 0xad, 0xde, // NC principal id
+            0xcc, 0xcc, // Unknown trailing data
+        ];
+
+        #[test]
+        fn should_parse_the_documented_layout() {
+            let result = FriendAccount::parse(&ACCOUNT_IMAGE).unwrap();
+
+            assert_eq!(
+                result,
+                FriendAccount {
+                    header: create_header(),
+                    local_account_id: 1,
+                    friend_key: FriendKey {
+                        principal_id: 0x12345678,
+                        padding: 0,
+                        local_friend_code: 0x987654321,
+                    },
+                    nc_principal_id: 0xdeadbeef,
+                    extra: vec![0xcc, 0xcc],
+                }
+            );
+        }
+
+        #[test]
+        fn should_round_trip() {
+            let result = FriendAccount::parse(&ACCOUNT_IMAGE)
+                .unwrap()
+                .to_bytes()
+                .unwrap();
+
+            assert_eq!(result, ACCOUNT_IMAGE);
+        }
+    }
+}
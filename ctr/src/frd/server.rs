@@ -0,0 +1,2256 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/Friend_Services) for the documentation
+*/
+
+//! Definitions for implementing frd:u, frd:a and frd:n in a sysmodule.
+//!
+//! Implement [FrdUServer], [FrdAServer] or [FrdNServer] on a router, generate its routes
+//! with [impl_frd_u_routes](crate::impl_frd_u_routes), [impl_frd_a_routes](crate::impl_frd_a_routes)
+//! or [impl_frd_n_routes](crate::impl_frd_n_routes), then route commands with `match_ctr_route`:
+//!
+//! ```ignore
+//! impl FrdUServer for FriendSysmodule {
+//!     fn get_my_friend_key(&mut self, _session_index: usize) -> CtrResult<FriendKey> {
+//!         Ok(self.my_friend_key)
+//!     }
+//! }
+//!
+//! ctr::impl_frd_u_routes!(FriendSysmodule);
+//!
+//! impl ServiceRouter for FriendSysmodule {
+//!     fn handle_request(&mut self, service_id: usize, session_index: usize) -> CtrResult<WrittenCommand> {
+//!         match_ctr_route!(
+//!             FriendSysmodule,
+//!             service_id,
+//!             session_index,
+//!             FrdUCommand::GetMyFriendKey,
+//!             FrdUCommand::GetFriendKeyList,
+//!         )
+//!     }
+//!     // ...
+//! }
+//! ```
+//!
+//! Commands that aren't implemented return `not_implemented`.
+
+use super::{
+    FriendComment, FriendKey, FriendProfile, GameKey, Mii, ScreenName, TrivialCharacterSet,
+};
+use crate::{
+    ipc::{make_header, CurrentProcessId, Handles, PermissionBuffer, StaticBuffer},
+    res::{error, CtrResult, ResultCode},
+    sysmodule::server::Service,
+};
+use core::{convert::TryInto, mem};
+use no_std_io::{EndianRead, EndianWrite};
+use num_enum::{FromPrimitive, IntoPrimitive};
+
+/// Commands shared by frd:u and frd:a.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[repr(u16)]
+pub enum FrdUCommand {
+    #[num_enum(default)]
+    Invalid = 0x0,
+    HasLoggedIn = 0x1,
+    IsOnline = 0x2,
+    Login = 0x3,
+    Logout = 0x4,
+    GetMyFriendKey = 0x5,
+    GetMyPreference = 0x6,
+    GetMyProfile = 0x7,
+    GetMyPresence = 0x8,
+    GetMyScreenName = 0x9,
+    GetMyMii = 0xa,
+    GetMyLocalAccountId = 0xb,
+    GetMyPlayingGame = 0xc,
+    GetMyFavoriteGame = 0xd,
+    GetMyNcPrincipalId = 0xe,
+    GetMyComment = 0xf,
+    GetMyPassword = 0x10,
+    GetFriendKeyList = 0x11,
+    GetFriendPresence = 0x12,
+    GetFriendScreenName = 0x13,
+    GetFriendMii = 0x14,
+    GetFriendProfile = 0x15,
+    GetFriendRelationship = 0x16,
+    GetFriendAttributeFlags = 0x17,
+    GetFriendPlayingGame = 0x18,
+    GetFriendFavoriteGame = 0x19,
+    GetFriendInfo = 0x1a,
+    IsIncludedInFriendList = 0x1b,
+    UnscrambleLocalFriendCode = 0x1c,
+    UpdateGameModeDescription = 0x1d,
+    UpdateGameMode = 0x1e,
+    SendInvitation = 0x1f,
+    AttachToEventNotification = 0x20,
+    SetNotificationMask = 0x21,
+    GetEventNotification = 0x22,
+    GetLastResponseResult = 0x23,
+    PrincipalIdToFriendCode = 0x24,
+    FriendCodeToPrincipalId = 0x25,
+    IsValidFriendCode = 0x26,
+    ResultToErrorCode = 0x27,
+    RequestGameAuthentication = 0x28,
+    GetGameAuthenticationData = 0x29,
+    RequestServiceLocator = 0x2a,
+    GetServiceLocatorData = 0x2b,
+    DetectNatProperties = 0x2c,
+    GetNatProperties = 0x2d,
+    GetServerTimeInterval = 0x2e,
+    AllowHalfAwake = 0x2f,
+    GetServerTypes = 0x30,
+    GetFriendComment = 0x31,
+    SetClientSdkVersion = 0x32,
+    GetMyApproachContext = 0x33,
+    AddFriendWithApproach = 0x34,
+    DecryptApproachContext = 0x35,
+}
+
+impl FrdUCommand {
+    /// The header a client sends, including the number of normal and translate parameters.
+    pub fn request_header(self) -> u32 {
+        let (normal, translate) = match self {
+            Self::Invalid
+            | Self::HasLoggedIn
+            | Self::IsOnline
+            | Self::Logout
+            | Self::GetMyFriendKey
+            | Self::GetMyPreference
+            | Self::GetMyProfile
+            | Self::GetMyPresence
+            | Self::GetMyScreenName
+            | Self::GetMyMii
+            | Self::GetMyLocalAccountId
+            | Self::GetMyPlayingGame
+            | Self::GetMyFavoriteGame
+            | Self::GetMyNcPrincipalId
+            | Self::GetMyComment
+            | Self::GetLastResponseResult
+            | Self::GetGameAuthenticationData
+            | Self::GetServiceLocatorData
+            | Self::GetNatProperties
+            | Self::GetServerTimeInterval
+            | Self::GetServerTypes
+            | Self::GetMyApproachContext => (0, 0),
+            Self::Login
+            | Self::UpdateGameModeDescription
+            | Self::AttachToEventNotification
+            | Self::DetectNatProperties => (0, 2),
+            Self::GetMyPassword
+            | Self::SetNotificationMask
+            | Self::GetEventNotification
+            | Self::PrincipalIdToFriendCode
+            | Self::ResultToErrorCode
+            | Self::AllowHalfAwake => (1, 0),
+            Self::GetFriendKeyList
+            | Self::IsIncludedInFriendList
+            | Self::FriendCodeToPrincipalId
+            | Self::IsValidFriendCode => (2, 0),
+            Self::GetFriendPresence
+            | Self::GetFriendProfile
+            | Self::GetFriendRelationship
+            | Self::GetFriendAttributeFlags
+            | Self::GetFriendFavoriteGame
+            | Self::UnscrambleLocalFriendCode
+            | Self::SendInvitation
+            | Self::SetClientSdkVersion => (1, 2),
+            Self::GetFriendMii | Self::GetFriendPlayingGame => (1, 4),
+            Self::AddFriendWithApproach => (1, 6),
+            Self::GetFriendComment | Self::DecryptApproachContext => (2, 2),
+            Self::GetFriendInfo => (3, 4),
+            Self::GetFriendScreenName => (5, 2),
+            Self::RequestServiceLocator => (8, 4),
+            Self::RequestGameAuthentication => (9, 4),
+            Self::UpdateGameMode => (11, 2),
+        };
+
+        make_header(self.into(), normal, translate)
+    }
+
+    /// The header of a successful response, including the number of normal and translate parameters.
+    pub fn response_header(self) -> u32 {
+        let (normal, translate) = match self {
+            Self::Invalid
+            | Self::Login
+            | Self::Logout
+            | Self::UpdateGameModeDescription
+            | Self::UpdateGameMode
+            | Self::SendInvitation
+            | Self::AttachToEventNotification
+            | Self::SetNotificationMask
+            | Self::RequestGameAuthentication
+            | Self::RequestServiceLocator
+            | Self::DetectNatProperties
+            | Self::AllowHalfAwake
+            | Self::SetClientSdkVersion
+            | Self::AddFriendWithApproach => (1, 0),
+            Self::HasLoggedIn
+            | Self::IsOnline
+            | Self::GetMyLocalAccountId
+            | Self::GetMyNcPrincipalId
+            | Self::IsIncludedInFriendList
+            | Self::GetLastResponseResult
+            | Self::FriendCodeToPrincipalId
+            | Self::IsValidFriendCode
+            | Self::ResultToErrorCode => (2, 0),
+            Self::GetMyProfile
+            | Self::PrincipalIdToFriendCode
+            | Self::GetNatProperties
+            | Self::GetServerTimeInterval => (3, 0),
+            Self::GetMyPreference | Self::GetServerTypes => (4, 0),
+            Self::GetMyFriendKey | Self::GetMyPlayingGame | Self::GetMyFavoriteGame => (5, 0),
+            Self::GetMyScreenName => (7, 0),
+            Self::GetMyComment => (10, 0),
+            Self::GetMyMii => (0x19, 0),
+            Self::GetMyPresence
+            | Self::GetMyPassword
+            | Self::GetFriendPresence
+            | Self::GetFriendScreenName
+            | Self::GetFriendMii
+            | Self::GetFriendProfile
+            | Self::GetFriendRelationship
+            | Self::GetFriendAttributeFlags
+            | Self::GetFriendPlayingGame
+            | Self::GetFriendFavoriteGame
+            | Self::GetFriendInfo
+            | Self::UnscrambleLocalFriendCode
+            | Self::GetGameAuthenticationData
+            | Self::GetServiceLocatorData
+            | Self::GetFriendComment
+            | Self::GetMyApproachContext
+            | Self::DecryptApproachContext => (1, 2),
+            Self::GetFriendKeyList => (2, 2),
+            Self::GetEventNotification => (3, 2),
+        };
+
+        make_header(self.into(), normal, translate)
+    }
+}
+
+impl Service for FrdUCommand {
+    const ID: usize = 0;
+    const NAME: &'static str = "frd:u";
+    const MAX_SESSION_COUNT: i32 = 8;
+}
+
+/// Commands available to frd:a, which includes every frd:u command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[repr(u16)]
+pub enum FrdACommand {
+    #[num_enum(default)]
+    Invalid = 0x0,
+    HasLoggedIn = 0x1,
+    IsOnline = 0x2,
+    Login = 0x3,
+    Logout = 0x4,
+    GetMyFriendKey = 0x5,
+    GetMyPreference = 0x6,
+    GetMyProfile = 0x7,
+    GetMyPresence = 0x8,
+    GetMyScreenName = 0x9,
+    GetMyMii = 0xa,
+    GetMyLocalAccountId = 0xb,
+    GetMyPlayingGame = 0xc,
+    GetMyFavoriteGame = 0xd,
+    GetMyNcPrincipalId = 0xe,
+    GetMyComment = 0xf,
+    GetMyPassword = 0x10,
+    GetFriendKeyList = 0x11,
+    GetFriendPresence = 0x12,
+    GetFriendScreenName = 0x13,
+    GetFriendMii = 0x14,
+    GetFriendProfile = 0x15,
+    GetFriendRelationship = 0x16,
+    GetFriendAttributeFlags = 0x17,
+    GetFriendPlayingGame = 0x18,
+    GetFriendFavoriteGame = 0x19,
+    GetFriendInfo = 0x1a,
+    IsIncludedInFriendList = 0x1b,
+    UnscrambleLocalFriendCode = 0x1c,
+    UpdateGameModeDescription = 0x1d,
+    UpdateGameMode = 0x1e,
+    SendInvitation = 0x1f,
+    AttachToEventNotification = 0x20,
+    SetNotificationMask = 0x21,
+    GetEventNotification = 0x22,
+    GetLastResponseResult = 0x23,
+    PrincipalIdToFriendCode = 0x24,
+    FriendCodeToPrincipalId = 0x25,
+    IsValidFriendCode = 0x26,
+    ResultToErrorCode = 0x27,
+    RequestGameAuthentication = 0x28,
+    GetGameAuthenticationData = 0x29,
+    RequestServiceLocator = 0x2a,
+    GetServiceLocatorData = 0x2b,
+    DetectNatProperties = 0x2c,
+    GetNatProperties = 0x2d,
+    GetServerTimeInterval = 0x2e,
+    AllowHalfAwake = 0x2f,
+    GetServerTypes = 0x30,
+    GetFriendComment = 0x31,
+    SetClientSdkVersion = 0x32,
+    GetMyApproachContext = 0x33,
+    AddFriendWithApproach = 0x34,
+    DecryptApproachContext = 0x35,
+    CreateLocalAccount = 0x401,
+    DeleteLocalAccount = 0x402,
+    LoadLocalAccount = 0x403,
+    UnloadLocalAccount = 0x404,
+    HasUserData = 0x405,
+    AddFriendOnline = 0x406,
+    AddFriendOffline = 0x407,
+    SetFriendDisplayName = 0x408,
+    RemoveFriend = 0x409,
+    SetPresenceGameKey = 0x40a,
+    SetPrivacySettings = 0x40b,
+    SetMyData = 0x40c,
+    SetMyFavoriteGame = 0x40d,
+    SetMyNcPrincipalId = 0x40e,
+    SetPersonalComment = 0x40f,
+    DecrementMiiChangeCounter = 0x410,
+}
+
+impl FrdACommand {
+    pub fn request_header(self) -> u32 {
+        let (normal, translate) = match self {
+            Self::UnloadLocalAccount | Self::HasUserData | Self::DecrementMiiChangeCounter => {
+                (0, 0)
+            }
+            Self::DeleteLocalAccount | Self::LoadLocalAccount | Self::SetMyNcPrincipalId => (1, 0),
+            Self::AddFriendOnline => (1, 2),
+            Self::RemoveFriend | Self::SetPrivacySettings => (3, 0),
+            Self::CreateLocalAccount | Self::SetPresenceGameKey | Self::SetMyFavoriteGame => (4, 0),
+            Self::SetPersonalComment => (9, 0),
+            Self::SetFriendDisplayName => (10, 0),
+            Self::SetMyData => (0x20, 0),
+            Self::AddFriendOffline => (0x24, 2),
+            _ => return FrdUCommand::from(u16::from(self)).request_header(),
+        };
+
+        make_header(self.into(), normal, translate)
+    }
+
+    pub fn response_header(self) -> u32 {
+        match self {
+            Self::CreateLocalAccount
+            | Self::DeleteLocalAccount
+            | Self::LoadLocalAccount
+            | Self::UnloadLocalAccount
+            | Self::HasUserData
+            | Self::AddFriendOnline
+            | Self::AddFriendOffline
+            | Self::SetFriendDisplayName
+            | Self::RemoveFriend
+            | Self::SetPresenceGameKey
+            | Self::SetPrivacySettings
+            | Self::SetMyData
+            | Self::SetMyFavoriteGame
+            | Self::SetMyNcPrincipalId
+            | Self::SetPersonalComment
+            | Self::DecrementMiiChangeCounter => make_header(self.into(), 1, 0),
+            _ => FrdUCommand::from(u16::from(self)).response_header(),
+        }
+    }
+}
+
+impl Service for FrdACommand {
+    const ID: usize = 1;
+    const NAME: &'static str = "frd:a";
+    const MAX_SESSION_COUNT: i32 = 8;
+}
+
+/// Commands available to frd:n, which is used by ndm.
+/// These commands are undocumented, so they're named by their ids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, IntoPrimitive)]
+#[repr(u16)]
+pub enum FrdNCommand {
+    #[num_enum(default)]
+    Invalid = 0x0,
+    Unknown1 = 0x1,
+    Unknown2 = 0x2,
+}
+
+impl FrdNCommand {
+    pub fn request_header(self) -> u32 {
+        make_header(self.into(), 0, 0)
+    }
+
+    pub fn response_header(self) -> u32 {
+        make_header(self.into(), 1, 0)
+    }
+}
+
+impl Service for FrdNCommand {
+    const ID: usize = 2;
+    const NAME: &'static str = "frd:n";
+    const MAX_SESSION_COUNT: i32 = 1;
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct EventIn {
+    pub event: Handles,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct StaticBufferOut {
+    pub out: StaticBuffer,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct GetFriendKeyListIn {
+    pub offset: u32,
+    pub max_count: u32,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct GetFriendKeyListOut {
+    pub count: u32,
+    pub friend_keys: StaticBuffer,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct FriendKeyListIn {
+    pub count: u32,
+    pub friend_keys: StaticBuffer,
+}
+
+impl FriendKeyListIn {
+    /// Creates the input from serialized friend keys, which need to stay valid until the command is sent.
+    pub fn new(friend_key_bytes: &[u8]) -> CtrResult<Self> {
+        Ok(Self {
+            count: (friend_key_bytes.len() / mem::size_of::<FriendKey>()).try_into()?,
+            friend_keys: StaticBuffer::new(friend_key_bytes, 0),
+        })
+    }
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct GetFriendScreenNameIn {
+    pub max_screen_name_count: u32,
+    pub max_screen_name_length: u32,
+    pub unk1: u32,
+    pub unk2: u32,
+    pub friend_keys: FriendKeyListIn,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct GetFriendMiiIn {
+    pub friend_keys: FriendKeyListIn,
+    pub out: PermissionBuffer,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct GetFriendMiiOut {
+    pub out: PermissionBuffer,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct GetEventNotificationOut {
+    pub unk: u32,
+    pub count: u32,
+    pub events: StaticBuffer,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct SetClientSdkVersionIn {
+    pub sdk_version: u32,
+    pub process_id: CurrentProcessId,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct RemoveFriendIn {
+    pub principal_id: u32,
+    pub local_friend_code: u64,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct PermissionBufferOut {
+    pub out: PermissionBuffer,
+}
+
+/// Privacy settings, where each setting takes a full word.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct MyPreference {
+    pub is_public_mode: u32,
+    pub is_show_game_name: u32,
+    pub is_show_played_game: u32,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct GetFriendPlayingGameIn {
+    pub friend_keys: FriendKeyListIn,
+    pub out: PermissionBuffer,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct GetFriendInfoIn {
+    pub count: u32,
+    pub unk: u32,
+    pub character_set: u32,
+    pub friend_keys: StaticBuffer,
+    pub out: PermissionBuffer,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct UnscrambleLocalFriendCodeIn {
+    pub count: u32,
+    pub scrambled_friend_codes: StaticBuffer,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct UpdateGameModeDescriptionIn {
+    pub description: StaticBuffer,
+}
+
+/// A [FriendPresence](super::FriendPresence) without its trailing word, followed by its description.
+#[derive(EndianRead, EndianWrite)]
+pub struct UpdateGameModeIn {
+    pub join_availability_flag: u32,
+    pub match_make_system_type: u32,
+    pub join_game_id: u32,
+    pub join_game_mode: u32,
+    pub owner_principal_id: u32,
+    pub join_group_id: u32,
+    pub application_arg: [u8; 20],
+    pub description: StaticBuffer,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct RequestGameAuthenticationIn {
+    pub server_id: u32,
+    pub in_game_screen_name: [u8; 0x18],
+    pub sdk_major_version: u32,
+    pub sdk_minor_version: u32,
+    pub process_id: CurrentProcessId,
+    pub event: Handles,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct RequestServiceLocatorIn {
+    pub server_id: u32,
+    pub key_hash: [u8; 0xc],
+    pub service: [u8; 8],
+    pub sdk_major_version: u32,
+    pub sdk_minor_version: u32,
+    pub process_id: CurrentProcessId,
+    pub event: Handles,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct GetNatPropertiesOut {
+    pub unk1: u32,
+    pub unk2: u32,
+}
+
+/// The servers an account uses, where each value takes a full word.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct ServerTypes {
+    pub nasc_environment: u32,
+    pub server_type_1: u32,
+    pub server_type_2: u32,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct GetFriendCommentIn {
+    pub max_comment_count: u32,
+    pub friend_keys: FriendKeyListIn,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct AddFriendWithApproachIn {
+    pub unk: u32,
+    pub event: Handles,
+    pub screen_name: StaticBuffer,
+    pub approach_context: StaticBuffer,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct DecryptApproachContextIn {
+    pub unk1: u32,
+    pub unk2: u32,
+    pub approach_context: StaticBuffer,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct CreateLocalAccountIn {
+    pub local_account_id: u32,
+    pub server_types: ServerTypes,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct AddFriendOnlineIn {
+    pub principal_id: u32,
+    pub event: Handles,
+}
+
+#[derive(EndianRead, EndianWrite)]
+pub struct AddFriendOfflineIn {
+    pub friend_key: FriendKey,
+    pub screen_name: ScreenName,
+    pub character_set: TrivialCharacterSet,
+    pub unk: u8,
+    pub mii: Mii,
+    pub profile: FriendProfile,
+    pub event: Handles,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct SetFriendDisplayNameIn {
+    pub friend_key: FriendKey,
+    pub display_name: ScreenName,
+    pub padding: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct SetMyDataIn {
+    pub screen_name: ScreenName,
+    pub character_set: TrivialCharacterSet,
+    pub unk: u8,
+    pub profile: FriendProfile,
+    pub mii: Mii,
+}
+
+/// Handlers for frd:u commands.  Output buffers need to stay valid until the reply has been sent.
+pub trait FrdUServer {
+    fn has_logged_in(&mut self, _session_index: usize) -> CtrResult<bool> {
+        Err(error::not_implemented())
+    }
+
+    fn is_online(&mut self, _session_index: usize) -> CtrResult<bool> {
+        Err(error::not_implemented())
+    }
+
+    fn login(&mut self, _session_index: usize, _input: EventIn) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn logout(&mut self, _session_index: usize) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_friend_key(&mut self, _session_index: usize) -> CtrResult<FriendKey> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_preference(&mut self, _session_index: usize) -> CtrResult<MyPreference> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_profile(&mut self, _session_index: usize) -> CtrResult<FriendProfile> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_presence(&mut self, _session_index: usize) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_screen_name(&mut self, _session_index: usize) -> CtrResult<ScreenName> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_mii(&mut self, _session_index: usize) -> CtrResult<Mii> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_local_account_id(&mut self, _session_index: usize) -> CtrResult<u8> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_playing_game(&mut self, _session_index: usize) -> CtrResult<GameKey> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_favorite_game(&mut self, _session_index: usize) -> CtrResult<GameKey> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_nc_principal_id(&mut self, _session_index: usize) -> CtrResult<u32> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_comment(&mut self, _session_index: usize) -> CtrResult<FriendComment> {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_password(
+        &mut self,
+        _session_index: usize,
+        _max_size: u32,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_key_list(
+        &mut self,
+        _session_index: usize,
+        _input: GetFriendKeyListIn,
+    ) -> CtrResult<GetFriendKeyListOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_presence(
+        &mut self,
+        _session_index: usize,
+        _input: FriendKeyListIn,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_screen_name(
+        &mut self,
+        _session_index: usize,
+        _input: GetFriendScreenNameIn,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_mii(
+        &mut self,
+        _session_index: usize,
+        _input: GetFriendMiiIn,
+    ) -> CtrResult<GetFriendMiiOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_profile(
+        &mut self,
+        _session_index: usize,
+        _input: FriendKeyListIn,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_relationship(
+        &mut self,
+        _session_index: usize,
+        _input: FriendKeyListIn,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_attribute_flags(
+        &mut self,
+        _session_index: usize,
+        _input: FriendKeyListIn,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_playing_game(
+        &mut self,
+        _session_index: usize,
+        _input: GetFriendPlayingGameIn,
+    ) -> CtrResult<PermissionBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_favorite_game(
+        &mut self,
+        _session_index: usize,
+        _input: FriendKeyListIn,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_info(
+        &mut self,
+        _session_index: usize,
+        _input: GetFriendInfoIn,
+    ) -> CtrResult<PermissionBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn is_included_in_friend_list(
+        &mut self,
+        _session_index: usize,
+        _friend_code: u64,
+    ) -> CtrResult<bool> {
+        Err(error::not_implemented())
+    }
+
+    fn unscramble_local_friend_code(
+        &mut self,
+        _session_index: usize,
+        _input: UnscrambleLocalFriendCodeIn,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn update_game_mode_description(
+        &mut self,
+        _session_index: usize,
+        _input: UpdateGameModeDescriptionIn,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn update_game_mode(&mut self, _session_index: usize, _input: UpdateGameModeIn) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn send_invitation(&mut self, _session_index: usize, _input: FriendKeyListIn) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn attach_to_event_notification(
+        &mut self,
+        _session_index: usize,
+        _input: EventIn,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn set_notification_mask(&mut self, _session_index: usize, _mask: u32) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn get_event_notification(
+        &mut self,
+        _session_index: usize,
+        _max_count: u32,
+    ) -> CtrResult<GetEventNotificationOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_last_response_result(&mut self, _session_index: usize) -> CtrResult<ResultCode> {
+        Err(error::not_implemented())
+    }
+
+    fn principal_id_to_friend_code(
+        &mut self,
+        _session_index: usize,
+        _principal_id: u32,
+    ) -> CtrResult<u64> {
+        Err(error::not_implemented())
+    }
+
+    fn friend_code_to_principal_id(
+        &mut self,
+        _session_index: usize,
+        _friend_code: u64,
+    ) -> CtrResult<u32> {
+        Err(error::not_implemented())
+    }
+
+    fn is_valid_friend_code(
+        &mut self,
+        _session_index: usize,
+        _friend_code: u64,
+    ) -> CtrResult<bool> {
+        Err(error::not_implemented())
+    }
+
+    fn result_to_error_code(
+        &mut self,
+        _session_index: usize,
+        _result_code: ResultCode,
+    ) -> CtrResult<u32> {
+        Err(error::not_implemented())
+    }
+
+    fn request_game_authentication(
+        &mut self,
+        _session_index: usize,
+        _input: RequestGameAuthenticationIn,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn get_game_authentication_data(
+        &mut self,
+        _session_index: usize,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn request_service_locator(
+        &mut self,
+        _session_index: usize,
+        _input: RequestServiceLocatorIn,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn get_service_locator_data(&mut self, _session_index: usize) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn detect_nat_properties(&mut self, _session_index: usize, _input: EventIn) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn get_nat_properties(&mut self, _session_index: usize) -> CtrResult<GetNatPropertiesOut> {
+        Err(error::not_implemented())
+    }
+
+    fn get_server_time_interval(&mut self, _session_index: usize) -> CtrResult<u64> {
+        Err(error::not_implemented())
+    }
+
+    fn allow_half_awake(&mut self, _session_index: usize, _allow: bool) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn get_server_types(&mut self, _session_index: usize) -> CtrResult<ServerTypes> {
+        Err(error::not_implemented())
+    }
+
+    fn get_friend_comment(
+        &mut self,
+        _session_index: usize,
+        _input: GetFriendCommentIn,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn set_client_sdk_version(
+        &mut self,
+        _session_index: usize,
+        _input: SetClientSdkVersionIn,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn get_my_approach_context(&mut self, _session_index: usize) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+
+    fn add_friend_with_approach(
+        &mut self,
+        _session_index: usize,
+        _input: AddFriendWithApproachIn,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn decrypt_approach_context(
+        &mut self,
+        _session_index: usize,
+        _input: DecryptApproachContextIn,
+    ) -> CtrResult<StaticBufferOut> {
+        Err(error::not_implemented())
+    }
+}
+
+/// Handlers for the frd:a commands that frd:u doesn't have.
+pub trait FrdAServer: FrdUServer {
+    fn create_local_account(
+        &mut self,
+        _session_index: usize,
+        _input: CreateLocalAccountIn,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn delete_local_account(&mut self, _session_index: usize, _local_account_id: u8) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn load_local_account(&mut self, _session_index: usize, _local_account_id: u8) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn unload_local_account(&mut self, _session_index: usize) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn has_user_data(&mut self, _session_index: usize) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn add_friend_online(&mut self, _session_index: usize, _input: AddFriendOnlineIn) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn add_friend_offline(
+        &mut self,
+        _session_index: usize,
+        _input: AddFriendOfflineIn,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn set_friend_display_name(
+        &mut self,
+        _session_index: usize,
+        _input: SetFriendDisplayNameIn,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn remove_friend(&mut self, _session_index: usize, _input: RemoveFriendIn) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn set_presence_game_key(&mut self, _session_index: usize, _game_key: GameKey) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn set_privacy_settings(
+        &mut self,
+        _session_index: usize,
+        _preference: MyPreference,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn set_my_data(&mut self, _session_index: usize, _input: SetMyDataIn) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn set_my_favorite_game(&mut self, _session_index: usize, _game_key: GameKey) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn set_my_nc_principal_id(&mut self, _session_index: usize, _principal_id: u32) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn set_personal_comment(
+        &mut self,
+        _session_index: usize,
+        _comment: FriendComment,
+    ) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn decrement_mii_change_counter(&mut self, _session_index: usize) -> CtrResult {
+        Err(error::not_implemented())
+    }
+}
+
+pub trait FrdNServer {
+    fn unknown_1(&mut self, _session_index: usize) -> CtrResult {
+        Err(error::not_implemented())
+    }
+
+    fn unknown_2(&mut self, _session_index: usize) -> CtrResult {
+        Err(error::not_implemented())
+    }
+}
+
+/// Implements a `ServiceRoute` for one command by calling a server trait method.
+/// This generates the same impl as `ctr_method`, using the response header of the command enum.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __frd_route {
+    ($server:ty, $command:ident :: $variant:ident, $server_trait:ident :: $method:ident) => {
+        impl
+            $crate::sysmodule::server::ServiceRoute<
+                $crate::frd::server::$command,
+                { $crate::frd::server::$command::$variant as u16 },
+            > for $server
+        {
+            fn handle_request(
+                &mut self,
+                session_index: usize,
+            ) -> $crate::res::CtrResult<$crate::ipc::WrittenCommand> {
+                let raw_out =
+                    <$server as $crate::frd::server::$server_trait>::$method(self, session_index)?;
+                let out = $crate::sysmodule::server::CtrSuccessResponse::new(raw_out);
+                let header = $crate::frd::server::$command::$variant.response_header();
+                Ok($crate::ipc::Command::new(header, out).write())
+            }
+        }
+    };
+    ($server:ty, $command:ident :: $variant:ident, $server_trait:ident :: $method:ident, input) => {
+        impl
+            $crate::sysmodule::server::ServiceRoute<
+                $crate::frd::server::$command,
+                { $crate::frd::server::$command::$variant as u16 },
+            > for $server
+        {
+            fn handle_request(
+                &mut self,
+                session_index: usize,
+            ) -> $crate::res::CtrResult<$crate::ipc::WrittenCommand> {
+                let input = $crate::ipc::Command::read()?.into_data();
+                let raw_out = <$server as $crate::frd::server::$server_trait>::$method(
+                    self,
+                    session_index,
+                    input,
+                )?;
+                let out = $crate::sysmodule::server::CtrSuccessResponse::new(raw_out);
+                let header = $crate::frd::server::$command::$variant.response_header();
+                Ok($crate::ipc::Command::new(header, out).write())
+            }
+        }
+    };
+}
+
+/// Routes the commands frd:u and frd:a share to [FrdUServer].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __frd_shared_routes {
+    ($server:ty, $command:ident) => {
+        $crate::__frd_route!($server, $command::HasLoggedIn, FrdUServer::has_logged_in);
+        $crate::__frd_route!($server, $command::IsOnline, FrdUServer::is_online);
+        $crate::__frd_route!($server, $command::Login, FrdUServer::login, input);
+        $crate::__frd_route!($server, $command::Logout, FrdUServer::logout);
+        $crate::__frd_route!(
+            $server,
+            $command::GetMyFriendKey,
+            FrdUServer::get_my_friend_key
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetMyPreference,
+            FrdUServer::get_my_preference
+        );
+        $crate::__frd_route!($server, $command::GetMyProfile, FrdUServer::get_my_profile);
+        $crate::__frd_route!(
+            $server,
+            $command::GetMyPresence,
+            FrdUServer::get_my_presence
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetMyScreenName,
+            FrdUServer::get_my_screen_name
+        );
+        $crate::__frd_route!($server, $command::GetMyMii, FrdUServer::get_my_mii);
+        $crate::__frd_route!(
+            $server,
+            $command::GetMyLocalAccountId,
+            FrdUServer::get_my_local_account_id
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetMyPlayingGame,
+            FrdUServer::get_my_playing_game
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetMyFavoriteGame,
+            FrdUServer::get_my_favorite_game
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetMyNcPrincipalId,
+            FrdUServer::get_my_nc_principal_id
+        );
+        $crate::__frd_route!($server, $command::GetMyComment, FrdUServer::get_my_comment);
+        $crate::__frd_route!(
+            $server,
+            $command::GetMyPassword,
+            FrdUServer::get_my_password,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendKeyList,
+            FrdUServer::get_friend_key_list,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendPresence,
+            FrdUServer::get_friend_presence,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendScreenName,
+            FrdUServer::get_friend_screen_name,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendMii,
+            FrdUServer::get_friend_mii,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendProfile,
+            FrdUServer::get_friend_profile,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendRelationship,
+            FrdUServer::get_friend_relationship,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendAttributeFlags,
+            FrdUServer::get_friend_attribute_flags,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendPlayingGame,
+            FrdUServer::get_friend_playing_game,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendFavoriteGame,
+            FrdUServer::get_friend_favorite_game,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendInfo,
+            FrdUServer::get_friend_info,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::IsIncludedInFriendList,
+            FrdUServer::is_included_in_friend_list,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::UnscrambleLocalFriendCode,
+            FrdUServer::unscramble_local_friend_code,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::UpdateGameModeDescription,
+            FrdUServer::update_game_mode_description,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::UpdateGameMode,
+            FrdUServer::update_game_mode,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::SendInvitation,
+            FrdUServer::send_invitation,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::AttachToEventNotification,
+            FrdUServer::attach_to_event_notification,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::SetNotificationMask,
+            FrdUServer::set_notification_mask,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetEventNotification,
+            FrdUServer::get_event_notification,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetLastResponseResult,
+            FrdUServer::get_last_response_result
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::PrincipalIdToFriendCode,
+            FrdUServer::principal_id_to_friend_code,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::FriendCodeToPrincipalId,
+            FrdUServer::friend_code_to_principal_id,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::IsValidFriendCode,
+            FrdUServer::is_valid_friend_code,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::ResultToErrorCode,
+            FrdUServer::result_to_error_code,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::RequestGameAuthentication,
+            FrdUServer::request_game_authentication,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetGameAuthenticationData,
+            FrdUServer::get_game_authentication_data
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::RequestServiceLocator,
+            FrdUServer::request_service_locator,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetServiceLocatorData,
+            FrdUServer::get_service_locator_data
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::DetectNatProperties,
+            FrdUServer::detect_nat_properties,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetNatProperties,
+            FrdUServer::get_nat_properties
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetServerTimeInterval,
+            FrdUServer::get_server_time_interval
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::AllowHalfAwake,
+            FrdUServer::allow_half_awake,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetServerTypes,
+            FrdUServer::get_server_types
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetFriendComment,
+            FrdUServer::get_friend_comment,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::SetClientSdkVersion,
+            FrdUServer::set_client_sdk_version,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::GetMyApproachContext,
+            FrdUServer::get_my_approach_context
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::AddFriendWithApproach,
+            FrdUServer::add_friend_with_approach,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            $command::DecryptApproachContext,
+            FrdUServer::decrypt_approach_context,
+            input
+        );
+    };
+}
+
+/// Implements a route for every [FrdUCommand] on a type that implements [FrdUServer].
+#[macro_export]
+macro_rules! impl_frd_u_routes {
+    ($server:ty) => {
+        $crate::__frd_shared_routes!($server, FrdUCommand);
+    };
+}
+
+/// Implements a route for every [FrdACommand] on a type that implements [FrdAServer].
+#[macro_export]
+macro_rules! impl_frd_a_routes {
+    ($server:ty) => {
+        $crate::__frd_shared_routes!($server, FrdACommand);
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::CreateLocalAccount,
+            FrdAServer::create_local_account,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::DeleteLocalAccount,
+            FrdAServer::delete_local_account,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::LoadLocalAccount,
+            FrdAServer::load_local_account,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::UnloadLocalAccount,
+            FrdAServer::unload_local_account
+        );
+        $crate::__frd_route!($server, FrdACommand::HasUserData, FrdAServer::has_user_data);
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::AddFriendOnline,
+            FrdAServer::add_friend_online,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::AddFriendOffline,
+            FrdAServer::add_friend_offline,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::SetFriendDisplayName,
+            FrdAServer::set_friend_display_name,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::RemoveFriend,
+            FrdAServer::remove_friend,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::SetPresenceGameKey,
+            FrdAServer::set_presence_game_key,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::SetPrivacySettings,
+            FrdAServer::set_privacy_settings,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::SetMyData,
+            FrdAServer::set_my_data,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::SetMyFavoriteGame,
+            FrdAServer::set_my_favorite_game,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::SetMyNcPrincipalId,
+            FrdAServer::set_my_nc_principal_id,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::SetPersonalComment,
+            FrdAServer::set_personal_comment,
+            input
+        );
+        $crate::__frd_route!(
+            $server,
+            FrdACommand::DecrementMiiChangeCounter,
+            FrdAServer::decrement_mii_change_counter
+        );
+    };
+}
+
+/// Implements a route for every [FrdNCommand] on a type that implements [FrdNServer].
+#[macro_export]
+macro_rules! impl_frd_n_routes {
+    ($server:ty) => {
+        $crate::__frd_route!($server, FrdNCommand::Unknown1, FrdNServer::unknown_1);
+        $crate::__frd_route!($server, FrdNCommand::Unknown2, FrdNServer::unknown_2);
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frd::{ExpandedFriendPresence, FriendPresence};
+    use alloc::vec;
+
+    /// The number of words a value takes in a command buffer on the 3DS,
+    /// where each of its `buffer_count` buffers has a 32 bit pointer.
+    fn word_count(value: &impl EndianWrite, buffer_count: usize) -> u32 {
+        let pointer_padding = buffer_count * (mem::size_of::<usize>() - mem::size_of::<u32>());
+        ((value.get_size() - pointer_padding).div_ceil(4)) as u32
+    }
+
+    /// The number of normal and translate words in a header.
+    fn param_count(header: u32) -> u32 {
+        ((header >> 6) & 0x3f) + (header & 0x3f)
+    }
+
+    fn assert_request(header: u32, expected_header: u32, input: &impl EndianWrite, buffers: usize) {
+        assert_eq!(header, expected_header);
+        assert_eq!(word_count(input, buffers), param_count(header));
+    }
+
+    /// The result code is the first normal parameter of a response.
+    fn assert_response(
+        header: u32,
+        expected_header: u32,
+        output: &impl EndianWrite,
+        buffers: usize,
+    ) {
+        assert_eq!(header, expected_header);
+        assert_eq!(word_count(output, buffers) + 1, param_count(header));
+    }
+
+    fn friend_key_list_in() -> FriendKeyListIn {
+        FriendKeyListIn {
+            count: 0,
+            friend_keys: StaticBuffer::new(&[], 0),
+        }
+    }
+
+    fn static_buffer_out() -> StaticBufferOut {
+        StaticBufferOut {
+            out: StaticBuffer::new(&[], 0),
+        }
+    }
+
+    fn permission_buffer_out() -> PermissionBufferOut {
+        PermissionBufferOut {
+            out: PermissionBuffer::new_write(&mut []),
+        }
+    }
+
+    fn event_in() -> EventIn {
+        EventIn {
+            event: Handles::new(vec![0]),
+        }
+    }
+
+    macro_rules! wire_test {
+        ($name:ident, $command:ident :: $variant:ident, $request:expr, $input:expr, $response:expr, $output:expr) => {
+            wire_test!(
+                $name,
+                $command::$variant,
+                $request,
+                $input,
+                $response,
+                $output,
+                buffers = (0, 0)
+            );
+        };
+        ($name:ident, $command:ident :: $variant:ident, $request:expr, $input:expr, $response:expr, $output:expr, buffers = ($in_buffers:expr, $out_buffers:expr)) => {
+            #[test]
+            fn $name() {
+                let command = $command::$variant;
+                assert_request(command.request_header(), $request, &$input, $in_buffers);
+                assert_response(command.response_header(), $response, &$output, $out_buffers);
+            }
+        };
+    }
+
+    mod frd_u {
+        use super::*;
+
+        wire_test!(
+            has_logged_in,
+            FrdUCommand::HasLoggedIn,
+            0x10000,
+            (),
+            0x10080,
+            true
+        );
+        wire_test!(is_online, FrdUCommand::IsOnline, 0x20000, (), 0x20080, true);
+        wire_test!(login, FrdUCommand::Login, 0x30002, event_in(), 0x30040, ());
+        wire_test!(logout, FrdUCommand::Logout, 0x40000, (), 0x40040, ());
+        wire_test!(
+            get_my_friend_key,
+            FrdUCommand::GetMyFriendKey,
+            0x50000,
+            (),
+            0x50140,
+            FriendKey::default()
+        );
+        wire_test!(
+            get_my_preference,
+            FrdUCommand::GetMyPreference,
+            0x60000,
+            (),
+            0x60100,
+            MyPreference::default()
+        );
+        wire_test!(
+            get_my_profile,
+            FrdUCommand::GetMyProfile,
+            0x70000,
+            (),
+            0x700c0,
+            FriendProfile::default()
+        );
+        wire_test!(
+            get_my_presence,
+            FrdUCommand::GetMyPresence,
+            0x80000,
+            (),
+            0x80042,
+            static_buffer_out(),
+            buffers = (0, 1)
+        );
+        wire_test!(
+            get_my_screen_name,
+            FrdUCommand::GetMyScreenName,
+            0x90000,
+            (),
+            0x901c0,
+            ScreenName::default()
+        );
+        wire_test!(
+            get_my_mii,
+            FrdUCommand::GetMyMii,
+            0xa0000,
+            (),
+            0xa0640,
+            Mii::default()
+        );
+        wire_test!(
+            get_my_local_account_id,
+            FrdUCommand::GetMyLocalAccountId,
+            0xb0000,
+            (),
+            0xb0080,
+            0u8
+        );
+        wire_test!(
+            get_my_playing_game,
+            FrdUCommand::GetMyPlayingGame,
+            0xc0000,
+            (),
+            0xc0140,
+            GameKey::default()
+        );
+        wire_test!(
+            get_my_favorite_game,
+            FrdUCommand::GetMyFavoriteGame,
+            0xd0000,
+            (),
+            0xd0140,
+            GameKey::default()
+        );
+        wire_test!(
+            get_my_nc_principal_id,
+            FrdUCommand::GetMyNcPrincipalId,
+            0xe0000,
+            (),
+            0xe0080,
+            0u32
+        );
+        wire_test!(
+            get_my_comment,
+            FrdUCommand::GetMyComment,
+            0xf0000,
+            (),
+            0xf0280,
+            FriendComment::default()
+        );
+        wire_test!(
+            get_my_password,
+            FrdUCommand::GetMyPassword,
+            0x100040,
+            0u32,
+            0x100042,
+            static_buffer_out(),
+            buffers = (0, 1)
+        );
+        wire_test!(
+            get_friend_key_list,
+            FrdUCommand::GetFriendKeyList,
+            0x110080,
+            GetFriendKeyListIn {
+                offset: 0,
+                max_count: 0
+            },
+            0x110082,
+            GetFriendKeyListOut {
+                count: 0,
+                friend_keys: StaticBuffer::new(&[], 0)
+            },
+            buffers = (0, 1)
+        );
+        wire_test!(
+            get_friend_presence,
+            FrdUCommand::GetFriendPresence,
+            0x120042,
+            friend_key_list_in(),
+            0x120042,
+            static_buffer_out(),
+            buffers = (1, 1)
+        );
+        wire_test!(
+            get_friend_screen_name,
+            FrdUCommand::GetFriendScreenName,
+            0x130142,
+            GetFriendScreenNameIn {
+                max_screen_name_count: 0,
+                max_screen_name_length: 0,
+                unk1: 0,
+                unk2: 0,
+                friend_keys: friend_key_list_in()
+            },
+            0x130042,
+            static_buffer_out(),
+            buffers = (1, 1)
+        );
+        wire_test!(
+            get_friend_mii,
+            FrdUCommand::GetFriendMii,
+            0x140044,
+            GetFriendMiiIn {
+                friend_keys: friend_key_list_in(),
+                out: PermissionBuffer::new_write(&mut [])
+            },
+            0x140042,
+            GetFriendMiiOut {
+                out: PermissionBuffer::new_write(&mut [])
+            },
+            buffers = (2, 1)
+        );
+        wire_test!(
+            get_friend_profile,
+            FrdUCommand::GetFriendProfile,
+            0x150042,
+            friend_key_list_in(),
+            0x150042,
+            static_buffer_out(),
+            buffers = (1, 1)
+        );
+        wire_test!(
+            get_friend_relationship,
+            FrdUCommand::GetFriendRelationship,
+            0x160042,
+            friend_key_list_in(),
+            0x160042,
+            static_buffer_out(),
+            buffers = (1, 1)
+        );
+        wire_test!(
+            get_friend_attribute_flags,
+            FrdUCommand::GetFriendAttributeFlags,
+            0x170042,
+            friend_key_list_in(),
+            0x170042,
+            static_buffer_out(),
+            buffers = (1, 1)
+        );
+        wire_test!(
+            get_friend_playing_game,
+            FrdUCommand::GetFriendPlayingGame,
+            0x180044,
+            GetFriendPlayingGameIn {
+                friend_keys: friend_key_list_in(),
+                out: PermissionBuffer::new_write(&mut [])
+            },
+            0x180042,
+            permission_buffer_out(),
+            buffers = (2, 1)
+        );
+        wire_test!(
+            get_friend_favorite_game,
+            FrdUCommand::GetFriendFavoriteGame,
+            0x190042,
+            friend_key_list_in(),
+            0x190042,
+            static_buffer_out(),
+            buffers = (1, 1)
+        );
+        wire_test!(
+            get_friend_info,
+            FrdUCommand::GetFriendInfo,
+            0x1a00c4,
+            GetFriendInfoIn {
+                count: 0,
+                unk: 0,
+                character_set: 0,
+                friend_keys: StaticBuffer::new(&[], 0),
+                out: PermissionBuffer::new_write(&mut [])
+            },
+            0x1a0042,
+            permission_buffer_out(),
+            buffers = (2, 1)
+        );
+        wire_test!(
+            is_included_in_friend_list,
+            FrdUCommand::IsIncludedInFriendList,
+            0x1b0080,
+            0u64,
+            0x1b0080,
+            true
+        );
+        wire_test!(
+            unscramble_local_friend_code,
+            FrdUCommand::UnscrambleLocalFriendCode,
+            0x1c0042,
+            UnscrambleLocalFriendCodeIn {
+                count: 0,
+                scrambled_friend_codes: StaticBuffer::new(&[], 1)
+            },
+            0x1c0042,
+            static_buffer_out(),
+            buffers = (1, 1)
+        );
+        wire_test!(
+            update_game_mode_description,
+            FrdUCommand::UpdateGameModeDescription,
+            0x1d0002,
+            UpdateGameModeDescriptionIn {
+                description: StaticBuffer::new(&[], 0)
+            },
+            0x1d0040,
+            (),
+            buffers = (1, 0)
+        );
+        wire_test!(
+            update_game_mode,
+            FrdUCommand::UpdateGameMode,
+            0x1e02c2,
+            UpdateGameModeIn {
+                join_availability_flag: 0,
+                match_make_system_type: 0,
+                join_game_id: 0,
+                join_game_mode: 0,
+                owner_principal_id: 0,
+                join_group_id: 0,
+                application_arg: [0; 20],
+                description: StaticBuffer::new(&[], 0)
+            },
+            0x1e0040,
+            (),
+            buffers = (1, 0)
+        );
+        wire_test!(
+            send_invitation,
+            FrdUCommand::SendInvitation,
+            0x1f0042,
+            friend_key_list_in(),
+            0x1f0040,
+            (),
+            buffers = (1, 0)
+        );
+        wire_test!(
+            attach_to_event_notification,
+            FrdUCommand::AttachToEventNotification,
+            0x200002,
+            event_in(),
+            0x200040,
+            ()
+        );
+        wire_test!(
+            set_notification_mask,
+            FrdUCommand::SetNotificationMask,
+            0x210040,
+            0u32,
+            0x210040,
+            ()
+        );
+        wire_test!(
+            get_event_notification,
+            FrdUCommand::GetEventNotification,
+            0x220040,
+            0u32,
+            0x2200c2,
+            GetEventNotificationOut {
+                unk: 0,
+                count: 0,
+                events: StaticBuffer::new(&[], 0)
+            },
+            buffers = (0, 1)
+        );
+        wire_test!(
+            get_last_response_result,
+            FrdUCommand::GetLastResponseResult,
+            0x230000,
+            (),
+            0x230080,
+            ResultCode::success()
+        );
+        wire_test!(
+            principal_id_to_friend_code,
+            FrdUCommand::PrincipalIdToFriendCode,
+            0x240040,
+            0u32,
+            0x2400c0,
+            0u64
+        );
+        wire_test!(
+            friend_code_to_principal_id,
+            FrdUCommand::FriendCodeToPrincipalId,
+            0x250080,
+            0u64,
+            0x250080,
+            0u32
+        );
+        wire_test!(
+            is_valid_friend_code,
+            FrdUCommand::IsValidFriendCode,
+            0x260080,
+            0u64,
+            0x260080,
+            true
+        );
+        wire_test!(
+            result_to_error_code,
+            FrdUCommand::ResultToErrorCode,
+            0x270040,
+            ResultCode::success(),
+            0x270080,
+            0u32
+        );
+        wire_test!(
+            request_game_authentication,
+            FrdUCommand::RequestGameAuthentication,
+            0x280244,
+            RequestGameAuthenticationIn {
+                server_id: 0,
+                in_game_screen_name: [0; 0x18],
+                sdk_major_version: 0,
+                sdk_minor_version: 0,
+                process_id: CurrentProcessId::new(),
+                event: Handles::new(vec![0])
+            },
+            0x280040,
+            ()
+        );
+        wire_test!(
+            get_game_authentication_data,
+            FrdUCommand::GetGameAuthenticationData,
+            0x290000,
+            (),
+            0x290042,
+            static_buffer_out(),
+            buffers = (0, 1)
+        );
+        wire_test!(
+            request_service_locator,
+            FrdUCommand::RequestServiceLocator,
+            0x2a0204,
+            RequestServiceLocatorIn {
+                server_id: 0,
+                key_hash: [0; 0xc],
+                service: [0; 8],
+                sdk_major_version: 0,
+                sdk_minor_version: 0,
+                process_id: CurrentProcessId::new(),
+                event: Handles::new(vec![0])
+            },
+            0x2a0040,
+            ()
+        );
+        wire_test!(
+            get_service_locator_data,
+            FrdUCommand::GetServiceLocatorData,
+            0x2b0000,
+            (),
+            0x2b0042,
+            static_buffer_out(),
+            buffers = (0, 1)
+        );
+        wire_test!(
+            detect_nat_properties,
+            FrdUCommand::DetectNatProperties,
+            0x2c0002,
+            event_in(),
+            0x2c0040,
+            ()
+        );
+        wire_test!(
+            get_nat_properties,
+            FrdUCommand::GetNatProperties,
+            0x2d0000,
+            (),
+            0x2d00c0,
+            GetNatPropertiesOut::default()
+        );
+        wire_test!(
+            get_server_time_interval,
+            FrdUCommand::GetServerTimeInterval,
+            0x2e0000,
+            (),
+            0x2e00c0,
+            0u64
+        );
+        wire_test!(
+            allow_half_awake,
+            FrdUCommand::AllowHalfAwake,
+            0x2f0040,
+            true,
+            0x2f0040,
+            ()
+        );
+        wire_test!(
+            get_server_types,
+            FrdUCommand::GetServerTypes,
+            0x300000,
+            (),
+            0x300100,
+            ServerTypes::default()
+        );
+        wire_test!(
+            get_friend_comment,
+            FrdUCommand::GetFriendComment,
+            0x310082,
+            GetFriendCommentIn {
+                max_comment_count: 0,
+                friend_keys: friend_key_list_in()
+            },
+            0x310042,
+            static_buffer_out(),
+            buffers = (1, 1)
+        );
+        wire_test!(
+            set_client_sdk_version,
+            FrdUCommand::SetClientSdkVersion,
+            0x320042,
+            SetClientSdkVersionIn {
+                sdk_version: 0,
+                process_id: CurrentProcessId::new()
+            },
+            0x320040,
+            ()
+        );
+        wire_test!(
+            get_my_approach_context,
+            FrdUCommand::GetMyApproachContext,
+            0x330000,
+            (),
+            0x330042,
+            static_buffer_out(),
+            buffers = (0, 1)
+        );
+        wire_test!(
+            add_friend_with_approach,
+            FrdUCommand::AddFriendWithApproach,
+            0x340046,
+            AddFriendWithApproachIn {
+                unk: 0,
+                event: Handles::new(vec![0]),
+                screen_name: StaticBuffer::new(&[], 0),
+                approach_context: StaticBuffer::new(&[], 1)
+            },
+            0x340040,
+            (),
+            buffers = (2, 0)
+        );
+        wire_test!(
+            decrypt_approach_context,
+            FrdUCommand::DecryptApproachContext,
+            0x350082,
+            DecryptApproachContextIn {
+                unk1: 0,
+                unk2: 0,
+                approach_context: StaticBuffer::new(&[], 0)
+            },
+            0x350042,
+            static_buffer_out(),
+            buffers = (1, 1)
+        );
+
+        #[test]
+        fn should_count_friend_keys() {
+            let friend_key_bytes = [0u8; 0x30];
+            let result = FriendKeyListIn::new(&friend_key_bytes).unwrap();
+
+            assert_eq!(result.count, 3);
+            assert_eq!(result.friend_keys.id(), 0);
+        }
+
+        #[test]
+        fn should_size_static_buffers_with_the_frd_types() {
+            assert_eq!(mem::size_of::<FriendKey>(), 0x10);
+            assert_eq!(mem::size_of::<FriendPresence>(), 0x30);
+            assert_eq!(mem::size_of::<ExpandedFriendPresence>(), 0x12c);
+            assert_eq!(mem::size_of::<ScreenName>(), 0x16);
+            assert_eq!(mem::size_of::<FriendProfile>(), 0x8);
+            assert_eq!(mem::size_of::<Mii>(), 0x60);
+        }
+
+        #[test]
+        fn should_use_invalid_for_unknown_commands() {
+            assert_eq!(FrdUCommand::from(0x405), FrdUCommand::Invalid);
+        }
+    }
+
+    mod frd_a {
+        use super::*;
+
+        #[test]
+        fn should_share_frd_u_headers() {
+            assert_eq!(
+                FrdACommand::GetFriendKeyList.request_header(),
+                FrdUCommand::GetFriendKeyList.request_header()
+            );
+            assert_eq!(
+                FrdACommand::GetEventNotification.response_header(),
+                FrdUCommand::GetEventNotification.response_header()
+            );
+        }
+
+        wire_test!(
+            create_local_account,
+            FrdACommand::CreateLocalAccount,
+            0x4010100,
+            CreateLocalAccountIn::default(),
+            0x4010040,
+            ()
+        );
+        wire_test!(
+            delete_local_account,
+            FrdACommand::DeleteLocalAccount,
+            0x4020040,
+            0u8,
+            0x4020040,
+            ()
+        );
+        wire_test!(
+            load_local_account,
+            FrdACommand::LoadLocalAccount,
+            0x4030040,
+            0u8,
+            0x4030040,
+            ()
+        );
+        wire_test!(
+            unload_local_account,
+            FrdACommand::UnloadLocalAccount,
+            0x4040000,
+            (),
+            0x4040040,
+            ()
+        );
+        wire_test!(
+            has_user_data,
+            FrdACommand::HasUserData,
+            0x4050000,
+            (),
+            0x4050040,
+            ()
+        );
+        wire_test!(
+            add_friend_online,
+            FrdACommand::AddFriendOnline,
+            0x4060042,
+            AddFriendOnlineIn {
+                principal_id: 0,
+                event: Handles::new(vec![0])
+            },
+            0x4060040,
+            ()
+        );
+        wire_test!(
+            add_friend_offline,
+            FrdACommand::AddFriendOffline,
+            0x4070902,
+            AddFriendOfflineIn {
+                friend_key: FriendKey::default(),
+                screen_name: ScreenName::default(),
+                character_set: TrivialCharacterSet::default(),
+                unk: 0,
+                mii: Mii::default(),
+                profile: FriendProfile::default(),
+                event: Handles::new(vec![0])
+            },
+            0x4070040,
+            ()
+        );
+        wire_test!(
+            set_friend_display_name,
+            FrdACommand::SetFriendDisplayName,
+            0x4080280,
+            SetFriendDisplayNameIn {
+                friend_key: FriendKey::default(),
+                display_name: ScreenName::default(),
+                padding: 0
+            },
+            0x4080040,
+            ()
+        );
+        wire_test!(
+            remove_friend,
+            FrdACommand::RemoveFriend,
+            0x40900c0,
+            RemoveFriendIn {
+                principal_id: 0,
+                local_friend_code: 0
+            },
+            0x4090040,
+            ()
+        );
+        wire_test!(
+            set_presence_game_key,
+            FrdACommand::SetPresenceGameKey,
+            0x40a0100,
+            GameKey::default(),
+            0x40a0040,
+            ()
+        );
+        wire_test!(
+            set_privacy_settings,
+            FrdACommand::SetPrivacySettings,
+            0x40b00c0,
+            MyPreference::default(),
+            0x40b0040,
+            ()
+        );
+        wire_test!(
+            set_my_data,
+            FrdACommand::SetMyData,
+            0x40c0800,
+            SetMyDataIn {
+                screen_name: ScreenName::default(),
+                character_set: TrivialCharacterSet::default(),
+                unk: 0,
+                profile: FriendProfile::default(),
+                mii: Mii::default()
+            },
+            0x40c0040,
+            ()
+        );
+        wire_test!(
+            set_my_favorite_game,
+            FrdACommand::SetMyFavoriteGame,
+            0x40d0100,
+            GameKey::default(),
+            0x40d0040,
+            ()
+        );
+        wire_test!(
+            set_my_nc_principal_id,
+            FrdACommand::SetMyNcPrincipalId,
+            0x40e0040,
+            0u32,
+            0x40e0040,
+            ()
+        );
+        wire_test!(
+            set_personal_comment,
+            FrdACommand::SetPersonalComment,
+            0x40f0240,
+            FriendComment::default(),
+            0x40f0040,
+            ()
+        );
+        wire_test!(
+            decrement_mii_change_counter,
+            FrdACommand::DecrementMiiChangeCounter,
+            0x4100000,
+            (),
+            0x4100040,
+            ()
+        );
+    }
+
+    mod frd_n {
+        use super::*;
+
+        wire_test!(unknown_1, FrdNCommand::Unknown1, 0x10000, (), 0x10040, ());
+        wire_test!(unknown_2, FrdNCommand::Unknown2, 0x20000, (), 0x20040, ());
+    }
+}
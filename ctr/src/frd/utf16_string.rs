@@ -0,0 +1,271 @@
+use super::CharacterSet;
+use crate::res::{error, CtrResult, ResultCode};
+use alloc::{string::String, vec::Vec};
+use core::{
+    char::{decode_utf16, REPLACEMENT_CHARACTER},
+    convert::{TryFrom, TryInto},
+    fmt, mem,
+};
+use no_std_io::{
+    Cursor, EndianRead, EndianWrite, ReadOutput, StreamContainer, StreamReader, StreamWriter,
+};
+
+/// A null terminated UTF-16 string stored in a fixed number of code units.
+/// The last code unit is reserved for the null terminator, so up to `N - 1` code units can be used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct FixedUtf16String<const N: usize> {
+    raw: [u16; N],
+}
+
+pub type ScreenName = FixedUtf16String<11>;
+pub type FriendComment = FixedUtf16String<17>;
+pub type GameDescription = FixedUtf16String<128>;
+
+impl<const N: usize> FixedUtf16String<N> {
+    /// The maximum number of UTF-16 code units, excluding the null terminator.
+    pub const MAX_LENGTH: usize = N - 1;
+
+    pub fn new(raw: [u16; N]) -> Self {
+        Self { raw }
+    }
+
+    /// Creates a string, checking that every character can be displayed with the character set's font.
+    pub fn new_with_character_set(text: &str, character_set: CharacterSet) -> CtrResult<Self> {
+        if !text
+            .chars()
+            .all(|character| character_set.supports(character))
+        {
+            return Err(error::invalid_value());
+        }
+
+        let code_units = text.encode_utf16().collect::<Vec<u16>>();
+
+        if code_units.len() > Self::MAX_LENGTH {
+            return Err(error::invalid_size());
+        }
+
+        let mut raw = [0; N];
+        raw[..code_units.len()].copy_from_slice(&code_units);
+
+        Ok(Self { raw })
+    }
+
+    pub fn as_raw(&self) -> &[u16; N] {
+        &self.raw
+    }
+
+    /// Returns the code units before the null terminator.
+    pub fn code_units(&self) -> &[u16] {
+        let length = self
+            .raw
+            .iter()
+            .position(|code_unit| *code_unit == 0)
+            .unwrap_or(N);
+        &self.raw[..length]
+    }
+
+    /// Returns the number of code units before the null terminator.
+    pub fn len(&self) -> usize {
+        self.code_units().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if every character can be displayed with the character set's font.
+    pub fn is_supported_by(&self, character_set: CharacterSet) -> bool {
+        decode_utf16(self.code_units().iter().copied()).all(|character| match character {
+            Ok(character) => character_set.supports(character),
+            Err(_) => false,
+        })
+    }
+
+    /// Converts to a String, returning an error if the string has unpaired surrogates.
+    pub fn try_to_string(&self) -> CtrResult<String> {
+        Ok(String::from_utf16(self.code_units())?)
+    }
+}
+
+impl<const N: usize> Default for FixedUtf16String<N> {
+    fn default() -> Self {
+        Self { raw: [0; N] }
+    }
+}
+
+/// Creates a string without a character set check.
+/// Returns an error if the text is too long or has a null character.
+impl<const N: usize> TryFrom<&str> for FixedUtf16String<N> {
+    type Error = ResultCode;
+
+    fn try_from(text: &str) -> CtrResult<Self> {
+        Self::new_with_character_set(text, CharacterSet::None)
+    }
+}
+
+/// Displays every code unit before the null terminator.
+/// Unpaired surrogates can't be represented in a `str`, so they're shown as U+FFFD.
+impl<const N: usize> fmt::Display for FixedUtf16String<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        decode_utf16(self.code_units().iter().copied())
+            .map(|character| character.unwrap_or(REPLACEMENT_CHARACTER))
+            .try_for_each(|character| fmt::Write::write_char(f, character))
+    }
+}
+
+impl<const N: usize> EndianRead for FixedUtf16String<N> {
+    fn try_read_le(bytes: &[u8]) -> Result<ReadOutput<Self>, no_std_io::Error> {
+        let read_size = mem::size_of::<Self>();
+        let raw = StreamContainer::new(bytes)
+            .into_le_iter()
+            .take(N)
+            .collect::<Vec<u16>>()
+            .try_into()
+            .map_err(|_| no_std_io::Error::InvalidSize {
+                wanted_size: read_size,
+                offset: 0,
+                data_len: bytes.len(),
+            })?;
+        Ok(ReadOutput::new(Self { raw }, read_size))
+    }
+
+    fn try_read_be(bytes: &[u8]) -> Result<ReadOutput<Self>, no_std_io::Error> {
+        let mut stream = StreamContainer::new(bytes);
+        let mut raw = [0; N];
+
+        for short in raw.iter_mut() {
+            *short = stream.read_stream_be()?;
+        }
+
+        Ok(ReadOutput::new(Self { raw }, stream.get_index()))
+    }
+}
+
+impl<const N: usize> EndianWrite for FixedUtf16String<N> {
+    fn get_size(&self) -> usize {
+        mem::size_of::<Self>()
+    }
+
+    fn try_write_le(&self, dst: &mut [u8]) -> Result<usize, no_std_io::Error> {
+        let mut stream = StreamContainer::new(dst);
+
+        for short in self.raw.iter() {
+            stream.write_stream_le(short)?;
+        }
+
+        Ok(stream.get_index())
+    }
+
+    fn try_write_be(&self, dst: &mut [u8]) -> Result<usize, no_std_io::Error> {
+        let mut stream = StreamContainer::new(dst);
+
+        for short in self.raw.iter() {
+            stream.write_stream_be(short)?;
+        }
+
+        Ok(stream.get_index())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use no_std_io::{Reader, Writer};
+
+    mod new_with_character_set {
+        use super::*;
+
+        #[test]
+        fn should_create_a_string() {
+            let result =
+                ScreenName::new_with_character_set("Test", CharacterSet::JapanUsaEuropeAustralia)
+                    .unwrap();
+            assert_eq!(result.code_units(), [0x54, 0x65, 0x73, 0x74]);
+            assert_eq!(result.as_raw()[4..], [0; 7]);
+        }
+
+        #[test]
+        fn should_allow_the_maximum_length() {
+            let result = ScreenName::try_from("0123456789").unwrap();
+            assert_eq!(result.len(), ScreenName::MAX_LENGTH);
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_text_is_too_long() {
+            let result = ScreenName::try_from("0123456789a");
+            assert_eq!(result, Err(error::invalid_size()));
+        }
+
+        #[test]
+        fn should_return_an_error_for_null_characters() {
+            let result = ScreenName::try_from("a\0b");
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+
+        #[test]
+        fn should_return_an_error_for_unsupported_characters() {
+            let result = ScreenName::new_with_character_set("한국", CharacterSet::China);
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+
+        #[test]
+        fn should_allow_characters_supported_by_the_character_set() {
+            let result = ScreenName::new_with_character_set("한국", CharacterSet::Korea).unwrap();
+            assert!(!result.is_supported_by(CharacterSet::JapanUsaEuropeAustralia));
+            assert!(result.is_supported_by(CharacterSet::Korea));
+        }
+    }
+
+    mod display {
+        use super::*;
+
+        #[test]
+        fn should_display_the_text() {
+            let result = FriendComment::try_from("Hello ♪ ともだち").unwrap();
+            assert_eq!(result.to_string(), "Hello ♪ ともだち");
+        }
+
+        #[test]
+        fn should_replace_unpaired_surrogates() {
+            let result = ScreenName::new([0x41, 0xd800, 0x42, 0, 0, 0, 0, 0, 0, 0, 0]);
+            assert_eq!(result.to_string(), "A\u{fffd}B");
+            assert_eq!(result.try_to_string(), Err(error::invalid_value()));
+        }
+    }
+
+    mod endian {
+        use super::*;
+
+        #[test]
+        fn should_read_and_write_the_raw_code_units() {
+            let screen_name = ScreenName::new([0x41, 0, 0x42, 0, 0, 0, 0, 0, 0, 0, 0x43]);
+            let mut bytes = [0u8; 22];
+
+            bytes.write_le(0, &screen_name).unwrap();
+
+            assert_eq!(bytes[..6], [0x41, 0, 0, 0, 0x42, 0]);
+            assert_eq!(bytes[20..], [0x43, 0]);
+            assert_eq!(bytes.read_le::<ScreenName>(0).unwrap(), screen_name);
+        }
+
+        #[test]
+        fn should_read_and_write_big_endian_code_units() {
+            let screen_name = ScreenName::new([0x41, 0, 0x42, 0, 0, 0, 0, 0, 0, 0, 0x43]);
+            let mut bytes = [0u8; 22];
+
+            bytes.write_be(0, &screen_name).unwrap();
+
+            assert_eq!(bytes[..6], [0, 0x41, 0, 0, 0, 0x42]);
+            assert_eq!(bytes[20..], [0, 0x43]);
+            assert_eq!(bytes.read_be::<ScreenName>(0).unwrap(), screen_name);
+        }
+
+        #[test]
+        fn should_error_if_big_endian_bytes_are_too_small() {
+            let result = [0u8; 21].read_be::<ScreenName>(0);
+            assert!(result.is_err());
+        }
+    }
+}
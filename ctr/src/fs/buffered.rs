@@ -0,0 +1,446 @@
+use super::{File, FileOps};
+use crate::res::CtrResult;
+use alloc::{vec, vec::Vec};
+use core::{cmp, convert::TryInto};
+
+/// The default number of bytes transferred per fs command.
+pub const DEFAULT_CHUNK_SIZE: usize = 0x4000;
+
+/// Reads a file in chunks, so large files never need to be in memory all at once.
+pub struct BufReader<F: FileOps = File> {
+    file: F,
+    offset: u64,
+    buffer: Vec<u8>,
+    position: usize,
+    filled: usize,
+}
+
+impl<F: FileOps> BufReader<F> {
+    pub fn new(file: F) -> Self {
+        Self::with_chunk_size(file, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates a reader that reads at most `chunk_size` bytes per fs command.
+    pub fn with_chunk_size(file: F, chunk_size: usize) -> Self {
+        Self {
+            file,
+            offset: 0,
+            buffer: vec![0; cmp::max(chunk_size, 1)],
+            position: 0,
+            filled: 0,
+        }
+    }
+
+    /// Returns the buffered data, reading the next chunk if everything has been consumed.
+    /// An empty slice means the end of the file has been reached.
+    pub fn fill_buf(&mut self) -> CtrResult<&[u8]> {
+        if self.position >= self.filled {
+            self.filled = self.file.read_into(self.offset, &mut self.buffer)?;
+            self.offset += self.filled as u64;
+            self.position = 0;
+        }
+
+        Ok(&self.buffer[self.position..self.filled])
+    }
+
+    /// Marks bytes returned by [BufReader::fill_buf] as read.
+    pub fn consume(&mut self, amount: usize) {
+        self.position = cmp::min(self.position + amount, self.filled);
+    }
+
+    /// Reads into a buffer and returns the number of bytes read.
+    /// Reading 0 bytes means the end of the file has been reached.
+    pub fn read(&mut self, out: &mut [u8]) -> CtrResult<usize> {
+        let available = self.fill_buf()?;
+        let read_size = cmp::min(available.len(), out.len());
+
+        out[..read_size].copy_from_slice(&available[..read_size]);
+        self.consume(read_size);
+
+        Ok(read_size)
+    }
+
+    /// Moves to an offset in the file and discards the buffered data.
+    pub fn seek(&mut self, offset: u64) {
+        self.offset = offset;
+        self.position = 0;
+        self.filled = 0;
+    }
+
+    /// The offset in the file of the next byte that will be read.
+    pub fn stream_position(&self) -> u64 {
+        self.offset - (self.filled - self.position) as u64
+    }
+
+    pub fn get_ref(&self) -> &F {
+        &self.file
+    }
+
+    pub fn into_inner(self) -> F {
+        self.file
+    }
+}
+
+/// Collects small writes and writes them to a file in chunks.
+/// Buffered data is written when the writer is dropped, but errors are only returned by [BufWriter::flush].
+pub struct BufWriter<F: FileOps = File> {
+    file: F,
+    buffer: Vec<u8>,
+    chunk_size: usize,
+    /// The size of the file after the last write, which shows how much of a failed write made it to the file.
+    file_size: Option<usize>,
+    /// The size of the file before a failed write, if the size couldn't be checked after it failed.
+    unchecked_write_start: Option<usize>,
+}
+
+impl<F: FileOps> BufWriter<F> {
+    pub fn new(file: F) -> Self {
+        Self::with_chunk_size(file, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates a writer that writes at most `chunk_size` bytes per fs command.
+    pub fn with_chunk_size(file: F, chunk_size: usize) -> Self {
+        let chunk_size = cmp::max(chunk_size, 1);
+
+        Self {
+            file,
+            buffer: Vec::with_capacity(chunk_size),
+            chunk_size,
+            file_size: None,
+            unchecked_write_start: None,
+        }
+    }
+
+    /// Writes data to the end of the file once a full chunk has been buffered.
+    pub fn write(&mut self, mut data: &[u8]) -> CtrResult {
+        while !data.is_empty() {
+            let write_size = cmp::min(self.chunk_size - self.buffer.len(), data.len());
+
+            self.buffer.extend_from_slice(&data[..write_size]);
+            data = &data[write_size..];
+
+            if self.buffer.len() == self.chunk_size {
+                self.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_str(&mut self, text: &str) -> CtrResult {
+        self.write(text.as_bytes())
+    }
+
+    /// Writes any buffered data to the file.
+    ///
+    /// Writes append to the file, so a failed write may have written part of the buffer.
+    /// Only the bytes that didn't make it to the file are kept, so flushing again continues where the write stopped.
+    /// If the file size can't be checked after a failure, the buffer is kept and checked again by the next flush.
+    pub fn flush(&mut self) -> CtrResult {
+        if let Some(start_size) = self.unchecked_write_start {
+            self.discard_written(start_size)?;
+            self.unchecked_write_start = None;
+        }
+
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let file_size = match self.file_size {
+            Some(file_size) => file_size,
+            None => self.file.size()?,
+        };
+
+        match self.file.write(&self.buffer) {
+            Ok(()) => {
+                self.file_size = Some(file_size + self.buffer.len());
+                self.buffer.clear();
+                Ok(())
+            }
+            Err(result) => {
+                if self.discard_written(file_size).is_err() {
+                    self.unchecked_write_start = Some(file_size);
+                }
+
+                Err(result)
+            }
+        }
+    }
+
+    /// Removes the bytes a failed write added to the file from the buffer.
+    fn discard_written(&mut self, start_size: usize) -> CtrResult {
+        let new_file_size = self.file.size()?;
+        let written_size = cmp::min(new_file_size.saturating_sub(start_size), self.buffer.len());
+        self.buffer.drain(..written_size);
+        self.file_size = Some(new_file_size);
+        Ok(())
+    }
+
+    pub fn get_ref(&self) -> &F {
+        &self.file
+    }
+}
+
+impl<F: FileOps> Drop for BufWriter<F> {
+    // If this fails, there's not much to recover from
+    #[allow(unused_must_use)]
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Reads an entire file, reading at most `chunk_size` bytes per fs command.
+pub fn read_to_end(file: &impl FileOps, chunk_size: usize) -> CtrResult<Vec<u8>> {
+    let file_size = file.size()?;
+    let chunk_size = cmp::max(chunk_size, 1);
+    // The buffer grows a chunk at a time, so a bad file size can't reserve more than was read
+    let mut result: Vec<u8> = Vec::new();
+
+    while result.len() < file_size {
+        let start = result.len();
+        let read_size = cmp::min(chunk_size, file_size - start);
+
+        result.resize(start + read_size, 0);
+        let bytes_read = file.read_into(start.try_into()?, &mut result[start..])?;
+        result.truncate(start + bytes_read);
+
+        if bytes_read == 0 {
+            break;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Appends the contents of one file to another and returns the number of bytes copied.
+/// At most `chunk_size` bytes are held in memory at a time.
+pub fn copy(src: &impl FileOps, dst: &mut impl FileOps, chunk_size: usize) -> CtrResult<u64> {
+    let mut buffer: Vec<u8> = vec![0; cmp::max(chunk_size, 1)];
+    let mut offset: u64 = 0;
+
+    loop {
+        let bytes_read = src.read_into(offset, &mut buffer)?;
+
+        if bytes_read == 0 {
+            break;
+        }
+
+        dst.write(&buffer[..bytes_read])?;
+        offset += bytes_read as u64;
+    }
+
+    Ok(offset)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        error,
+        fs::{
+            error as fs_error, ArchiveId, ArchiveOps, FsPath, MemoryArchive, MemoryFile, MemoryFs,
+            MemoryFsFault, OpenFlags,
+        },
+    };
+    use core::convert::TryFrom;
+
+    fn open_sd_archive() -> (MemoryFs, MemoryArchive) {
+        let fs = MemoryFs::new();
+        fs.mount_archive(ArchiveId::Sdmc, &FsPath::new_empty_path());
+        let archive = fs
+            .open_archive(ArchiveId::Sdmc, &FsPath::new_empty_path())
+            .unwrap();
+        (fs, archive)
+    }
+
+    fn open_file(archive: &MemoryArchive, path: &str) -> MemoryFile {
+        archive
+            .open_file(&FsPath::try_from(path).unwrap(), OpenFlags::ReadWriteCreate)
+            .unwrap()
+    }
+
+    fn create_file(archive: &MemoryArchive, path: &str, size: usize) -> MemoryFile {
+        let mut file = open_file(archive, path);
+        let contents = (0..size).map(|index| index as u8).collect::<Vec<u8>>();
+        file.write(&contents).unwrap();
+        file
+    }
+
+    mod buf_reader {
+        use super::*;
+
+        #[test]
+        fn should_read_in_chunks() {
+            let (_fs, archive) = open_sd_archive();
+            let file = create_file(&archive, "/test.bin", 10);
+            let mut reader = BufReader::with_chunk_size(file, 4);
+
+            assert_eq!(reader.fill_buf().unwrap(), &[0, 1, 2, 3]);
+            reader.consume(3);
+            assert_eq!(reader.stream_position(), 3);
+
+            let mut out = [0; 8];
+            assert_eq!(reader.read(&mut out).unwrap(), 1);
+            assert_eq!(out[0], 3);
+            assert_eq!(reader.read(&mut out).unwrap(), 4);
+            assert_eq!(out[..4], [4, 5, 6, 7]);
+            assert_eq!(reader.read(&mut out).unwrap(), 2);
+            assert_eq!(out[..2], [8, 9]);
+            assert_eq!(reader.read(&mut out).unwrap(), 0);
+        }
+
+        #[test]
+        fn should_seek() {
+            let (_fs, archive) = open_sd_archive();
+            let file = create_file(&archive, "/test.bin", 10);
+            let mut reader = BufReader::with_chunk_size(file, 4);
+
+            reader.fill_buf().unwrap();
+            reader.seek(8);
+
+            assert_eq!(reader.fill_buf().unwrap(), &[8, 9]);
+        }
+    }
+
+    mod buf_writer {
+        use super::*;
+
+        #[test]
+        fn should_write_full_chunks() {
+            let (_fs, archive) = open_sd_archive();
+            let file = open_file(&archive, "/test.bin");
+            let mut writer = BufWriter::with_chunk_size(file, 4);
+
+            writer.write(&[0, 1, 2]).unwrap();
+            assert_eq!(writer.get_ref().size().unwrap(), 0);
+
+            writer.write(&[3, 4, 5, 6, 7, 8]).unwrap();
+            assert_eq!(writer.get_ref().size().unwrap(), 8);
+
+            writer.flush().unwrap();
+            assert_eq!(
+                writer.get_ref().read(0, 10).unwrap(),
+                vec![0, 1, 2, 3, 4, 5, 6, 7, 8]
+            );
+        }
+
+        #[test]
+        fn should_write_buffered_data_when_dropped() {
+            let (_fs, archive) = open_sd_archive();
+            let mut writer = BufWriter::new(open_file(&archive, "/test.bin"));
+
+            writer.write_str("test").unwrap();
+            drop(writer);
+
+            let file = open_file(&archive, "/test.bin");
+            assert_eq!(file.read(0, 10).unwrap(), b"test");
+        }
+
+        #[test]
+        fn should_keep_buffered_data_if_a_write_fails() {
+            let (fs, archive) = open_sd_archive();
+            let mut writer = BufWriter::new(open_file(&archive, "/test.bin"));
+            writer.write(&[1, 2]).unwrap();
+
+            fs.inject_fault(MemoryFsFault::DiskFull);
+            assert_eq!(writer.flush().unwrap_err(), fs_error::not_enough_space());
+
+            fs.clear_faults();
+            writer.flush().unwrap();
+            assert_eq!(writer.get_ref().read(0, 10).unwrap(), vec![1, 2]);
+        }
+
+        #[test]
+        fn should_not_write_bytes_twice_after_a_partial_write() {
+            let (fs, archive) = open_sd_archive();
+            let mut file = open_file(&archive, "/test.bin");
+            file.write(&[9]).unwrap();
+            let mut writer = BufWriter::new(file);
+            writer.write(&[1, 2, 3, 4]).unwrap();
+
+            fs.inject_fault(MemoryFsFault::PartialWrite(2, error::busy()));
+            assert_eq!(writer.flush().unwrap_err(), error::busy());
+            assert_eq!(writer.get_ref().read(0, 10).unwrap(), vec![9, 1, 2]);
+
+            fs.clear_faults();
+            writer.flush().unwrap();
+            assert_eq!(writer.get_ref().read(0, 10).unwrap(), vec![9, 1, 2, 3, 4]);
+        }
+
+        #[test]
+        fn should_keep_buffered_data_if_the_size_check_fails() {
+            let (fs, archive) = open_sd_archive();
+            let mut file = open_file(&archive, "/test.bin");
+            file.write(&[9]).unwrap();
+            let mut writer = BufWriter::new(file);
+            writer.write(&[1, 2]).unwrap();
+            writer.flush().unwrap();
+            writer.write(&[3, 4, 5, 6]).unwrap();
+
+            fs.inject_fault(MemoryFsFault::PartialWrite(2, error::busy()));
+            fs.inject_fault(MemoryFsFault::FailedSize(error::busy()));
+            assert_eq!(writer.flush().unwrap_err(), error::busy());
+            fs.clear_faults();
+            fs.inject_fault(MemoryFsFault::FailedSize(error::busy()));
+            assert_eq!(writer.flush().unwrap_err(), error::busy());
+            assert_eq!(writer.get_ref().read(0, 10).unwrap(), vec![9, 1, 2, 3, 4]);
+
+            fs.clear_faults();
+            writer.flush().unwrap();
+            assert_eq!(
+                writer.get_ref().read(0, 10).unwrap(),
+                vec![9, 1, 2, 3, 4, 5, 6]
+            );
+        }
+    }
+
+    mod read_to_end {
+        use super::*;
+
+        #[test]
+        fn should_read_the_whole_file() {
+            let (_fs, archive) = open_sd_archive();
+            let file = create_file(&archive, "/test.bin", 10);
+
+            let result = read_to_end(&file, 3).unwrap();
+            assert_eq!(result, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        }
+
+        #[test]
+        fn should_handle_partial_reads() {
+            let (fs, archive) = open_sd_archive();
+            let file = create_file(&archive, "/test.bin", 10);
+            fs.inject_fault(MemoryFsFault::PartialRead(2));
+
+            let result = read_to_end(&file, 4).unwrap();
+            assert_eq!(result, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        }
+    }
+
+    mod copy {
+        use super::*;
+
+        #[test]
+        fn should_copy_files() {
+            let (_fs, archive) = open_sd_archive();
+            let src = create_file(&archive, "/src.bin", 10);
+            let mut dst = open_file(&archive, "/dst.bin");
+
+            let result = copy(&src, &mut dst, 4).unwrap();
+
+            assert_eq!(result, 10);
+            assert_eq!(dst.read(0, 20).unwrap(), src.read(0, 20).unwrap());
+        }
+
+        #[test]
+        fn should_return_write_errors() {
+            let (fs, archive) = open_sd_archive();
+            let src = create_file(&archive, "/src.bin", 10);
+            let mut dst = open_file(&archive, "/dst.bin");
+            fs.inject_fault(MemoryFsFault::DiskFull);
+
+            let result = copy(&src, &mut dst, 4).unwrap_err();
+            assert_eq!(result, fs_error::not_enough_space());
+        }
+    }
+}
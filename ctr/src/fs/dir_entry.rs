@@ -0,0 +1,21 @@
+use alloc::string::String;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    name: String,
+    file_size: u64,
+}
+
+impl DirEntry {
+    pub(super) fn new(name: String, file_size: u64) -> Self {
+        Self { name, file_size }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn size(&self) -> u64 {
+        self.file_size
+    }
+}
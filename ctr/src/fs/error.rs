@@ -0,0 +1,49 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/Error_codes)
+    and Citra (https://github.com/citra-emu/citra/blob/master/src/core/hle/service/fs/errors.h)
+    for the documentation
+*/
+
+use crate::res::ResultCode;
+
+pub fn archive_not_mounted() -> ResultCode {
+    ResultCode::new_from_raw(0xc8804465)
+}
+
+pub fn file_not_found() -> ResultCode {
+    ResultCode::new_from_raw(0xc8804470)
+}
+
+pub fn path_not_found() -> ResultCode {
+    ResultCode::new_from_raw(0xc8804471)
+}
+
+pub fn file_already_exists() -> ResultCode {
+    ResultCode::new_from_raw(0xc82044b4)
+}
+
+pub fn directory_already_exists() -> ResultCode {
+    ResultCode::new_from_raw(0xc82044b9)
+}
+
+pub fn directory_not_empty() -> ResultCode {
+    ResultCode::new_from_raw(0xc92044f0)
+}
+
+pub fn not_enough_space() -> ResultCode {
+    ResultCode::new_from_raw(0xc86044d2)
+}
+
+pub fn invalid_path() -> ResultCode {
+    ResultCode::new_from_raw(0xe0e046be)
+}
+
+pub fn save_data_not_found() -> ResultCode {
+    ResultCode::new_from_raw(0xc8804478)
+}
+
+/// Returns true if the result means a file doesn't exist.
+/// Most archives return [file_not_found], but SDMC returns the same result as [save_data_not_found].
+pub fn is_not_found(result: ResultCode) -> bool {
+    result == file_not_found() || result == save_data_not_found()
+}
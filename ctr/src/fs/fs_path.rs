@@ -0,0 +1,292 @@
+use super::error as fs_error;
+use crate::{
+    res::{error, CtrResult, ResultCode},
+    utils::convert::bytes_to_utf16le_string,
+};
+use alloc::{string::String, vec, vec::Vec};
+use core::{convert::TryFrom, iter};
+
+/// A path used with the fs module to open archives and files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsPath {
+    Empty(Vec<u8>),
+    Binary(Vec<u8>),
+    Ascii(Vec<u8>),
+    Utf16(Vec<u8>),
+}
+
+impl FsPath {
+    pub fn new_empty_path() -> Self {
+        Self::Empty(vec![])
+    }
+
+    pub fn new_binary<const N: usize>(path: [u32; N]) -> Self {
+        let inner = path.iter().flat_map(|word| word.to_le_bytes()).collect();
+        Self::Binary(inner)
+    }
+
+    /// Creates a normalized ascii path.
+    /// Returns an error if the path is not ascii or can't be normalized.
+    pub fn new_ascii(path: &str) -> CtrResult<Self> {
+        if !path.is_ascii() {
+            return Err(error::invalid_value());
+        }
+
+        let mut inner = normalize_path(path)?.into_bytes();
+        inner.push(0);
+
+        Ok(Self::Ascii(inner))
+    }
+
+    /// Creates a normalized utf16 path.
+    /// Returns an error if the path can't be normalized.
+    pub fn new_utf16(path: &str) -> CtrResult<Self> {
+        let inner = normalize_path(path)?
+            .encode_utf16()
+            .chain(iter::once(0))
+            .flat_map(|short| short.to_le_bytes())
+            .collect();
+
+        Ok(Self::Utf16(inner))
+    }
+
+    pub fn get_raw_type(&self) -> u32 {
+        match self {
+            Self::Empty(_) => 1,
+            Self::Binary(_) => 2,
+            Self::Ascii(_) => 3,
+            Self::Utf16(_) => 4,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Empty(_) => 1,
+            Self::Binary(path) => path.len(),
+            Self::Ascii(path) => path.len() + 1,
+            Self::Utf16(path) => path.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get_inner(&self) -> &[u8] {
+        match self {
+            Self::Empty(path) => path,
+            Self::Binary(path) => path,
+            Self::Ascii(path) => path,
+            Self::Utf16(path) => path,
+        }
+    }
+
+    /// Returns the path as a string.
+    /// Empty paths are the root directory, and binary paths return an error.
+    pub(super) fn to_path_string(&self) -> CtrResult<String> {
+        match self {
+            Self::Empty(_) => Ok(String::from("/")),
+            Self::Ascii(path) => {
+                let path = path.strip_suffix(&[0]).unwrap_or(path);
+                String::from_utf8(path.to_vec()).map_err(|_| fs_error::invalid_path())
+            }
+            Self::Utf16(path) => {
+                bytes_to_utf16le_string(path).map_err(|_| fs_error::invalid_path())
+            }
+            Self::Binary(_) => Err(fs_error::invalid_path()),
+        }
+    }
+}
+
+/// Converts a string into an ascii path if possible, otherwise a utf16 path.
+/// An empty string becomes an empty path.
+impl TryFrom<&str> for FsPath {
+    type Error = ResultCode;
+
+    fn try_from(path: &str) -> CtrResult<Self> {
+        if path.is_empty() {
+            Ok(Self::new_empty_path())
+        } else if path.is_ascii() {
+            Self::new_ascii(path)
+        } else {
+            Self::new_utf16(path)
+        }
+    }
+}
+
+/// Normalizes an absolute path by removing `.` components, resolving `..` components,
+/// and removing repeated and trailing slashes.
+///
+/// Returns an error if the path is not absolute, contains a null character,
+/// or goes above the root directory.
+pub fn normalize_path(path: &str) -> CtrResult<String> {
+    if !path.starts_with('/') || path.contains('\0') {
+        return Err(error::invalid_value());
+    }
+
+    let mut components: Vec<&str> = vec![];
+
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop().ok_or_else(error::invalid_value)?;
+            }
+            _ => components.push(component),
+        }
+    }
+
+    let mut result = String::with_capacity(path.len());
+
+    for component in components.iter() {
+        result.push('/');
+        result.push_str(component);
+    }
+
+    if result.is_empty() {
+        result.push('/');
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::convert::TryInto;
+
+    mod normalize_path {
+        use super::*;
+
+        #[test]
+        fn should_not_change_a_normalized_path() {
+            let result = normalize_path("/3ds/test.txt").unwrap();
+            assert_eq!(result, "/3ds/test.txt");
+        }
+
+        #[test]
+        fn should_remove_repeated_slashes() {
+            let result = normalize_path("//3ds///test.txt").unwrap();
+            assert_eq!(result, "/3ds/test.txt");
+        }
+
+        #[test]
+        fn should_remove_trailing_slashes() {
+            let result = normalize_path("/3ds/dir//").unwrap();
+            assert_eq!(result, "/3ds/dir");
+        }
+
+        #[test]
+        fn should_remove_current_directory_components() {
+            let result = normalize_path("/./3ds/./test.txt").unwrap();
+            assert_eq!(result, "/3ds/test.txt");
+        }
+
+        #[test]
+        fn should_resolve_parent_directory_components() {
+            let result = normalize_path("/3ds/dir/../test.txt").unwrap();
+            assert_eq!(result, "/3ds/test.txt");
+        }
+
+        #[test]
+        fn should_keep_the_root_directory() {
+            let result = normalize_path("/3ds/..").unwrap();
+            assert_eq!(result, "/");
+        }
+
+        #[test]
+        fn should_error_if_the_path_goes_above_the_root_directory() {
+            let result = normalize_path("/3ds/../../test.txt").unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+
+        #[test]
+        fn should_error_if_the_path_is_relative() {
+            let result = normalize_path("3ds/test.txt").unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+
+        #[test]
+        fn should_error_if_the_path_has_a_null_character() {
+            let result = normalize_path("/3ds/te\0st.txt").unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+    }
+
+    mod try_from_str {
+        use super::*;
+
+        #[test]
+        fn should_create_an_empty_path_from_an_empty_string() {
+            let result: FsPath = "".try_into().unwrap();
+            assert_eq!(result, FsPath::new_empty_path());
+        }
+
+        #[test]
+        fn should_create_a_normalized_ascii_path() {
+            let result: FsPath = "/3ds//test.txt".try_into().unwrap();
+            assert_eq!(result, FsPath::Ascii(b"/3ds/test.txt\0".to_vec()));
+            assert_eq!(result.get_raw_type(), 3);
+        }
+
+        #[test]
+        fn should_create_a_utf16_path_for_non_ascii_strings() {
+            let result: FsPath = "/写真/é".try_into().unwrap();
+            assert_eq!(
+                result,
+                FsPath::Utf16(vec![
+                    0x2f, 0x00, 0x99, 0x51, 0x1f, 0x77, 0x2f, 0x00, 0xe9, 0x00, 0x00, 0x00
+                ])
+            );
+            assert_eq!(result.get_raw_type(), 4);
+            assert_eq!(result.len(), 12);
+        }
+
+        #[test]
+        fn should_error_if_the_path_has_a_null_character() {
+            let result = FsPath::try_from("/te\0st.txt").unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+
+        #[test]
+        fn should_error_if_the_path_is_relative() {
+            let result = FsPath::try_from("test.txt").unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+    }
+
+    mod to_path_string {
+        use super::*;
+
+        #[test]
+        fn should_return_the_root_for_empty_paths() {
+            let result = FsPath::new_empty_path().to_path_string().unwrap();
+            assert_eq!(result, "/");
+        }
+
+        #[test]
+        fn should_convert_ascii_and_utf16_paths() {
+            let ascii_path = FsPath::new_ascii("/3ds/test.txt").unwrap();
+            let utf16_path = FsPath::new_utf16("/写真/é").unwrap();
+
+            assert_eq!(ascii_path.to_path_string().unwrap(), "/3ds/test.txt");
+            assert_eq!(utf16_path.to_path_string().unwrap(), "/写真/é");
+        }
+
+        #[test]
+        fn should_error_for_binary_paths() {
+            let result = FsPath::new_binary([0, 0]).to_path_string().unwrap_err();
+            assert_eq!(result, fs_error::invalid_path());
+        }
+    }
+
+    mod new_ascii {
+        use super::*;
+
+        #[test]
+        fn should_error_if_the_path_is_not_ascii() {
+            let result = FsPath::new_ascii("/café").unwrap_err();
+            assert_eq!(result, error::invalid_value());
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     res::{error, CtrResult},
 };
 use alloc::string::String;
+use core::convert::TryInto;
 
 /// A file path.
 /// Supported paths are `sd:/` for sd files and `syssave:/` for system save files.
@@ -38,21 +39,22 @@ impl Path {
                 archive_id: ArchiveId::Sdmc,
                 archive_path: FsPath::new_empty_path(),
                 // Need at least the '/' and one more character - 4 total
-                file_path: path[3..].into(),
+                file_path: path[3..].try_into()?,
             });
         }
 
         // syssave:/0000000000010032/1/friendlist
         if path.starts_with("syssave:/") && path.len() > 26 {
+            // The high word is the media type and the low word is the save id, not a title id.
             let archive_path = u64::from_str_radix(&path[9..25], 16)?;
-            let archive_path_high = (archive_path >> 32) as u32;
-            let archive_path_low = archive_path as u32;
+            let media_type = (archive_path >> 32) as u32;
+            let save_id = archive_path as u32;
 
             return Ok(Self {
                 archive_id: ArchiveId::SystemSaveData,
-                archive_path: FsPath::new_binary([archive_path_high, archive_path_low]),
+                archive_path: FsPath::new_binary([media_type, save_id]),
                 // Need at least the '/' and one more character - 4 total
-                file_path: path[25..].into(),
+                file_path: path[25..].try_into()?,
             });
         }
 
@@ -93,7 +95,7 @@ mod test {
                 Path {
                     archive_id: ArchiveId::Sdmc,
                     archive_path: FsPath::new_empty_path(),
-                    file_path: "/3ds/test.txt".into()
+                    file_path: "/3ds/test.txt".try_into().unwrap()
                 }
             )
         }
@@ -106,11 +108,41 @@ mod test {
                 Path {
                     archive_id: ArchiveId::SystemSaveData,
                     archive_path: FsPath::new_binary([0x00000000, 0x00010032]),
-                    file_path: "/1/friendlist".into()
+                    file_path: "/1/friendlist".try_into().unwrap()
                 }
             )
         }
 
+        #[test]
+        fn should_parse_non_ascii_sd_path() {
+            let result = Path::new_checked("sd:/写真/café.jpg").unwrap();
+            assert_eq!(
+                result.file_path,
+                FsPath::new_utf16("/写真/café.jpg").unwrap()
+            )
+        }
+
+        #[test]
+        fn should_normalize_sd_path() {
+            let result = Path::new_checked("sd:/3ds//dir/../test.txt/").unwrap();
+            assert_eq!(
+                result.file_path,
+                FsPath::new_ascii("/3ds/test.txt").unwrap()
+            )
+        }
+
+        #[test]
+        fn should_error_if_sd_path_goes_above_the_root_directory() {
+            let result = Path::new_checked("sd:/../test.txt").unwrap_err();
+            assert_eq!(result, error::invalid_value())
+        }
+
+        #[test]
+        fn should_error_if_sd_path_has_a_null_character() {
+            let result = Path::new_checked("sd:/te\0st.txt").unwrap_err();
+            assert_eq!(result, error::invalid_value())
+        }
+
         #[test]
         fn should_error_if_only_sd_mount_is_provided() {
             let result = Path::new_checked("sd:/").unwrap_err();
@@ -1,20 +1,34 @@
 use super::Path;
 use crate::{
-    fs::{DirEntry, FsArchive, FsDirectory, OpenFlags},
+    fs::{
+        error as fs_error, read_to_end, ArchiveId, ArchiveOps, DirEntry, FileOps, FsArchive,
+        FsDirectory, FsPath, OpenFlags, DEFAULT_CHUNK_SIZE,
+    },
     res::CtrResult,
     service_session::session,
 };
-use alloc::vec::Vec;
-use core::iter::Iterator;
+use alloc::{format, vec::Vec};
+use core::{convert::TryFrom, iter::Iterator};
 
 /// A convenience function to read an entire file into a vector.
+///
+/// If the file is missing because a [write_atomic] was interrupted, the write is recovered first.
 pub fn read(path: impl Into<Path>) -> CtrResult<Vec<u8>> {
     session!(fs);
     let path = path.into();
     let archive = FsArchive::new(path.archive_id, &path.archive_path)?;
-    let file = archive.open_file(&path.file_path, OpenFlags::Read)?;
-    let file_size = file.size()?;
-    file.read(0, file_size)
+    let file = match archive.open_file(&path.file_path, OpenFlags::Read) {
+        Err(result) if fs_error::is_not_found(result) => {
+            if !recover_atomic_in_archive(&archive, &path.file_path)? {
+                return Err(result);
+            }
+
+            commit_if_save_data(&archive, path.archive_id)?;
+            archive.open_file(&path.file_path, OpenFlags::Read)?
+        }
+        file => file?,
+    };
+    read_to_end(&file, DEFAULT_CHUNK_SIZE)
 }
 
 /// A convenience function to write data to a file.
@@ -26,6 +40,93 @@ pub fn write(path: impl Into<Path>, contents: impl AsRef<[u8]>) -> CtrResult {
     file.write(contents.as_ref())
 }
 
+/// Writes data to a file, replacing the file's contents.
+///
+/// The data is written to a temporary file next to the target file first,
+/// so the target file is never left partially written if the write is interrupted.
+/// Save data archives are committed afterwards.
+pub fn write_atomic(path: impl Into<Path>, contents: impl AsRef<[u8]>) -> CtrResult {
+    session!(fs);
+    let path = path.into();
+    let archive = FsArchive::new(path.archive_id, &path.archive_path)?;
+    write_atomic_in_archive(&archive, &path.file_path, contents.as_ref())?;
+    commit_if_save_data(&archive, path.archive_id)
+}
+
+/// Replaces a file's contents in an archive.  See [write_atomic] for details.
+///
+/// Save data archives need to be committed afterwards.
+pub fn write_atomic_in_archive(
+    archive: &impl ArchiveOps,
+    file_path: &FsPath,
+    contents: &[u8],
+) -> CtrResult {
+    let (temp_path, complete_path) = get_atomic_write_paths(file_path)?;
+
+    recover_atomic_in_archive(archive, file_path)?;
+
+    // The temp file is only renamed once everything has been written,
+    // so an interrupted write never leaves a partial file under the complete path
+    {
+        let mut temp_file = archive.open_file(&temp_path, OpenFlags::ReadWriteCreate)?;
+        temp_file.write(contents)?;
+    }
+
+    archive.rename_file(&temp_path, &complete_path)?;
+    replace_file(archive, &complete_path, file_path)
+}
+
+/// Finishes or cleans up an interrupted [write_atomic_in_archive].
+///
+/// A partially written temp file is removed, and a completely written one replaces the target file.
+/// Returns true if a leftover file was found.
+pub fn recover_atomic_in_archive(archive: &impl ArchiveOps, file_path: &FsPath) -> CtrResult<bool> {
+    let (temp_path, complete_path) = get_atomic_write_paths(file_path)?;
+    let has_temp_file = file_exists(archive, &temp_path)?;
+    let has_complete_file = file_exists(archive, &complete_path)?;
+
+    if has_temp_file {
+        archive.delete_file(&temp_path)?;
+    }
+
+    if has_complete_file {
+        replace_file(archive, &complete_path, file_path)?;
+    }
+
+    Ok(has_temp_file || has_complete_file)
+}
+
+fn get_atomic_write_paths(file_path: &FsPath) -> CtrResult<(FsPath, FsPath)> {
+    let file_path = file_path.to_path_string()?;
+    let temp_path = FsPath::try_from(format!("{}.tmp", file_path).as_str())?;
+    let complete_path = FsPath::try_from(format!("{}.new", file_path).as_str())?;
+    Ok((temp_path, complete_path))
+}
+
+fn file_exists(archive: &impl ArchiveOps, path: &FsPath) -> CtrResult<bool> {
+    match archive.open_file(path, OpenFlags::Read) {
+        Ok(_) => Ok(true),
+        Err(result) if fs_error::is_not_found(result) => Ok(false),
+        Err(result) => Err(result),
+    }
+}
+
+fn replace_file(archive: &impl ArchiveOps, src_path: &FsPath, dst_path: &FsPath) -> CtrResult {
+    if file_exists(archive, dst_path)? {
+        archive.delete_file(dst_path)?;
+    }
+
+    archive.rename_file(src_path, dst_path)
+}
+
+fn commit_if_save_data(archive: &FsArchive, archive_id: ArchiveId) -> CtrResult {
+    if archive_id == ArchiveId::SystemSaveData {
+        archive.commit()?;
+    }
+
+    Ok(())
+}
+
 pub fn create_dir(path: impl Into<Path>) -> CtrResult {
     session!(fs);
     let path = path.into();
@@ -52,3 +153,162 @@ pub fn read_dir(path: impl Into<Path>) -> CtrResult<ReadDir> {
     let directory = archive.open_directory(&path.file_path)?;
     Ok(ReadDir { directory })
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fs::{MemoryArchive, MemoryFs, MemoryFsFault};
+
+    fn path(path: &str) -> FsPath {
+        FsPath::try_from(path).unwrap()
+    }
+
+    fn open_sd_archive() -> (MemoryFs, MemoryArchive) {
+        let fs = MemoryFs::new();
+        fs.mount_archive(ArchiveId::Sdmc, &FsPath::new_empty_path());
+        let archive = fs
+            .open_archive(ArchiveId::Sdmc, &FsPath::new_empty_path())
+            .unwrap();
+        (fs, archive)
+    }
+
+    fn write_file(archive: &MemoryArchive, file_path: &str, contents: &[u8]) {
+        archive
+            .open_file(&path(file_path), OpenFlags::ReadWriteCreate)
+            .unwrap()
+            .write(contents)
+            .unwrap();
+    }
+
+    fn read_file(archive: &MemoryArchive, file_path: &str) -> CtrResult<Vec<u8>> {
+        let file = archive.open_file(&path(file_path), OpenFlags::Read)?;
+        file.read(0, file.size()?)
+    }
+
+    mod write_atomic_in_archive {
+        use super::*;
+
+        #[test]
+        fn should_replace_the_file_contents() {
+            let (_fs, archive) = open_sd_archive();
+            write_file(&archive, "/config.bin", b"old contents");
+
+            write_atomic_in_archive(&archive, &path("/config.bin"), b"new").unwrap();
+
+            assert_eq!(read_file(&archive, "/config.bin").unwrap(), b"new");
+            assert_eq!(
+                read_file(&archive, "/config.bin.tmp").unwrap_err(),
+                fs_error::file_not_found()
+            );
+            assert_eq!(
+                read_file(&archive, "/config.bin.new").unwrap_err(),
+                fs_error::file_not_found()
+            );
+        }
+
+        #[test]
+        fn should_create_the_file_if_it_does_not_exist() {
+            let (_fs, archive) = open_sd_archive();
+
+            write_atomic_in_archive(&archive, &path("/config.bin"), b"new").unwrap();
+
+            assert_eq!(read_file(&archive, "/config.bin").unwrap(), b"new");
+        }
+
+        #[test]
+        fn should_keep_the_old_file_if_the_write_fails() {
+            let (fs, archive) = open_sd_archive();
+            write_file(&archive, "/config.bin", b"old contents");
+            fs.inject_fault(MemoryFsFault::PartialWrite(2, fs_error::not_enough_space()));
+
+            let result =
+                write_atomic_in_archive(&archive, &path("/config.bin"), b"new").unwrap_err();
+            assert_eq!(result, fs_error::not_enough_space());
+
+            fs.clear_faults();
+            assert_eq!(read_file(&archive, "/config.bin").unwrap(), b"old contents");
+
+            write_atomic_in_archive(&archive, &path("/config.bin"), b"new").unwrap();
+            assert_eq!(read_file(&archive, "/config.bin").unwrap(), b"new");
+        }
+
+        #[test]
+        fn should_handle_the_sdmc_not_found_result() {
+            let (fs, archive) = open_sd_archive();
+            fs.inject_fault(MemoryFsFault::SdmcNotFound);
+
+            write_atomic_in_archive(&archive, &path("/config.bin"), b"new").unwrap();
+            write_atomic_in_archive(&archive, &path("/config.bin"), b"newer").unwrap();
+
+            assert_eq!(read_file(&archive, "/config.bin").unwrap(), b"newer");
+        }
+
+        #[test]
+        fn should_error_if_the_archive_is_not_mounted() {
+            let (fs, archive) = open_sd_archive();
+            fs.inject_fault(MemoryFsFault::ArchiveNotMounted);
+
+            let result =
+                write_atomic_in_archive(&archive, &path("/config.bin"), b"new").unwrap_err();
+            assert_eq!(result, fs_error::archive_not_mounted());
+        }
+    }
+
+    mod recover_atomic_in_archive {
+        use super::*;
+
+        #[test]
+        fn should_remove_partially_written_files() {
+            let (_fs, archive) = open_sd_archive();
+            write_file(&archive, "/config.bin.tmp", b"ne");
+
+            let result = recover_atomic_in_archive(&archive, &path("/config.bin")).unwrap();
+
+            assert!(result);
+            assert_eq!(
+                read_file(&archive, "/config.bin.tmp").unwrap_err(),
+                fs_error::file_not_found()
+            );
+            assert_eq!(
+                read_file(&archive, "/config.bin").unwrap_err(),
+                fs_error::file_not_found()
+            );
+        }
+
+        #[test]
+        fn should_move_completely_written_files_into_place() {
+            let (_fs, archive) = open_sd_archive();
+            write_file(&archive, "/config.bin", b"old contents");
+            write_file(&archive, "/config.bin.new", b"new");
+
+            let result = recover_atomic_in_archive(&archive, &path("/config.bin")).unwrap();
+
+            assert!(result);
+            assert_eq!(read_file(&archive, "/config.bin").unwrap(), b"new");
+            assert_eq!(
+                read_file(&archive, "/config.bin.new").unwrap_err(),
+                fs_error::file_not_found()
+            );
+        }
+
+        #[test]
+        fn should_do_nothing_without_leftover_files() {
+            let (_fs, archive) = open_sd_archive();
+            write_file(&archive, "/config.bin", b"old contents");
+
+            let result = recover_atomic_in_archive(&archive, &path("/config.bin")).unwrap();
+
+            assert!(!result);
+
+            assert_eq!(read_file(&archive, "/config.bin").unwrap(), b"old contents");
+        }
+
+        #[test]
+        fn should_error_for_binary_paths() {
+            let (_fs, archive) = open_sd_archive();
+            let result =
+                recover_atomic_in_archive(&archive, &FsPath::new_binary([0, 0])).unwrap_err();
+            assert_eq!(result, fs_error::invalid_path());
+        }
+    }
+}
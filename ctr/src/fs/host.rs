@@ -0,0 +1,326 @@
+use super::{
+    error as fs_error, ArchiveId, DirEntry, FsPath, OpenFlags, SaveDataConfig, SystemSaveDataInfo,
+};
+use crate::res::{error, CtrResult, ResultCode};
+use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+use core::{cell::RefCell, convert::TryInto};
+use std::{
+    env, fs, io,
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path as HostPath, PathBuf},
+    sync::Mutex,
+};
+
+static HOST_ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
+static SECURE_VALUES: Mutex<BTreeMap<u32, u64>> = Mutex::new(BTreeMap::new());
+
+/// Sets the host directory that archives are mapped to.
+///
+/// Archives are directories inside of the root:
+/// - `<root>/sdmc` for the sd card
+/// - `<root>/syssave/<save_id>` for system save data, e.g. `<root>/syssave/0000000000010032`
+///
+/// If a root isn't set, the `CTR_HOST_FS_ROOT` environment variable is used,
+/// followed by the current directory.
+pub fn set_host_root(root: impl Into<PathBuf>) {
+    *HOST_ROOT.lock().unwrap() = Some(root.into());
+}
+
+fn get_host_root() -> PathBuf {
+    HOST_ROOT
+        .lock()
+        .unwrap()
+        .clone()
+        .or_else(|| env::var_os("CTR_HOST_FS_ROOT").map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+fn get_archive_directory(id: ArchiveId, path: &FsPath) -> CtrResult<PathBuf> {
+    let root = get_host_root();
+
+    match (id, path) {
+        (ArchiveId::Sdmc, FsPath::Empty(_)) => Ok(root.join("sdmc")),
+        (ArchiveId::SystemSaveData, FsPath::Binary(binary_path)) => {
+            if binary_path.len() != 8 {
+                return Err(fs_error::invalid_path());
+            }
+
+            let high = u32::from_le_bytes(binary_path[0..4].try_into().unwrap());
+            let low = u32::from_le_bytes(binary_path[4..8].try_into().unwrap());
+            let save_id = ((high as u64) << 32) | (low as u64);
+            Ok(root.join("syssave").join(format!("{:016x}", save_id)))
+        }
+        _ => Err(fs_error::invalid_path()),
+    }
+}
+
+fn get_relative_path(path: &FsPath) -> CtrResult<String> {
+    // FsPaths are normalized, so removing the root is enough to keep the path inside the archive
+    Ok(String::from(path.to_path_string()?.trim_start_matches('/')))
+}
+
+fn map_io_error(io_error: io::Error) -> ResultCode {
+    // ENOSPC
+    if io_error.raw_os_error() == Some(28) {
+        return fs_error::not_enough_space();
+    }
+
+    match io_error.kind() {
+        io::ErrorKind::NotFound => fs_error::file_not_found(),
+        io::ErrorKind::AlreadyExists => fs_error::file_already_exists(),
+        io::ErrorKind::PermissionDenied => error::not_authorized(),
+        _ => error::invalid_value(),
+    }
+}
+
+/// Opens a file.
+/// The file is closed automatically when dropped.
+pub struct File {
+    file: fs::File,
+}
+
+impl File {
+    fn new_from_host_path(path: &HostPath, flags: OpenFlags) -> CtrResult<Self> {
+        let raw_flags = flags as u32;
+        let file = fs::OpenOptions::new()
+            .read(raw_flags & OpenFlags::Read as u32 != 0)
+            .write(raw_flags & (OpenFlags::Write as u32 | OpenFlags::Create as u32) != 0)
+            .create(raw_flags & OpenFlags::Create as u32 != 0)
+            .open(path)
+            .map_err(map_io_error)?;
+
+        if file.metadata().map_err(map_io_error)?.is_dir() {
+            return Err(fs_error::file_not_found());
+        }
+
+        Ok(Self { file })
+    }
+
+    pub fn new(
+        archive_path: &FsPath,
+        file_path: &FsPath,
+        archive_id: ArchiveId,
+        flags: OpenFlags,
+    ) -> CtrResult<Self> {
+        FsArchive::new(archive_id, archive_path)?.open_file(file_path, flags)
+    }
+
+    pub fn write_str(&mut self, text: &str) -> CtrResult {
+        self.write(text.as_bytes())
+    }
+
+    pub fn write(&mut self, data: &[u8]) -> CtrResult {
+        self.file.seek(SeekFrom::End(0)).map_err(map_io_error)?;
+        self.file.write_all(data).map_err(map_io_error)?;
+        self.file.flush().map_err(map_io_error)
+    }
+
+    pub fn read(&self, offset: u64, max_size: usize) -> CtrResult<Vec<u8>> {
+        let mut buffer: Vec<u8> = vec![0; max_size];
+        let bytes_read = self.read_into(offset, &mut buffer)?;
+
+        buffer.truncate(bytes_read);
+
+        Ok(buffer)
+    }
+
+    /// Reads into a buffer and returns the number of bytes read.
+    pub fn read_into(&self, offset: u64, buffer: &mut [u8]) -> CtrResult<usize> {
+        let mut file = &self.file;
+        let mut bytes_read = 0;
+
+        file.seek(SeekFrom::Start(offset)).map_err(map_io_error)?;
+
+        while bytes_read < buffer.len() {
+            let read_size = file.read(&mut buffer[bytes_read..]).map_err(map_io_error)?;
+
+            if read_size == 0 {
+                break;
+            }
+
+            bytes_read += read_size;
+        }
+
+        Ok(bytes_read)
+    }
+
+    pub fn size(&self) -> CtrResult<usize> {
+        let size = self
+            .file
+            .metadata()
+            .map_err(map_io_error)?
+            .len()
+            .try_into()?;
+        Ok(size)
+    }
+}
+
+/// Opens an archive containing directories and files.
+/// Archives are mapped to host directories, see [set_host_root].
+pub struct FsArchive {
+    root: PathBuf,
+}
+
+impl FsArchive {
+    pub fn new(id: ArchiveId, path: &FsPath) -> CtrResult<Self> {
+        let root = get_archive_directory(id, path)?;
+
+        if !root.is_dir() {
+            return match id {
+                ArchiveId::SystemSaveData => Err(fs_error::save_data_not_found()),
+                _ => Err(fs_error::archive_not_mounted()),
+            };
+        }
+
+        Ok(Self { root })
+    }
+
+    fn get_host_path(&self, path: &FsPath) -> CtrResult<PathBuf> {
+        Ok(self.root.join(get_relative_path(path)?))
+    }
+
+    pub fn open_file(&self, path: &FsPath, flags: OpenFlags) -> CtrResult<File> {
+        File::new_from_host_path(&self.get_host_path(path)?, flags)
+    }
+
+    pub fn delete_file(&self, path: &FsPath) -> CtrResult {
+        let path = self.get_host_path(path)?;
+
+        if !path.is_file() {
+            return Err(fs_error::file_not_found());
+        }
+
+        fs::remove_file(path).map_err(map_io_error)
+    }
+
+    pub fn rename_file(&self, src_path: &FsPath, dst_path: &FsPath) -> CtrResult {
+        let src_path = self.get_host_path(src_path)?;
+        let dst_path = self.get_host_path(dst_path)?;
+
+        if !src_path.is_file() {
+            return Err(fs_error::file_not_found());
+        }
+
+        if dst_path.exists() {
+            return Err(fs_error::file_already_exists());
+        }
+
+        fs::rename(src_path, dst_path).map_err(map_io_error)
+    }
+
+    pub fn create_directory(&self, path: &FsPath, _attributes: u32) -> CtrResult {
+        fs::create_dir(self.get_host_path(path)?).map_err(|io_error| match io_error.kind() {
+            io::ErrorKind::AlreadyExists => fs_error::directory_already_exists(),
+            io::ErrorKind::NotFound => fs_error::path_not_found(),
+            _ => map_io_error(io_error),
+        })
+    }
+
+    pub fn rename_directory(&self, src_path: &FsPath, dst_path: &FsPath) -> CtrResult {
+        let src_path = self.get_host_path(src_path)?;
+        let dst_path = self.get_host_path(dst_path)?;
+
+        if !src_path.is_dir() {
+            return Err(fs_error::path_not_found());
+        }
+
+        if dst_path.exists() {
+            return Err(fs_error::directory_already_exists());
+        }
+
+        fs::rename(src_path, dst_path).map_err(map_io_error)
+    }
+
+    pub fn open_directory(&self, path: &FsPath) -> CtrResult<FsDirectory> {
+        let entries =
+            fs::read_dir(self.get_host_path(path)?).map_err(|io_error| match io_error.kind() {
+                io::ErrorKind::NotFound => fs_error::path_not_found(),
+                _ => map_io_error(io_error),
+            })?;
+
+        Ok(FsDirectory {
+            entries: RefCell::new(entries),
+        })
+    }
+
+    /// Changes are written immediately, so there's nothing to commit.
+    pub fn commit(&self) -> CtrResult {
+        Ok(())
+    }
+}
+
+pub struct FsDirectory {
+    entries: RefCell<fs::ReadDir>,
+}
+
+impl FsDirectory {
+    /// Reads the next directory entry.
+    pub(super) fn read_next(&self) -> CtrResult<Option<DirEntry>> {
+        let entry = match self.entries.borrow_mut().next() {
+            Some(entry) => entry.map_err(map_io_error)?,
+            None => return Ok(None),
+        };
+
+        let name = entry
+            .file_name()
+            .into_string()
+            .map_err(|_| error::invalid_value())?;
+        let metadata = entry.metadata().map_err(map_io_error)?;
+        let file_size = if metadata.is_dir() { 0 } else { metadata.len() };
+
+        Ok(Some(DirEntry::new(name, file_size)))
+    }
+}
+
+fn get_system_save_data_directory(info: SystemSaveDataInfo) -> CtrResult<PathBuf> {
+    let path = FsPath::new_binary([info.media_type.into(), info.save_id]);
+    get_archive_directory(ArchiveId::SystemSaveData, &path)
+}
+
+pub(super) fn create_system_save_data(
+    info: SystemSaveDataInfo,
+    _config: &SaveDataConfig,
+) -> CtrResult {
+    let directory = get_system_save_data_directory(info)?;
+
+    if directory.exists() {
+        return Err(fs_error::directory_already_exists());
+    }
+
+    fs::create_dir_all(directory).map_err(map_io_error)
+}
+
+pub(super) fn delete_system_save_data(info: SystemSaveDataInfo) -> CtrResult {
+    let directory = get_system_save_data_directory(info)?;
+
+    if !directory.is_dir() {
+        return Err(fs_error::save_data_not_found());
+    }
+
+    fs::remove_dir_all(directory).map_err(map_io_error)
+}
+
+pub(super) fn format_save_data(
+    archive_id: ArchiveId,
+    path: &FsPath,
+    _config: &SaveDataConfig,
+) -> CtrResult {
+    let directory = get_archive_directory(archive_id, path)?;
+
+    if !directory.is_dir() {
+        return Err(fs_error::save_data_not_found());
+    }
+
+    fs::remove_dir_all(&directory).map_err(map_io_error)?;
+    fs::create_dir(directory).map_err(map_io_error)
+}
+
+/// Secure values are only kept in memory for the host backend.
+pub(super) fn get_secure_value(slot: u32) -> CtrResult<Option<u64>> {
+    Ok(SECURE_VALUES.lock().unwrap().get(&slot).copied())
+}
+
+pub(super) fn set_secure_value(slot: u32, value: u64) -> CtrResult {
+    SECURE_VALUES.lock().unwrap().insert(slot, value);
+    Ok(())
+}
@@ -1,13 +1,13 @@
+use super::{FsPath, SaveDataConfig};
 use crate::{
     ipc::{Command, CurrentProcessId, StaticBuffer},
-    res::{error, CtrResult},
+    res::{error, CtrResult, INVALID_ENUM_VALUE_MESSAGE},
     service_session::create_session_manager,
     srv::get_service_handle_direct,
-    Handle,
+    Handle, TitleId,
 };
 use alloc::{str, vec, vec::Vec};
 use core::{convert::Into, mem};
-use cstr_core::CString;
 use no_std_io::{EndianRead, EndianWrite, ReadOutput, Writer};
 use num_enum::IntoPrimitive;
 
@@ -19,11 +19,27 @@ pub struct ProductInfo {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[repr(u8)]
 pub enum MediaType {
-    Nand = 0,
-    Sd = 1,
-    GameCard = 2,
+    Nand,
+    Sd,
+    GameCard,
+    /// A media type this crate doesn't know about.
+    /// This is only created by [MediaType::from], since reading a media type is strict.
+    Unknown(u8),
+}
+
+impl MediaType {
+    /// Converts a raw media type, and returns an error if the media type is unknown.
+    pub fn try_from_raw(raw_media_type: u8) -> CtrResult<Self> {
+        match Self::from(raw_media_type) {
+            MediaType::Unknown(_) => Err(error::invalid_enum_value()),
+            media_type => Ok(media_type),
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        !matches!(self, MediaType::Unknown(_))
+    }
 }
 
 impl From<u8> for MediaType {
@@ -32,18 +48,43 @@ impl From<u8> for MediaType {
             0 => MediaType::Nand,
             1 => MediaType::Sd,
             2 => MediaType::GameCard,
-            _ => MediaType::Nand,
+            _ => MediaType::Unknown(raw_media_type),
         }
     }
 }
 
+impl From<MediaType> for u8 {
+    fn from(media_type: MediaType) -> Self {
+        match media_type {
+            MediaType::Nand => 0,
+            MediaType::Sd => 1,
+            MediaType::GameCard => 2,
+            MediaType::Unknown(raw_media_type) => raw_media_type,
+        }
+    }
+}
+
+impl From<MediaType> for u32 {
+    fn from(media_type: MediaType) -> Self {
+        u8::from(media_type) as u32
+    }
+}
+
 impl EndianRead for MediaType {
     fn try_read_le(bytes: &[u8]) -> Result<ReadOutput<Self>, no_std_io::Error> {
-        Ok(u8::try_read_le(bytes)?.into_other())
+        let raw_media_type = u8::try_read_le(bytes)?.into_data();
+
+        match MediaType::from(raw_media_type) {
+            MediaType::Unknown(_) => Err(no_std_io::Error::InvalidRead {
+                message: INVALID_ENUM_VALUE_MESSAGE,
+            }),
+            media_type => Ok(ReadOutput::new(media_type, mem::size_of::<u8>())),
+        }
     }
 
-    fn try_read_be(_bytes: &[u8]) -> Result<ReadOutput<Self>, no_std_io::Error> {
-        unimplemented!()
+    fn try_read_be(bytes: &[u8]) -> Result<ReadOutput<Self>, no_std_io::Error> {
+        // A single byte is the same in either endianness
+        Self::try_read_le(bytes)
     }
 }
 
@@ -53,18 +94,18 @@ impl EndianWrite for MediaType {
     }
 
     fn try_write_le(&self, mut dst: &mut [u8]) -> Result<usize, no_std_io::Error> {
-        dst.write_le(0, &(*self as u8))
+        dst.write_le(0, &u8::from(*self))
     }
 
-    fn try_write_be(&self, _dst: &mut [u8]) -> Result<usize, no_std_io::Error> {
-        unimplemented!()
+    fn try_write_be(&self, dst: &mut [u8]) -> Result<usize, no_std_io::Error> {
+        self.try_write_le(dst)
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, EndianRead, EndianWrite)]
 #[repr(C)]
 pub struct ProgramInfo {
-    pub program_id: u64,
+    pub program_id: TitleId,
     pub media_type: MediaType,
     pub padding: [u8; 7],
 }
@@ -72,7 +113,7 @@ pub struct ProgramInfo {
 impl Default for ProgramInfo {
     fn default() -> Self {
         Self {
-            program_id: 0,
+            program_id: TitleId::default(),
             media_type: MediaType::Nand,
             padding: [0; 7],
         }
@@ -86,6 +127,30 @@ pub enum ArchiveId {
     Sdmc = 9,
 }
 
+#[derive(IntoPrimitive)]
+#[repr(u32)]
+pub enum ArchiveAction {
+    CommitSaveData = 0,
+}
+
+/// Identifies system save data, which is used as the binary path of a system save archive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EndianRead, EndianWrite)]
+pub struct SystemSaveDataInfo {
+    pub media_type: MediaType,
+    pub padding: [u8; 3],
+    pub save_id: u32,
+}
+
+impl SystemSaveDataInfo {
+    pub fn new(media_type: MediaType, save_id: u32) -> Self {
+        Self {
+            media_type,
+            padding: [0; 3],
+            save_id,
+        }
+    }
+}
+
 #[derive(IntoPrimitive)]
 #[repr(u32)]
 pub enum WriteFlags {
@@ -101,64 +166,7 @@ pub enum OpenFlags {
     ReadWriteCreate = 7,
 }
 
-/// A path used with the fs module to open archives and files.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum FsPath {
-    Empty(Vec<u8>),
-    Binary(Vec<u8>),
-    Ascii(Vec<u8>),
-}
-
-impl FsPath {
-    pub fn new_empty_path() -> Self {
-        Self::Empty(vec![])
-    }
-
-    pub fn new_binary(path: [u32; 2]) -> Self {
-        let inner = path.iter().flat_map(|word| word.to_le_bytes()).collect();
-        Self::Binary(inner)
-    }
-
-    pub fn get_raw_type(&self) -> u32 {
-        match self {
-            Self::Empty(_) => 1,
-            Self::Binary(_) => 2,
-            Self::Ascii(_) => 3,
-        }
-    }
-
-    pub fn len(&self) -> usize {
-        match self {
-            Self::Empty(_) => 1,
-            Self::Binary(path) => path.len(),
-            Self::Ascii(path) => path.len() + 1,
-        }
-    }
-
-    pub fn is_empty(&self) -> bool {
-        self.len() == 0
-    }
-
-    pub fn get_inner(&self) -> &[u8] {
-        match self {
-            Self::Empty(path) => path,
-            Self::Binary(path) => path,
-            Self::Ascii(path) => path,
-        }
-    }
-}
-
-impl From<&str> for FsPath {
-    fn from(path: &str) -> Self {
-        if path.is_empty() {
-            Self::new_empty_path()
-        } else {
-            let c_path = CString::new(path).unwrap_or_default();
-            Self::Ascii(c_path.into_bytes_with_nul())
-        }
-    }
-}
-
+#[cfg(not(feature = "host_fs"))]
 create_session_manager!({
     let handle = get_service_handle_direct("fs:USER")?;
     user::initialize_with_sdk_version(&handle, 0x90c00c8)?;
@@ -166,8 +174,13 @@ create_session_manager!({
     handle
 });
 
+// The host backend doesn't use the fs service
+#[cfg(feature = "host_fs")]
+create_session_manager!(mock);
+
 pub mod user {
     use super::*;
+    use crate::ipc::PermissionBuffer;
 
     pub fn set_priority(session: &Handle, priority: u32) -> CtrResult {
         let raw_handle = unsafe { session.get_raw() };
@@ -289,6 +302,54 @@ pub mod user {
         Command::new(0x80E0080, raw_archive_handle).send(get_handle())
     }
 
+    #[derive(Debug, EndianRead, EndianWrite)]
+    struct DeleteFileIn {
+        zero: u32,
+        raw_archive_handle: u64,
+        path_type: u32,
+        path_len: u32,
+        path_buf: StaticBuffer,
+    }
+
+    pub fn delete_file(raw_archive_handle: u64, path: &FsPath) -> CtrResult {
+        let input = DeleteFileIn {
+            zero: 0,
+            raw_archive_handle,
+            path_type: path.get_raw_type(),
+            path_len: path.len() as u32,
+            path_buf: StaticBuffer::new(path.get_inner(), 0),
+        };
+        Command::new(0x8040142, input).send(get_handle())
+    }
+
+    #[derive(Debug, EndianRead, EndianWrite)]
+    struct RenameFileIn {
+        zero: u32,
+        src_archive_handle: u64,
+        src_path_type: u32,
+        src_path_len: u32,
+        dst_archive_handle: u64,
+        dst_path_type: u32,
+        dst_path_len: u32,
+        src_path_buf: StaticBuffer,
+        dst_path_buf: StaticBuffer,
+    }
+
+    pub fn rename_file(raw_archive_handle: u64, src_path: &FsPath, dst_path: &FsPath) -> CtrResult {
+        let input = RenameFileIn {
+            zero: 0,
+            src_archive_handle: raw_archive_handle,
+            src_path_type: src_path.get_raw_type(),
+            src_path_len: src_path.len() as u32,
+            dst_archive_handle: raw_archive_handle,
+            dst_path_type: dst_path.get_raw_type(),
+            dst_path_len: dst_path.len() as u32,
+            src_path_buf: StaticBuffer::new(src_path.get_inner(), 1),
+            dst_path_buf: StaticBuffer::new(dst_path.get_inner(), 2),
+        };
+        Command::new(0x8050244, input).send(get_handle())
+    }
+
     #[derive(Debug, EndianRead, EndianWrite)]
     struct CreateDirectoryIn {
         zero: u32,
@@ -368,6 +429,129 @@ pub mod user {
         Ok(result.handle.into())
     }
 
+    #[derive(EndianRead, EndianWrite)]
+    struct ControlArchiveIn {
+        raw_archive_handle: u64,
+        action: u32,
+        input_len: u32,
+        output_len: u32,
+        input_buf: PermissionBuffer,
+        output_buf: PermissionBuffer,
+    }
+
+    pub fn control_archive(
+        raw_archive_handle: u64,
+        action: ArchiveAction,
+        input: &[u8],
+        output: &mut [u8],
+    ) -> CtrResult {
+        let input = ControlArchiveIn {
+            raw_archive_handle,
+            action: action.into(),
+            input_len: input.len() as u32,
+            output_len: output.len() as u32,
+            input_buf: PermissionBuffer::new_read(input),
+            output_buf: PermissionBuffer::new_write(output),
+        };
+        Command::new(0x80D0144, input).send(get_handle())
+    }
+
+    #[derive(EndianRead, EndianWrite)]
+    struct FormatSaveDataIn {
+        archive_id: u32,
+        path_type: u32,
+        path_len: u32,
+        block_size: u32,
+        max_directories: u32,
+        max_files: u32,
+        directory_buckets: u32,
+        file_buckets: u32,
+        duplicate_data: u32,
+        path_buf: StaticBuffer,
+    }
+
+    pub fn format_save_data(
+        archive_id: ArchiveId,
+        path: &FsPath,
+        config: &SaveDataConfig,
+    ) -> CtrResult {
+        let input = FormatSaveDataIn {
+            archive_id: archive_id.into(),
+            path_type: path.get_raw_type(),
+            path_len: path.len() as u32,
+            block_size: config.block_size,
+            max_directories: config.max_directories,
+            max_files: config.max_files,
+            directory_buckets: config.directory_buckets,
+            file_buckets: config.file_buckets,
+            duplicate_data: config.duplicate_data.into(),
+            path_buf: StaticBuffer::new(path.get_inner(), 0),
+        };
+        Command::new(0x84C0242, input).send(get_handle())
+    }
+
+    #[derive(EndianRead, EndianWrite)]
+    struct CreateSystemSaveDataIn {
+        info: SystemSaveDataInfo,
+        total_size: u32,
+        block_size: u32,
+        max_directories: u32,
+        max_files: u32,
+        directory_buckets: u32,
+        file_buckets: u32,
+        duplicate_data: u32,
+    }
+
+    pub fn create_system_save_data(info: SystemSaveDataInfo, config: &SaveDataConfig) -> CtrResult {
+        let input = CreateSystemSaveDataIn {
+            info,
+            total_size: config.total_size,
+            block_size: config.block_size,
+            max_directories: config.max_directories,
+            max_files: config.max_files,
+            directory_buckets: config.directory_buckets,
+            file_buckets: config.file_buckets,
+            duplicate_data: config.duplicate_data.into(),
+        };
+        Command::new(0x8560240, input).send(get_handle())
+    }
+
+    pub fn delete_system_save_data(info: SystemSaveDataInfo) -> CtrResult {
+        Command::new(0x8570080, info).send(get_handle())
+    }
+
+    #[derive(EndianRead, EndianWrite)]
+    struct SetThisSaveDataSecureValueIn {
+        slot: u32,
+        value: u64,
+    }
+
+    pub fn set_this_save_data_secure_value(slot: u32, value: u64) -> CtrResult {
+        let input = SetThisSaveDataSecureValueIn { slot, value };
+        Command::new(0x86E00C0, input).send(get_handle())
+    }
+
+    #[derive(EndianRead, EndianWrite)]
+    struct GetThisSaveDataSecureValueOut {
+        exists: u8,
+        padding_0: [u8; 3],
+        is_game_card: u8,
+        padding_1: [u8; 3],
+        value: u64,
+    }
+
+    /// Returns the secure value in a slot, or None if the slot hasn't been set.
+    pub fn get_this_save_data_secure_value(slot: u32) -> CtrResult<Option<u64>> {
+        let result: GetThisSaveDataSecureValueOut =
+            Command::new(0x86F0040, slot).send(get_handle())?;
+
+        if result.exists == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(result.value))
+    }
+
     pub fn get_program_launch_info(process_id: u32) -> CtrResult<ProgramInfo> {
         Command::new(0x82F0040, process_id).send(get_handle())
     }
@@ -433,17 +617,25 @@ pub mod file {
     /// The output size is not guaranteed to be the max read size.
     pub fn read(handle: &Handle, offset: u64, max_read_size: usize) -> CtrResult<Vec<u8>> {
         let mut out_buffer: Vec<u8> = vec![0; max_read_size];
+        let bytes_read = read_into(handle, offset, &mut out_buffer)?;
+
+        out_buffer.truncate(bytes_read);
+
+        Ok(out_buffer)
+    }
+
+    /// Reads from a file into a buffer and returns the number of bytes read.
+    /// The number of bytes read is not guaranteed to be the buffer size.
+    pub fn read_into(handle: &Handle, offset: u64, out_buffer: &mut [u8]) -> CtrResult<usize> {
         let input = FileReadIn {
-            max_read_size: max_read_size as u32,
+            max_read_size: out_buffer.len() as u32,
             offset,
-            out_buffer: PermissionBuffer::new_write(&mut out_buffer),
+            out_buffer: PermissionBuffer::new_write(out_buffer),
         };
         let raw_handle = unsafe { handle.get_raw() };
         let result: FileReadOut = Command::new(0x80200C2, input).send(raw_handle)?;
 
-        out_buffer.resize(result.bytes_read as usize, 0);
-
-        Ok(out_buffer)
+        Ok(result.bytes_read as usize)
     }
 
     pub fn close(handle: &Handle) -> CtrResult {
@@ -508,3 +700,127 @@ pub mod dir {
         Ok(Some(entry))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::TitleId;
+    use no_std_io::Reader;
+
+    mod media_type {
+        use super::*;
+
+        #[test]
+        fn should_map_unknown_values_to_unknown() {
+            assert_eq!(MediaType::from(1), MediaType::Sd);
+            assert_eq!(MediaType::from(7), MediaType::Unknown(7));
+            assert_eq!(u8::from(MediaType::Unknown(7)), 7);
+        }
+
+        #[test]
+        fn should_error_when_converting_unknown_values_strictly() {
+            assert_eq!(MediaType::try_from_raw(2), Ok(MediaType::GameCard));
+            assert_eq!(
+                MediaType::try_from_raw(7).unwrap_err(),
+                error::invalid_enum_value()
+            );
+        }
+
+        #[test]
+        fn should_error_when_reading_unknown_values() {
+            let bytes = [7u8];
+
+            let le_result: CtrResult<MediaType> = bytes.read_le(0).map_err(Into::into);
+            let be_result: CtrResult<MediaType> = bytes.read_be(0).map_err(Into::into);
+
+            assert_eq!(le_result.unwrap_err(), error::invalid_enum_value());
+            assert_eq!(be_result.unwrap_err(), error::invalid_enum_value());
+        }
+    }
+
+    mod program_info {
+        use super::*;
+
+        fn create_program_info() -> ProgramInfo {
+            ProgramInfo {
+                program_id: TitleId::new(0x0004013000003202),
+                media_type: MediaType::Sd,
+                padding: [0; 7],
+            }
+        }
+
+        #[test]
+        fn should_round_trip_little_endian() {
+            let mut bytes = [0u8; 0x10];
+            bytes.write_le(0, &create_program_info()).unwrap();
+
+            assert_eq!(bytes[..8], 0x0004013000003202u64.to_le_bytes());
+            assert_eq!(bytes[8], 1);
+            assert_eq!(
+                bytes.read_le::<ProgramInfo>(0).unwrap(),
+                create_program_info()
+            );
+        }
+
+        #[test]
+        fn should_round_trip_big_endian() {
+            let mut bytes = [0u8; 0x10];
+            bytes.write_be(0, &create_program_info()).unwrap();
+
+            assert_eq!(bytes[..8], 0x0004013000003202u64.to_be_bytes());
+            assert_eq!(
+                bytes.read_be::<ProgramInfo>(0).unwrap(),
+                create_program_info()
+            );
+        }
+
+        #[test]
+        fn should_error_if_the_media_type_is_unknown() {
+            let mut bytes = [0u8; 0x10];
+            bytes[8] = 3;
+
+            let result: CtrResult<ProgramInfo> = bytes.read_le(0).map_err(Into::into);
+            assert_eq!(result.unwrap_err(), error::invalid_enum_value());
+        }
+    }
+
+    mod product_info {
+        use super::*;
+
+        #[test]
+        fn should_round_trip() {
+            let mut product_code = [0; 0x10];
+            product_code[..10].copy_from_slice(b"CTR-P-TEST");
+            let product_info = ProductInfo {
+                product_code,
+                company_code: *b"01",
+                remaster_version: 2,
+            };
+
+            let mut le_bytes = [0u8; 0x14];
+            le_bytes.write_le(0, &product_info).unwrap();
+            let mut be_bytes = [0u8; 0x14];
+            be_bytes.write_be(0, &product_info).unwrap();
+
+            assert_eq!(le_bytes[0x12..], [2, 0]);
+            assert_eq!(be_bytes[0x12..], [0, 2]);
+            assert_eq!(le_bytes.read_le::<ProductInfo>(0).unwrap(), product_info);
+            assert_eq!(be_bytes.read_be::<ProductInfo>(0).unwrap(), product_info);
+        }
+    }
+
+    mod system_save_data_info {
+        use super::*;
+
+        #[test]
+        fn should_round_trip() {
+            let info = SystemSaveDataInfo::new(MediaType::Nand, 0x10032);
+
+            let mut bytes = [0u8; 8];
+            bytes.write_le(0, &info).unwrap();
+
+            assert_eq!(bytes, [0, 0, 0, 0, 0x32, 0, 1, 0]);
+            assert_eq!(bytes.read_le::<SystemSaveDataInfo>(0).unwrap(), info);
+        }
+    }
+}
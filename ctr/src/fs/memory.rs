@@ -0,0 +1,844 @@
+use super::{
+    error as fs_error, ArchiveId, ArchiveOps, DirEntry, DirectoryOps, FileOps, FsPath, OpenFlags,
+};
+use crate::res::{CtrResult, ResultCode};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    rc::Rc,
+    string::String,
+    vec,
+    vec::Vec,
+};
+use core::{cell::RefCell, cmp, convert::TryInto};
+
+/// A fault to make the in-memory filesystem misbehave like a bad sd card.
+/// Faults stay active until they're cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryFsFault {
+    /// Creating files and directories or writing to files fails with `not_enough_space`.
+    DiskFull,
+    /// Opening files fails with `file_not_found`.
+    FileNotFound,
+    /// Opening missing files fails with `save_data_not_found` instead of `file_not_found`, like SDMC does.
+    SdmcNotFound,
+    /// Using archives, files, and directories fails with `archive_not_mounted`.
+    ArchiveNotMounted,
+    /// Reads return at most the given number of bytes.
+    PartialRead(usize),
+    /// Writes only write the given number of bytes, then fail with the given result.
+    PartialWrite(usize, ResultCode),
+    /// Getting the size of files fails with the given result.
+    FailedSize(ResultCode),
+}
+
+struct MemoryArchiveData {
+    files: BTreeMap<String, Vec<u8>>,
+    directories: BTreeSet<String>,
+}
+
+impl Default for MemoryArchiveData {
+    fn default() -> Self {
+        let mut directories = BTreeSet::new();
+        directories.insert(String::from("/"));
+
+        Self {
+            files: BTreeMap::new(),
+            directories,
+        }
+    }
+}
+
+struct MountedArchive {
+    id: ArchiveId,
+    path: FsPath,
+    data: Rc<RefCell<MemoryArchiveData>>,
+}
+
+#[derive(Default)]
+struct MemoryFsState {
+    archives: Vec<MountedArchive>,
+    faults: Vec<MemoryFsFault>,
+}
+
+/// An in-memory filesystem that works like the fs service.
+/// Clones share the same archives and faults, so faults can be injected while archives are open.
+#[derive(Clone, Default)]
+pub struct MemoryFs {
+    state: Rc<RefCell<MemoryFsState>>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mounts an empty archive.
+    /// Nothing happens if the archive is already mounted.
+    pub fn mount_archive(&self, id: ArchiveId, path: &FsPath) {
+        let mut state = self.state.borrow_mut();
+
+        if state
+            .archives
+            .iter()
+            .any(|archive| archive.id == id && &archive.path == path)
+        {
+            return;
+        }
+
+        state.archives.push(MountedArchive {
+            id,
+            path: path.clone(),
+            data: Rc::new(RefCell::new(MemoryArchiveData::default())),
+        });
+    }
+
+    /// Unmounts an archive and removes its contents.
+    /// Anything still open from the archive will fail with `archive_not_mounted`.
+    pub fn unmount_archive(&self, id: ArchiveId, path: &FsPath) {
+        self.state
+            .borrow_mut()
+            .archives
+            .retain(|archive| archive.id != id || &archive.path != path);
+    }
+
+    pub fn inject_fault(&self, fault: MemoryFsFault) {
+        self.state.borrow_mut().faults.push(fault);
+    }
+
+    pub fn clear_faults(&self) {
+        self.state.borrow_mut().faults.clear();
+    }
+
+    pub fn open_archive(&self, id: ArchiveId, path: &FsPath) -> CtrResult<MemoryArchive> {
+        let data = self
+            .state
+            .borrow()
+            .archives
+            .iter()
+            .find(|archive| archive.id == id && &archive.path == path)
+            .map(|archive| archive.data.clone())
+            .ok_or_else(fs_error::archive_not_mounted)?;
+
+        let archive = ArchiveRef {
+            fs: self.clone(),
+            data,
+        };
+        archive.check_mounted()?;
+
+        Ok(MemoryArchive { archive })
+    }
+
+    fn has_fault(&self, fault: MemoryFsFault) -> bool {
+        self.state.borrow().faults.contains(&fault)
+    }
+
+    fn get_partial_read_size(&self) -> Option<usize> {
+        self.state
+            .borrow()
+            .faults
+            .iter()
+            .find_map(|fault| match fault {
+                MemoryFsFault::PartialRead(size) => Some(*size),
+                _ => None,
+            })
+    }
+
+    fn get_partial_write(&self) -> Option<(usize, ResultCode)> {
+        self.state
+            .borrow()
+            .faults
+            .iter()
+            .find_map(|fault| match fault {
+                MemoryFsFault::PartialWrite(size, result) => Some((*size, *result)),
+                _ => None,
+            })
+    }
+
+    fn check_file_size(&self) -> CtrResult {
+        let failed_size = self
+            .state
+            .borrow()
+            .faults
+            .iter()
+            .find_map(|fault| match fault {
+                MemoryFsFault::FailedSize(result) => Some(*result),
+                _ => None,
+            });
+
+        match failed_size {
+            Some(result) => Err(result),
+            None => Ok(()),
+        }
+    }
+
+    fn check_disk_space(&self) -> CtrResult {
+        if self.has_fault(MemoryFsFault::DiskFull) {
+            return Err(fs_error::not_enough_space());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct ArchiveRef {
+    fs: MemoryFs,
+    data: Rc<RefCell<MemoryArchiveData>>,
+}
+
+impl ArchiveRef {
+    fn check_mounted(&self) -> CtrResult {
+        let is_mounted = self
+            .fs
+            .state
+            .borrow()
+            .archives
+            .iter()
+            .any(|archive| Rc::ptr_eq(&archive.data, &self.data));
+
+        if !is_mounted || self.fs.has_fault(MemoryFsFault::ArchiveNotMounted) {
+            return Err(fs_error::archive_not_mounted());
+        }
+
+        Ok(())
+    }
+}
+
+fn get_parent(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(0) | None => "/",
+        Some(index) => &path[..index],
+    }
+}
+
+fn get_child_name<'a>(directory: &str, path: &'a str) -> Option<&'a str> {
+    if path != "/" && get_parent(path) == directory {
+        path.rsplit('/').next()
+    } else {
+        None
+    }
+}
+
+fn is_inside_directory(directory: &str, path: &str) -> bool {
+    path == directory || (path.starts_with(directory) && path[directory.len()..].starts_with('/'))
+}
+
+/// An archive from a [MemoryFs].
+pub struct MemoryArchive {
+    archive: ArchiveRef,
+}
+
+impl ArchiveOps for MemoryArchive {
+    type File = MemoryFile;
+    type Directory = MemoryDirectory;
+
+    fn open_file(&self, path: &FsPath, flags: OpenFlags) -> CtrResult<MemoryFile> {
+        self.archive.check_mounted()?;
+
+        if self.archive.fs.has_fault(MemoryFsFault::FileNotFound) {
+            return Err(fs_error::file_not_found());
+        }
+
+        let path = path.to_path_string()?;
+        let mut data = self.archive.data.borrow_mut();
+
+        if !data.files.contains_key(&path) {
+            if u32::from(flags) & u32::from(OpenFlags::Create) == 0
+                || data.directories.contains(&path)
+            {
+                if self.archive.fs.has_fault(MemoryFsFault::SdmcNotFound) {
+                    return Err(fs_error::save_data_not_found());
+                }
+
+                return Err(fs_error::file_not_found());
+            }
+
+            if !data.directories.contains(get_parent(&path)) {
+                return Err(fs_error::path_not_found());
+            }
+
+            self.archive.fs.check_disk_space()?;
+            data.files.insert(path.clone(), vec![]);
+        }
+
+        Ok(MemoryFile {
+            archive: self.archive.clone(),
+            path,
+        })
+    }
+
+    fn delete_file(&self, path: &FsPath) -> CtrResult {
+        self.archive.check_mounted()?;
+
+        let path = path.to_path_string()?;
+        self.archive
+            .data
+            .borrow_mut()
+            .files
+            .remove(&path)
+            .ok_or_else(fs_error::file_not_found)?;
+
+        Ok(())
+    }
+
+    fn rename_file(&self, src_path: &FsPath, dst_path: &FsPath) -> CtrResult {
+        self.archive.check_mounted()?;
+
+        let src_path = src_path.to_path_string()?;
+        let dst_path = dst_path.to_path_string()?;
+        let mut data = self.archive.data.borrow_mut();
+
+        if !data.files.contains_key(&src_path) {
+            return Err(fs_error::file_not_found());
+        }
+
+        if data.files.contains_key(&dst_path) || data.directories.contains(&dst_path) {
+            return Err(fs_error::file_already_exists());
+        }
+
+        if !data.directories.contains(get_parent(&dst_path)) {
+            return Err(fs_error::path_not_found());
+        }
+
+        if let Some(contents) = data.files.remove(&src_path) {
+            data.files.insert(dst_path, contents);
+        }
+
+        Ok(())
+    }
+
+    fn create_directory(&self, path: &FsPath, _attributes: u32) -> CtrResult {
+        self.archive.check_mounted()?;
+
+        let path = path.to_path_string()?;
+        let mut data = self.archive.data.borrow_mut();
+
+        if data.directories.contains(&path) {
+            return Err(fs_error::directory_already_exists());
+        }
+
+        if data.files.contains_key(&path) {
+            return Err(fs_error::file_already_exists());
+        }
+
+        if !data.directories.contains(get_parent(&path)) {
+            return Err(fs_error::path_not_found());
+        }
+
+        self.archive.fs.check_disk_space()?;
+        data.directories.insert(path);
+
+        Ok(())
+    }
+
+    fn rename_directory(&self, src_path: &FsPath, dst_path: &FsPath) -> CtrResult {
+        self.archive.check_mounted()?;
+
+        let src_path = src_path.to_path_string()?;
+        let dst_path = dst_path.to_path_string()?;
+        let mut data = self.archive.data.borrow_mut();
+
+        if src_path == "/" || !data.directories.contains(&src_path) {
+            return Err(fs_error::path_not_found());
+        }
+
+        if data.directories.contains(&dst_path) || data.files.contains_key(&dst_path) {
+            return Err(fs_error::directory_already_exists());
+        }
+
+        if !data.directories.contains(get_parent(&dst_path))
+            || is_inside_directory(&src_path, &dst_path)
+        {
+            return Err(fs_error::path_not_found());
+        }
+
+        let rename = |path: &str| format!("{}{}", dst_path, &path[src_path.len()..]);
+
+        let directories = data
+            .directories
+            .iter()
+            .filter(|path| is_inside_directory(&src_path, path))
+            .cloned()
+            .collect::<Vec<String>>();
+
+        for directory in directories {
+            data.directories.remove(&directory);
+            data.directories.insert(rename(&directory));
+        }
+
+        let files = data
+            .files
+            .keys()
+            .filter(|path| is_inside_directory(&src_path, path))
+            .cloned()
+            .collect::<Vec<String>>();
+
+        for file in files {
+            if let Some(contents) = data.files.remove(&file) {
+                data.files.insert(rename(&file), contents);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn open_directory(&self, path: &FsPath) -> CtrResult<MemoryDirectory> {
+        self.archive.check_mounted()?;
+
+        let path = path.to_path_string()?;
+        let data = self.archive.data.borrow();
+
+        if !data.directories.contains(&path) {
+            return Err(fs_error::path_not_found());
+        }
+
+        let directories = data.directories.iter().filter_map(|directory| {
+            get_child_name(&path, directory).map(|name| DirEntry::new(String::from(name), 0))
+        });
+        let files = data.files.iter().filter_map(|(file, contents)| {
+            get_child_name(&path, file)
+                .map(|name| DirEntry::new(String::from(name), contents.len() as u64))
+        });
+        let entries = directories.chain(files).collect::<Vec<DirEntry>>();
+
+        Ok(MemoryDirectory {
+            archive: self.archive.clone(),
+            entries: RefCell::new(entries.into_iter()),
+        })
+    }
+
+    fn commit(&self) -> CtrResult {
+        self.archive.check_mounted()
+    }
+}
+
+/// A file from a [MemoryArchive].
+pub struct MemoryFile {
+    archive: ArchiveRef,
+    path: String,
+}
+
+impl FileOps for MemoryFile {
+    fn write(&mut self, data: &[u8]) -> CtrResult {
+        self.archive.check_mounted()?;
+        self.archive.fs.check_disk_space()?;
+
+        let partial_write = self.archive.fs.get_partial_write();
+        let mut archive_data = self.archive.data.borrow_mut();
+        let contents = archive_data
+            .files
+            .get_mut(&self.path)
+            .ok_or_else(fs_error::file_not_found)?;
+
+        match partial_write {
+            Some((size, result)) => {
+                contents.extend_from_slice(&data[..cmp::min(size, data.len())]);
+                Err(result)
+            }
+            None => {
+                contents.extend_from_slice(data);
+                Ok(())
+            }
+        }
+    }
+
+    fn read(&self, offset: u64, max_size: usize) -> CtrResult<Vec<u8>> {
+        self.archive.check_mounted()?;
+
+        let max_size = match self.archive.fs.get_partial_read_size() {
+            Some(size) => cmp::min(size, max_size),
+            None => max_size,
+        };
+
+        let archive_data = self.archive.data.borrow();
+        let contents = archive_data
+            .files
+            .get(&self.path)
+            .ok_or_else(fs_error::file_not_found)?;
+
+        let offset: usize = offset.try_into()?;
+        let start = cmp::min(offset, contents.len());
+        let end = cmp::min(start.saturating_add(max_size), contents.len());
+
+        Ok(contents[start..end].to_vec())
+    }
+
+    fn size(&self) -> CtrResult<usize> {
+        self.archive.check_mounted()?;
+        self.archive.fs.check_file_size()?;
+
+        let archive_data = self.archive.data.borrow();
+        let contents = archive_data
+            .files
+            .get(&self.path)
+            .ok_or_else(fs_error::file_not_found)?;
+
+        Ok(contents.len())
+    }
+}
+
+/// A directory from a [MemoryArchive].
+/// The entries are read when the directory is opened.
+pub struct MemoryDirectory {
+    archive: ArchiveRef,
+    entries: RefCell<vec::IntoIter<DirEntry>>,
+}
+
+impl DirectoryOps for MemoryDirectory {
+    fn read_next(&self) -> CtrResult<Option<DirEntry>> {
+        self.archive.check_mounted()?;
+        Ok(self.entries.borrow_mut().next())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::res::error;
+    use core::convert::TryFrom;
+
+    fn path(path: &str) -> FsPath {
+        FsPath::try_from(path).unwrap()
+    }
+
+    fn open_sd_archive() -> (MemoryFs, MemoryArchive) {
+        let fs = MemoryFs::new();
+        fs.mount_archive(ArchiveId::Sdmc, &FsPath::new_empty_path());
+        let archive = fs
+            .open_archive(ArchiveId::Sdmc, &FsPath::new_empty_path())
+            .unwrap();
+        (fs, archive)
+    }
+
+    fn read_names(directory: &MemoryDirectory) -> Vec<(String, u64)> {
+        let mut names = vec![];
+
+        while let Some(entry) = directory.read_next().unwrap() {
+            names.push((String::from(entry.name()), entry.size()));
+        }
+
+        names
+    }
+
+    // Code written against the fs traits should work with any backend
+    fn save_data(archive: &impl ArchiveOps, data: &[u8]) -> CtrResult {
+        let mut file = archive.open_file(&path("/save.bin"), OpenFlags::ReadWriteCreate)?;
+        file.write(data)
+    }
+
+    mod open_archive {
+        use super::*;
+
+        #[test]
+        fn should_error_if_the_archive_is_not_mounted() {
+            let fs = MemoryFs::new();
+            let result = fs
+                .open_archive(ArchiveId::Sdmc, &FsPath::new_empty_path())
+                .err()
+                .unwrap();
+            assert_eq!(result, fs_error::archive_not_mounted());
+        }
+
+        #[test]
+        fn should_keep_archives_separate() {
+            let fs = MemoryFs::new();
+            let save_path = FsPath::new_binary([0, 0x10032]);
+            fs.mount_archive(ArchiveId::Sdmc, &FsPath::new_empty_path());
+            fs.mount_archive(ArchiveId::SystemSaveData, &save_path);
+
+            let sd_archive = fs
+                .open_archive(ArchiveId::Sdmc, &FsPath::new_empty_path())
+                .unwrap();
+            let save_archive = fs
+                .open_archive(ArchiveId::SystemSaveData, &save_path)
+                .unwrap();
+            save_data(&save_archive, &[1, 2, 3]).unwrap();
+
+            let result = sd_archive
+                .open_file(&path("/save.bin"), OpenFlags::Read)
+                .err()
+                .unwrap();
+            assert_eq!(result, fs_error::file_not_found());
+        }
+
+        #[test]
+        fn should_error_after_the_archive_is_unmounted() {
+            let (fs, archive) = open_sd_archive();
+            let file = archive
+                .open_file(&path("/test.txt"), OpenFlags::ReadWriteCreate)
+                .unwrap();
+
+            fs.unmount_archive(ArchiveId::Sdmc, &FsPath::new_empty_path());
+
+            assert_eq!(file.size().unwrap_err(), fs_error::archive_not_mounted());
+            assert_eq!(
+                archive.create_directory(&path("/3ds"), 0).unwrap_err(),
+                fs_error::archive_not_mounted()
+            );
+        }
+    }
+
+    mod open_file {
+        use super::*;
+
+        #[test]
+        fn should_write_and_read_files() {
+            let (_fs, archive) = open_sd_archive();
+            save_data(&archive, b"save ").unwrap();
+            save_data(&archive, b"data").unwrap();
+
+            let file = archive
+                .open_file(&path("/save.bin"), OpenFlags::Read)
+                .unwrap();
+
+            assert_eq!(file.size().unwrap(), 9);
+            assert_eq!(file.read(0, 100).unwrap(), b"save data");
+            assert_eq!(file.read(5, 2).unwrap(), b"da");
+            assert_eq!(file.read(100, 2).unwrap(), b"");
+        }
+
+        #[test]
+        fn should_error_if_the_file_does_not_exist() {
+            let (_fs, archive) = open_sd_archive();
+            let result = archive
+                .open_file(&path("/test.txt"), OpenFlags::Read)
+                .err()
+                .unwrap();
+            assert_eq!(result, fs_error::file_not_found());
+        }
+
+        #[test]
+        fn should_error_if_the_parent_directory_does_not_exist() {
+            let (_fs, archive) = open_sd_archive();
+            let result = archive
+                .open_file(&path("/3ds/test.txt"), OpenFlags::ReadWriteCreate)
+                .err()
+                .unwrap();
+            assert_eq!(result, fs_error::path_not_found());
+        }
+    }
+
+    mod files {
+        use super::*;
+
+        #[test]
+        fn should_rename_files() {
+            let (_fs, archive) = open_sd_archive();
+            save_data(&archive, &[1, 2]).unwrap();
+
+            archive
+                .rename_file(&path("/save.bin"), &path("/renamed.bin"))
+                .unwrap();
+
+            let file = archive
+                .open_file(&path("/renamed.bin"), OpenFlags::Read)
+                .unwrap();
+            assert_eq!(file.read(0, 2).unwrap(), vec![1, 2]);
+
+            let result = archive
+                .open_file(&path("/save.bin"), OpenFlags::Read)
+                .err()
+                .unwrap();
+            assert_eq!(result, fs_error::file_not_found());
+        }
+
+        #[test]
+        fn should_not_rename_over_existing_files() {
+            let (_fs, archive) = open_sd_archive();
+            save_data(&archive, &[1]).unwrap();
+            archive
+                .open_file(&path("/other.bin"), OpenFlags::ReadWriteCreate)
+                .unwrap();
+
+            let result = archive
+                .rename_file(&path("/other.bin"), &path("/save.bin"))
+                .unwrap_err();
+            assert_eq!(result, fs_error::file_already_exists());
+        }
+
+        #[test]
+        fn should_delete_files() {
+            let (_fs, archive) = open_sd_archive();
+            save_data(&archive, &[1]).unwrap();
+
+            archive.delete_file(&path("/save.bin")).unwrap();
+
+            let result = archive.delete_file(&path("/save.bin")).unwrap_err();
+            assert_eq!(result, fs_error::file_not_found());
+        }
+    }
+
+    mod directories {
+        use super::*;
+
+        #[test]
+        fn should_create_and_read_directories() {
+            let (_fs, archive) = open_sd_archive();
+            archive.create_directory(&path("/3ds"), 0).unwrap();
+            archive.create_directory(&path("/3ds/写真"), 0).unwrap();
+            save_data(&archive, &[1, 2, 3]).unwrap();
+            archive
+                .open_file(&path("/3ds/test.txt"), OpenFlags::ReadWriteCreate)
+                .unwrap();
+
+            let root = archive.open_directory(&path("/")).unwrap();
+            let sub_directory = archive.open_directory(&path("/3ds")).unwrap();
+
+            assert_eq!(
+                read_names(&root),
+                vec![(String::from("3ds"), 0), (String::from("save.bin"), 3)]
+            );
+            assert_eq!(
+                read_names(&sub_directory),
+                vec![(String::from("写真"), 0), (String::from("test.txt"), 0)]
+            );
+        }
+
+        #[test]
+        fn should_error_if_a_directory_already_exists() {
+            let (_fs, archive) = open_sd_archive();
+            archive.create_directory(&path("/3ds"), 0).unwrap();
+
+            let result = archive.create_directory(&path("/3ds"), 0).unwrap_err();
+            assert_eq!(result, fs_error::directory_already_exists());
+        }
+
+        #[test]
+        fn should_rename_directories_and_their_contents() {
+            let (_fs, archive) = open_sd_archive();
+            archive.create_directory(&path("/tmp"), 0).unwrap();
+            archive.create_directory(&path("/tmp/sub"), 0).unwrap();
+            archive
+                .open_file(&path("/tmp/sub/test.txt"), OpenFlags::ReadWriteCreate)
+                .unwrap()
+                .write(b"test")
+                .unwrap();
+
+            archive
+                .rename_directory(&path("/tmp"), &path("/saves"))
+                .unwrap();
+
+            let file = archive
+                .open_file(&path("/saves/sub/test.txt"), OpenFlags::Read)
+                .unwrap();
+            assert_eq!(file.read(0, 4).unwrap(), b"test");
+
+            let result = archive.open_directory(&path("/tmp")).err().unwrap();
+            assert_eq!(result, fs_error::path_not_found());
+        }
+
+        #[test]
+        fn should_not_rename_a_directory_into_itself() {
+            let (_fs, archive) = open_sd_archive();
+            archive.create_directory(&path("/tmp"), 0).unwrap();
+
+            let result = archive
+                .rename_directory(&path("/tmp"), &path("/tmp/sub"))
+                .unwrap_err();
+            assert_eq!(result, fs_error::path_not_found());
+        }
+    }
+
+    mod faults {
+        use super::*;
+
+        #[test]
+        fn should_fail_writes_if_the_disk_is_full() {
+            let (fs, archive) = open_sd_archive();
+            save_data(&archive, &[1]).unwrap();
+            fs.inject_fault(MemoryFsFault::DiskFull);
+
+            assert_eq!(
+                save_data(&archive, &[2]).unwrap_err(),
+                fs_error::not_enough_space()
+            );
+            assert_eq!(
+                archive.create_directory(&path("/3ds"), 0).unwrap_err(),
+                fs_error::not_enough_space()
+            );
+
+            fs.clear_faults();
+            save_data(&archive, &[3]).unwrap();
+
+            let file = archive
+                .open_file(&path("/save.bin"), OpenFlags::Read)
+                .unwrap();
+            assert_eq!(file.read(0, 10).unwrap(), vec![1, 3]);
+        }
+
+        #[test]
+        fn should_fail_to_open_files() {
+            let (fs, archive) = open_sd_archive();
+            save_data(&archive, &[1]).unwrap();
+            fs.inject_fault(MemoryFsFault::FileNotFound);
+
+            let result = archive
+                .open_file(&path("/save.bin"), OpenFlags::Read)
+                .err()
+                .unwrap();
+            assert_eq!(result, fs_error::file_not_found());
+        }
+
+        #[test]
+        fn should_fail_if_the_archive_is_not_mounted() {
+            let (fs, archive) = open_sd_archive();
+            let directory = archive.open_directory(&path("/")).unwrap();
+            fs.inject_fault(MemoryFsFault::ArchiveNotMounted);
+
+            assert_eq!(
+                directory.read_next().unwrap_err(),
+                fs_error::archive_not_mounted()
+            );
+            assert_eq!(
+                fs.open_archive(ArchiveId::Sdmc, &FsPath::new_empty_path())
+                    .err()
+                    .unwrap(),
+                fs_error::archive_not_mounted()
+            );
+        }
+
+        #[test]
+        fn should_partially_read_files() {
+            let (fs, archive) = open_sd_archive();
+            save_data(&archive, &[1, 2, 3, 4]).unwrap();
+            fs.inject_fault(MemoryFsFault::PartialRead(3));
+
+            let file = archive
+                .open_file(&path("/save.bin"), OpenFlags::Read)
+                .unwrap();
+            assert_eq!(file.read(0, 4).unwrap(), vec![1, 2, 3]);
+            assert_eq!(file.read(2, 4).unwrap(), vec![3, 4]);
+        }
+
+        #[test]
+        fn should_partially_write_files() {
+            let (fs, archive) = open_sd_archive();
+            fs.inject_fault(MemoryFsFault::PartialWrite(2, error::busy()));
+
+            let result = save_data(&archive, &[1, 2, 3, 4]).unwrap_err();
+            assert_eq!(result, error::busy());
+
+            fs.clear_faults();
+            let file = archive
+                .open_file(&path("/save.bin"), OpenFlags::Read)
+                .unwrap();
+            assert_eq!(file.read(0, 4).unwrap(), vec![1, 2]);
+        }
+
+        #[test]
+        fn should_fail_to_get_file_sizes() {
+            let (fs, archive) = open_sd_archive();
+            save_data(&archive, &[1, 2, 3]).unwrap();
+            let file = archive
+                .open_file(&path("/save.bin"), OpenFlags::Read)
+                .unwrap();
+            fs.inject_fault(MemoryFsFault::FailedSize(error::busy()));
+
+            assert_eq!(file.size().unwrap_err(), error::busy());
+
+            fs.clear_faults();
+            assert_eq!(file.size().unwrap(), 3);
+        }
+    }
+}
@@ -1,8 +1,35 @@
+#[cfg(not(feature = "host_fs"))]
 mod wrapper;
+#[cfg(not(feature = "host_fs"))]
 pub use wrapper::*;
 
+#[cfg(feature = "host_fs")]
+mod host;
+#[cfg(feature = "host_fs")]
+pub use host::*;
+
+mod ops;
+pub use ops::*;
+
+mod memory;
+pub use memory::*;
+
+mod buffered;
+pub use buffered::*;
+
+mod save_data;
+pub use save_data::*;
+
+mod dir_entry;
+pub use dir_entry::*;
+
+pub mod error;
+
 mod ipc;
 pub use ipc::*;
 
+mod fs_path;
+pub use fs_path::*;
+
 mod helpers;
 pub use helpers::*;
@@ -0,0 +1,110 @@
+use super::{DirEntry, File, FsArchive, FsDirectory, FsPath, OpenFlags};
+use crate::res::CtrResult;
+use alloc::vec::Vec;
+
+/// Operations supported by an open file.
+pub trait FileOps {
+    /// Writes data to the end of the file.
+    fn write(&mut self, data: &[u8]) -> CtrResult;
+
+    fn read(&self, offset: u64, max_size: usize) -> CtrResult<Vec<u8>>;
+
+    fn size(&self) -> CtrResult<usize>;
+
+    /// Reads into a buffer and returns the number of bytes read.
+    /// Reading 0 bytes means the end of the file has been reached.
+    fn read_into(&self, offset: u64, buffer: &mut [u8]) -> CtrResult<usize> {
+        let data = self.read(offset, buffer.len())?;
+        buffer[..data.len()].copy_from_slice(&data);
+        Ok(data.len())
+    }
+
+    fn write_str(&mut self, text: &str) -> CtrResult {
+        self.write(text.as_bytes())
+    }
+}
+
+/// Operations supported by an open directory.
+pub trait DirectoryOps {
+    /// Reads the next directory entry.
+    fn read_next(&self) -> CtrResult<Option<DirEntry>>;
+}
+
+/// Operations supported by an open archive.
+pub trait ArchiveOps {
+    type File: FileOps;
+    type Directory: DirectoryOps;
+
+    fn open_file(&self, path: &FsPath, flags: OpenFlags) -> CtrResult<Self::File>;
+
+    fn delete_file(&self, path: &FsPath) -> CtrResult;
+
+    /// Renames a file.  Fails if the destination already exists.
+    fn rename_file(&self, src_path: &FsPath, dst_path: &FsPath) -> CtrResult;
+
+    fn create_directory(&self, path: &FsPath, attributes: u32) -> CtrResult;
+
+    fn rename_directory(&self, src_path: &FsPath, dst_path: &FsPath) -> CtrResult;
+
+    fn open_directory(&self, path: &FsPath) -> CtrResult<Self::Directory>;
+
+    /// Commits changes to save data archives.
+    fn commit(&self) -> CtrResult;
+}
+
+impl FileOps for File {
+    fn write(&mut self, data: &[u8]) -> CtrResult {
+        File::write(self, data)
+    }
+
+    fn read(&self, offset: u64, max_size: usize) -> CtrResult<Vec<u8>> {
+        File::read(self, offset, max_size)
+    }
+
+    fn size(&self) -> CtrResult<usize> {
+        File::size(self)
+    }
+
+    fn read_into(&self, offset: u64, buffer: &mut [u8]) -> CtrResult<usize> {
+        File::read_into(self, offset, buffer)
+    }
+}
+
+impl DirectoryOps for FsDirectory {
+    fn read_next(&self) -> CtrResult<Option<DirEntry>> {
+        FsDirectory::read_next(self)
+    }
+}
+
+impl ArchiveOps for FsArchive {
+    type File = File;
+    type Directory = FsDirectory;
+
+    fn open_file(&self, path: &FsPath, flags: OpenFlags) -> CtrResult<File> {
+        FsArchive::open_file(self, path, flags)
+    }
+
+    fn delete_file(&self, path: &FsPath) -> CtrResult {
+        FsArchive::delete_file(self, path)
+    }
+
+    fn rename_file(&self, src_path: &FsPath, dst_path: &FsPath) -> CtrResult {
+        FsArchive::rename_file(self, src_path, dst_path)
+    }
+
+    fn create_directory(&self, path: &FsPath, attributes: u32) -> CtrResult {
+        FsArchive::create_directory(self, path, attributes)
+    }
+
+    fn rename_directory(&self, src_path: &FsPath, dst_path: &FsPath) -> CtrResult {
+        FsArchive::rename_directory(self, src_path, dst_path)
+    }
+
+    fn open_directory(&self, path: &FsPath) -> CtrResult<FsDirectory> {
+        FsArchive::open_directory(self, path)
+    }
+
+    fn commit(&self) -> CtrResult {
+        FsArchive::commit(self)
+    }
+}
@@ -0,0 +1,155 @@
+#[cfg(feature = "host_fs")]
+use super::host as backend;
+#[cfg(not(feature = "host_fs"))]
+use super::wrapper as backend;
+use super::{error as fs_error, ArchiveId, FsArchive, FsPath, MediaType, SystemSaveDataInfo};
+use crate::res::{error, CtrResult};
+
+/// Parameters used to create and format save data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SaveDataConfig {
+    /// The size of the save data in bytes.  This is only used when creating save data.
+    pub total_size: u32,
+    pub block_size: u32,
+    pub max_directories: u32,
+    pub max_files: u32,
+    pub directory_buckets: u32,
+    pub file_buckets: u32,
+    /// Keeps a second copy of the data so an interrupted write can't corrupt the save.
+    pub duplicate_data: bool,
+}
+
+impl SaveDataConfig {
+    /// Creates a config with the recommended block size and bucket counts for the given quotas.
+    ///
+    /// Errors if a quota is too large to have a bucket count.
+    pub fn new(total_size: u32, max_directories: u32, max_files: u32) -> CtrResult<Self> {
+        let directory_buckets = max_directories
+            .checked_add(1)
+            .ok_or_else(error::out_of_range)?;
+        let file_buckets = max_files.checked_add(1).ok_or_else(error::out_of_range)?;
+
+        Ok(Self {
+            total_size,
+            block_size: 0x1000,
+            max_directories,
+            max_files,
+            directory_buckets,
+            file_buckets,
+            duplicate_data: true,
+        })
+    }
+}
+
+impl Default for SaveDataConfig {
+    fn default() -> Self {
+        Self::new(0x40000, 10, 10).unwrap()
+    }
+}
+
+/// System save data, such as the save data used by a sysmodule.
+///
+/// The fs service needs to be initialized before using this.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SaveData {
+    info: SystemSaveDataInfo,
+}
+
+impl SaveData {
+    pub fn new_system(media_type: MediaType, save_id: u32) -> Self {
+        Self {
+            info: SystemSaveDataInfo::new(media_type, save_id),
+        }
+    }
+
+    pub fn info(&self) -> SystemSaveDataInfo {
+        self.info
+    }
+
+    /// The binary path used to open the save data archive.
+    pub fn archive_path(&self) -> FsPath {
+        FsPath::new_binary([self.info.media_type.into(), self.info.save_id])
+    }
+
+    pub fn create(&self, config: &SaveDataConfig) -> CtrResult {
+        backend::create_system_save_data(self.info, config)
+    }
+
+    pub fn delete(&self) -> CtrResult {
+        backend::delete_system_save_data(self.info)
+    }
+
+    /// Formats the save data, which removes all of its files and directories.
+    pub fn format(&self, config: &SaveDataConfig) -> CtrResult {
+        backend::format_save_data(ArchiveId::SystemSaveData, &self.archive_path(), config)
+    }
+
+    /// Opens the save data archive.
+    /// Changes need to be committed with [FsArchive::commit] to be saved.
+    pub fn open(&self) -> CtrResult<FsArchive> {
+        FsArchive::new(ArchiveId::SystemSaveData, &self.archive_path())
+    }
+
+    /// Opens the save data archive, and creates the save data if it doesn't exist yet.
+    pub fn open_or_create(&self, config: &SaveDataConfig) -> CtrResult<FsArchive> {
+        match self.open() {
+            Err(result) if result == fs_error::save_data_not_found() => {
+                self.create(config)?;
+                self.open()
+            }
+            result => result,
+        }
+    }
+}
+
+/// Returns the secure value in a slot of the current process's save data,
+/// or None if the slot hasn't been set.
+///
+/// Secure values are used to detect save data being rolled back to an older copy.
+pub fn get_this_save_data_secure_value(slot: u32) -> CtrResult<Option<u64>> {
+    backend::get_secure_value(slot)
+}
+
+/// Sets the secure value in a slot of the current process's save data.
+pub fn set_this_save_data_secure_value(slot: u32, value: u64) -> CtrResult {
+    backend::set_secure_value(slot, value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn should_use_the_media_type_and_save_id_as_the_archive_path() {
+        let save_data = SaveData::new_system(MediaType::Nand, 0x10032);
+        assert_eq!(save_data.archive_path(), FsPath::new_binary([0, 0x10032]));
+    }
+
+    #[test]
+    fn should_create_a_config_with_bucket_counts_for_the_quotas() {
+        let result = SaveDataConfig::new(0x20000, 4, 20).unwrap();
+        let expected = SaveDataConfig {
+            total_size: 0x20000,
+            block_size: 0x1000,
+            max_directories: 4,
+            max_files: 20,
+            directory_buckets: 5,
+            file_buckets: 21,
+            duplicate_data: true,
+        };
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn should_error_if_a_quota_has_too_many_entries_for_a_bucket_count() {
+        assert_eq!(
+            SaveDataConfig::new(0x20000, u32::MAX, 20),
+            Err(error::out_of_range())
+        );
+        assert_eq!(
+            SaveDataConfig::new(0x20000, 4, u32::MAX),
+            Err(error::out_of_range())
+        );
+    }
+}
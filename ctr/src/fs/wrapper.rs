@@ -1,6 +1,9 @@
-use super::ipc::{dir, file, user, ArchiveId, FsPath, OpenFlags, WriteFlags};
+use super::{
+    ipc::{dir, file, user, ArchiveAction, ArchiveId, OpenFlags, SystemSaveDataInfo, WriteFlags},
+    DirEntry, FsPath, SaveDataConfig,
+};
 use crate::{res::CtrResult, utils::convert::bytes_to_utf16le_string, Handle};
-use alloc::{string::String, vec::Vec};
+use alloc::vec::Vec;
 use core::{convert::TryInto, ops::Drop};
 
 /// Opens a file.
@@ -56,6 +59,11 @@ impl File {
         Ok(buffer)
     }
 
+    /// Reads into a buffer and returns the number of bytes read.
+    pub fn read_into(&self, offset: u64, buffer: &mut [u8]) -> CtrResult<usize> {
+        file::read_into(&self.handle, offset, buffer)
+    }
+
     pub fn size(&self) -> CtrResult<usize> {
         let size = file::get_size(&self.handle)?.try_into()?;
         Ok(size)
@@ -87,6 +95,14 @@ impl FsArchive {
         File::new_from_archive(self, path, flags)
     }
 
+    pub fn delete_file(&self, path: &FsPath) -> CtrResult {
+        user::delete_file(self.raw_archive_handle, path)
+    }
+
+    pub fn rename_file(&self, src_path: &FsPath, dst_path: &FsPath) -> CtrResult {
+        user::rename_file(self.raw_archive_handle, src_path, dst_path)
+    }
+
     pub fn create_directory(&self, path: &FsPath, attributes: u32) -> CtrResult {
         user::create_directory(self.raw_archive_handle, path, attributes)
     }
@@ -99,6 +115,16 @@ impl FsArchive {
         let handle = user::open_directory(self.raw_archive_handle, path)?;
         Ok(FsDirectory::new_from_handle(handle))
     }
+
+    /// Commits changes to save data archives.
+    pub fn commit(&self) -> CtrResult {
+        user::control_archive(
+            self.raw_archive_handle,
+            ArchiveAction::CommitSaveData,
+            &[],
+            &mut [],
+        )
+    }
 }
 
 impl Drop for FsArchive {
@@ -109,22 +135,6 @@ impl Drop for FsArchive {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct DirEntry {
-    name: String,
-    file_size: u64,
-}
-
-impl DirEntry {
-    pub fn name(&self) -> &str {
-        &self.name
-    }
-
-    pub fn size(&self) -> u64 {
-        self.file_size
-    }
-}
-
 pub struct FsDirectory {
     handle: Handle,
 }
@@ -139,10 +149,10 @@ impl FsDirectory {
         let dir_entry = dir::read_next_entry(&self.handle)?;
 
         if let Some(entry) = dir_entry {
-            return Ok(Some(DirEntry {
-                name: bytes_to_utf16le_string(entry.name())?,
-                file_size: entry.file_size(),
-            }));
+            return Ok(Some(DirEntry::new(
+                bytes_to_utf16le_string(entry.name())?,
+                entry.file_size(),
+            )));
         }
 
         Ok(None)
@@ -156,3 +166,30 @@ impl Drop for FsDirectory {
         dir::close(&self.handle);
     }
 }
+
+pub(super) fn create_system_save_data(
+    info: SystemSaveDataInfo,
+    config: &SaveDataConfig,
+) -> CtrResult {
+    user::create_system_save_data(info, config)
+}
+
+pub(super) fn delete_system_save_data(info: SystemSaveDataInfo) -> CtrResult {
+    user::delete_system_save_data(info)
+}
+
+pub(super) fn format_save_data(
+    archive_id: ArchiveId,
+    path: &FsPath,
+    config: &SaveDataConfig,
+) -> CtrResult {
+    user::format_save_data(archive_id, path, config)
+}
+
+pub(super) fn get_secure_value(slot: u32) -> CtrResult<Option<u64>> {
+    user::get_this_save_data_secure_value(slot)
+}
+
+pub(super) fn set_secure_value(slot: u32, value: u64) -> CtrResult {
+    user::set_this_save_data_secure_value(slot, value)
+}
@@ -72,7 +72,7 @@ pub fn set_static_buffer(static_buffer: &StaticBuffer) {
 }
 
 #[inline(always)]
-pub(super) fn make_header(command_id: u16, normal_params: u16, translate_params: u16) -> u32 {
+pub(crate) fn make_header(command_id: u16, normal_params: u16, translate_params: u16) -> u32 {
     ((command_id as u32) << 16)
         | (((normal_params & 0x3F) << 6) as u32)
         | ((translate_params & 0x3F) as u32)
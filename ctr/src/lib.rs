@@ -3,6 +3,8 @@
 #![cfg_attr(not(target_os = "horizon"), allow(unused))]
 
 extern crate alloc;
+#[cfg(feature = "host_fs")]
+extern crate std;
 
 pub mod allocator;
 pub use allocator::*;
@@ -16,21 +18,28 @@ pub use log::*;
 mod process;
 pub use process::*;
 
+mod title_id;
+pub use title_id::*;
+
 pub mod result;
 pub use result as res;
 pub use result::error;
 
 pub mod ac;
 pub mod cfg;
+pub mod crypto;
+pub mod formats;
 pub mod frd;
 pub mod fs;
 pub mod hid;
 pub mod http;
 pub mod ipc;
 pub mod memory;
+pub mod nasc;
 pub mod ndm;
 pub mod os;
 pub mod pm_dbg;
+pub mod prudp;
 pub mod ps;
 pub mod ptm_sysm;
 pub mod srv;
@@ -8,7 +8,7 @@ use crate::{
 use alloc::format;
 use alloc::string::{String, ToString};
 #[cfg(target_os = "horizon")]
-use core::convert::TryInto;
+use core::convert::TryFrom;
 use core::fmt;
 
 #[derive(Clone, Copy, Debug)]
@@ -32,14 +32,17 @@ fn log(_file_name: &str, _log_type: LogType, _text: &str) {}
 fn log(file_name: &str, log_type: LogType, text: &str) {
     let command_cache: [u8; 0x64] = backup_thread_command_buffer();
 
-    let archive_path: FsPath = "".try_into().unwrap();
-    let file_path: FsPath = file_name.try_into().unwrap();
-    if let Ok(mut file) = File::new(
-        &archive_path,
-        &file_path,
-        ArchiveId::Sdmc,
-        OpenFlags::ReadWriteCreate,
-    ) {
+    let archive_path = FsPath::new_empty_path();
+    let file = FsPath::try_from(file_name).and_then(|file_path| {
+        File::new(
+            &archive_path,
+            &file_path,
+            ArchiveId::Sdmc,
+            OpenFlags::ReadWriteCreate,
+        )
+    });
+
+    if let Ok(mut file) = file {
         let new_line_text = format!("[{}] {}: {}\n", log_type, get_time(), text);
         file.write_str(&new_line_text).unwrap();
     }
@@ -0,0 +1,648 @@
+//! NASC is the server the friends module and games log in to before connecting to a game server.
+//!
+//! Requests and responses are forms where every value is base64 encoded with NASC's alphabet,
+//! which [base64_encode] and [base64_decode] already use.
+
+use crate::{
+    cfg::{self, Region, SystemLanguage},
+    fs::{MediaType, ProductInfo, ProgramInfo},
+    http::HttpContext,
+    res::{error, CtrResult},
+    time::{FormattedTimestamp, SystemTimestamp},
+    utils::{base64_decode, base64_encode},
+    TitleId,
+};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::{
+    convert::{TryFrom, TryInto},
+    fmt, str,
+};
+
+/// The unit code 3DS consoles send.
+pub const CTR_UNIT_CODE: u8 = 2;
+/// The return code NASC sends when a request succeeds.
+pub const NASC_SUCCESS_RETURN_CODE: u16 = 1;
+
+const MAX_RESPONSE_SIZE: usize = 0x1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NascAction {
+    Login,
+    ServiceLocator,
+}
+
+impl NascAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NascAction::Login => "LOGIN",
+            NascAction::ServiceLocator => "SVCLOC",
+        }
+    }
+
+    pub fn parse(action: &str) -> CtrResult<Self> {
+        match action {
+            "LOGIN" => Ok(NascAction::Login),
+            "SVCLOC" => Ok(NascAction::ServiceLocator),
+            _ => Err(error::invalid_enum_value()),
+        }
+    }
+}
+
+/// A timestamp in NASC's format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NascDateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl NascDateTime {
+    /// Parses `YYYYMMDDHHMMSS`, which is what the server sends.
+    pub fn parse(date_time: &str) -> CtrResult<Self> {
+        if date_time.len() != 14 {
+            return Err(error::invalid_size());
+        }
+
+        Self::parse_with_year(date_time[..4].parse()?, &date_time[4..])
+    }
+
+    /// Parses `YYMMDDHHMMSS`, which is what the console sends.
+    pub fn parse_device_time(device_time: &str) -> CtrResult<Self> {
+        if device_time.len() != 12 {
+            return Err(error::invalid_size());
+        }
+
+        let year: u16 = device_time[..2].parse()?;
+        Self::parse_with_year(2000 + year, &device_time[2..])
+    }
+
+    fn parse_with_year(year: u16, rest: &str) -> CtrResult<Self> {
+        if !rest.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(error::invalid_value());
+        }
+
+        let result = Self {
+            year,
+            month: rest[0..2].parse()?,
+            day: rest[2..4].parse()?,
+            hour: rest[4..6].parse()?,
+            minute: rest[6..8].parse()?,
+            second: rest[8..10].parse()?,
+        };
+
+        if !(1..=12).contains(&result.month)
+            || !(1..=31).contains(&result.day)
+            || result.hour > 23
+            || result.minute > 59
+            || result.second > 59
+        {
+            return Err(error::invalid_value());
+        }
+
+        Ok(result)
+    }
+
+    pub fn to_device_time(&self) -> String {
+        format!(
+            "{:02}{:02}{:02}{:02}{:02}{:02}",
+            self.year % 100,
+            self.month,
+            self.day,
+            self.hour,
+            self.minute,
+            self.second
+        )
+    }
+}
+
+impl From<SystemTimestamp> for NascDateTime {
+    fn from(timestamp: SystemTimestamp) -> Self {
+        let timestamp = FormattedTimestamp::from(timestamp);
+
+        Self {
+            year: timestamp.get_year(),
+            month: timestamp.get_month() as u8,
+            day: timestamp.get_date() as u8,
+            hour: timestamp.get_hours() as u8,
+            minute: timestamp.get_minutes() as u8,
+            second: timestamp.get_seconds() as u8,
+        }
+    }
+}
+
+impl fmt::Display for NascDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}{:02}{:02}{:02}{:02}{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// The game server NASC points the client to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NascLocator {
+    pub host: String,
+    pub port: u16,
+}
+
+impl NascLocator {
+    /// Parses `host:port`.
+    pub fn parse(locator: &str) -> CtrResult<Self> {
+        let (host, port) = locator.rsplit_once(':').ok_or_else(error::invalid_value)?;
+
+        if host.is_empty() {
+            return Err(error::invalid_value());
+        }
+
+        Ok(Self {
+            host: String::from(host),
+            port: port.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for NascLocator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.host, self.port)
+    }
+}
+
+/// Decoded form fields, in the order they appeared.
+struct NascForm {
+    fields: Vec<(String, Vec<u8>)>,
+}
+
+impl NascForm {
+    fn parse(body: &str) -> CtrResult<Self> {
+        let fields = body
+            .trim_end_matches(|character| {
+                character == '\0' || character == '\r' || character == '\n'
+            })
+            .split('&')
+            .filter(|field| !field.is_empty())
+            .map(|field| {
+                let (name, value) = field.split_once('=').ok_or_else(error::invalid_value)?;
+                Ok((String::from(name), base64_decode(value)?))
+            })
+            .collect::<CtrResult<Vec<(String, Vec<u8>)>>>()?;
+
+        Ok(Self { fields })
+    }
+
+    fn get_bytes(&self, name: &str) -> Option<&[u8]> {
+        self.fields
+            .iter()
+            .find(|(field_name, _)| field_name == name)
+            .map(|(_, value)| value.as_slice())
+    }
+
+    fn get_str(&self, name: &str) -> CtrResult<Option<&str>> {
+        match self.get_bytes(name) {
+            Some(value) => Ok(Some(str::from_utf8(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn require_bytes(&self, name: &str) -> CtrResult<&[u8]> {
+        self.get_bytes(name).ok_or_else(error::not_found)
+    }
+
+    fn require_str(&self, name: &str) -> CtrResult<&str> {
+        self.get_str(name)?.ok_or_else(error::not_found)
+    }
+
+    fn require_hex<T: TryFrom<u64>>(&self, name: &str) -> CtrResult<T> {
+        let value = u64::from_str_radix(self.require_str(name)?, 16)?;
+        value.try_into().map_err(|_| error::invalid_value())
+    }
+}
+
+fn encode_form(fields: &[(&str, Vec<u8>)]) -> String {
+    fields
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, base64_encode(value)))
+        .collect::<Vec<String>>()
+        .join("&")
+}
+
+fn format_hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn parse_hex_bytes<const N: usize>(hex: &str) -> CtrResult<[u8; N]> {
+    if hex.len() != N * 2 || !hex.is_ascii() {
+        return Err(error::invalid_size());
+    }
+
+    let mut result = [0; N];
+    for (index, byte) in result.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16)?;
+    }
+
+    Ok(result)
+}
+
+fn parse_fixed_bytes<const N: usize>(value: &[u8]) -> CtrResult<[u8; N]> {
+    value.try_into().map_err(|_| error::invalid_size())
+}
+
+/// The form a client sends to log in to NASC.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NascLoginRequest {
+    pub action: NascAction,
+    /// The unique id of the title logging in.
+    pub game_id: u32,
+    pub sdk_version: u32,
+    pub title_id: TitleId,
+    /// The last 4 characters of the product code, e.g. `ABCE` for `CTR-P-ABCE`.
+    pub game_code: [u8; 4],
+    pub game_version: u16,
+    pub media_type: MediaType,
+    pub maker_code: [u8; 2],
+    pub unit_code: u8,
+    pub mac_address: [u8; 6],
+    /// The console's device certificate.
+    pub device_cert: Vec<u8>,
+    pub device_time: NascDateTime,
+    pub language: SystemLanguage,
+    pub region: Region,
+    pub serial_number: String,
+    /// The seed bytes of the console's [LocalFriendCodeSeed](crate::formats::local_friend_code_seed::LocalFriendCodeSeed).
+    pub local_friend_code_seed: [u8; 8],
+    pub user_id: Option<u32>,
+}
+
+impl NascLoginRequest {
+    /// Creates a request for a program, with the console fields left blank.
+    pub fn new(program_info: &ProgramInfo, product_info: &ProductInfo) -> Self {
+        let mut game_code = [0; 4];
+        game_code.copy_from_slice(&product_info.product_code[6..10]);
+
+        Self {
+            action: NascAction::Login,
+            game_id: program_info.program_id.unique_id(),
+            sdk_version: 0,
+            title_id: program_info.program_id,
+            game_code,
+            game_version: product_info.remaster_version,
+            media_type: program_info.media_type,
+            maker_code: product_info.company_code,
+            unit_code: CTR_UNIT_CODE,
+            mac_address: [0; 6],
+            device_cert: vec![],
+            device_time: NascDateTime::default(),
+            language: SystemLanguage::English,
+            region: Region::America,
+            serial_number: String::new(),
+            local_friend_code_seed: [0; 8],
+            user_id: None,
+        }
+    }
+
+    /// Creates a request for a program with the console's MAC address, device certificate and time.
+    ///
+    /// None of these are read from the system, so the caller must supply them:
+    /// - `mac_address` is the console's wifi MAC address.
+    /// - `device_cert` is the console's device certificate, which ps and cfg don't provide.
+    /// - `device_time` is the time sent to the server, usually the current time.
+    ///
+    /// The cfg values are left blank.
+    pub fn with_device(
+        program_info: &ProgramInfo,
+        product_info: &ProductInfo,
+        mac_address: [u8; 6],
+        device_cert: Vec<u8>,
+        device_time: SystemTimestamp,
+    ) -> Self {
+        let mut result = Self::new(program_info, product_info);
+        result.mac_address = mac_address;
+        result.device_cert = device_cert;
+        result.device_time = device_time.into();
+        result
+    }
+
+    /// Creates a request like [with_device](Self::with_device),
+    /// and fills in the language, region, serial number and local friend code seed from cfg.
+    ///
+    /// The game fields come from `program_info` and `product_info`.
+    /// The MAC address, device certificate and time are still supplied by the caller,
+    /// since nothing here reads them from the system.
+    pub fn from_system(
+        program_info: &ProgramInfo,
+        product_info: &ProductInfo,
+        mac_address: [u8; 6],
+        device_cert: Vec<u8>,
+        device_time: SystemTimestamp,
+    ) -> CtrResult<Self> {
+        let mut result = Self::with_device(
+            program_info,
+            product_info,
+            mac_address,
+            device_cert,
+            device_time,
+        );
+        result.language = cfg::get_system_language()?;
+        result.region = cfg::get_region()?;
+        result.serial_number = cfg::get_serial_number()?;
+        result.local_friend_code_seed = cfg::get_local_friend_code_seed()?.get_seed_bytes();
+        Ok(result)
+    }
+
+    fn to_fields(&self) -> Vec<(&'static str, Vec<u8>)> {
+        let mut fields = vec![
+            ("action", self.action.as_str().into()),
+            ("gameid", format!("{:08X}", self.game_id).into_bytes()),
+            ("sdkver", format!("{:06}", self.sdk_version).into_bytes()),
+            (
+                "titleid",
+                format!("{:016X}", self.title_id.raw()).into_bytes(),
+            ),
+            ("gamecd", self.game_code.to_vec()),
+            ("gamever", format!("{:04X}", self.game_version).into_bytes()),
+            (
+                "mediatype",
+                format!("{}", u8::from(self.media_type)).into_bytes(),
+            ),
+            ("makercd", self.maker_code.to_vec()),
+            ("unitcd", format!("{}", self.unit_code).into_bytes()),
+            ("macadr", format_hex_bytes(&self.mac_address).into_bytes()),
+            ("fcdcert", self.device_cert.clone()),
+            ("devtime", self.device_time.to_device_time().into_bytes()),
+            (
+                "lang",
+                format!("{:02X}", u8::from(self.language)).into_bytes(),
+            ),
+            (
+                "region",
+                format!("{:02X}", u8::from(self.region)).into_bytes(),
+            ),
+            ("csnum", self.serial_number.clone().into_bytes()),
+            ("lfcs", self.local_friend_code_seed.to_vec()),
+        ];
+
+        if let Some(user_id) = self.user_id {
+            fields.push(("userid", format!("{}", user_id).into_bytes()));
+        }
+
+        fields
+    }
+
+    /// Encodes the request as a form body.
+    pub fn to_form_body(&self) -> String {
+        encode_form(&self.to_fields())
+    }
+
+    /// Parses a form body, which is what a server receives.
+    pub fn parse_form_body(body: &str) -> CtrResult<Self> {
+        let form = NascForm::parse(body)?;
+
+        Ok(Self {
+            action: NascAction::parse(form.require_str("action")?)?,
+            game_id: form.require_hex("gameid")?,
+            sdk_version: form.require_str("sdkver")?.parse()?,
+            title_id: TitleId::new(form.require_hex("titleid")?),
+            game_code: parse_fixed_bytes(form.require_bytes("gamecd")?)?,
+            game_version: form.require_hex("gamever")?,
+            media_type: MediaType::try_from_raw(form.require_str("mediatype")?.parse()?)?,
+            maker_code: parse_fixed_bytes(form.require_bytes("makercd")?)?,
+            unit_code: form.require_str("unitcd")?.parse()?,
+            mac_address: parse_hex_bytes(form.require_str("macadr")?)?,
+            device_cert: form.require_bytes("fcdcert")?.to_vec(),
+            device_time: NascDateTime::parse_device_time(form.require_str("devtime")?)?,
+            language: SystemLanguage::try_from(form.require_hex::<u8>("lang")?)
+                .map_err(|_| error::invalid_enum_value())?,
+            region: Region::from_raw(form.require_hex("region")?)?,
+            serial_number: String::from(form.require_str("csnum")?),
+            local_friend_code_seed: parse_fixed_bytes(form.require_bytes("lfcs")?)?,
+            user_id: form
+                .get_str("userid")?
+                .map(|user_id| user_id.parse())
+                .transpose()?,
+        })
+    }
+
+    /// Adds the request's fields to an HTTP context as POST data.
+    pub fn add_to_context(&self, context: &impl NascHttpContext) -> CtrResult {
+        for (name, value) in self.to_fields() {
+            context.add_post_base64_field(name, &value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The form NASC responds with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NascLoginResponse {
+    pub return_code: u16,
+    pub retry: bool,
+    pub locator: Option<NascLocator>,
+    pub token: Option<String>,
+    pub date_time: Option<NascDateTime>,
+}
+
+impl NascLoginResponse {
+    pub fn is_success(&self) -> bool {
+        self.return_code == NASC_SUCCESS_RETURN_CODE
+    }
+
+    pub fn parse(body: &str) -> CtrResult<Self> {
+        let form = NascForm::parse(body)?;
+
+        Ok(Self {
+            return_code: form.require_str("returncd")?.parse()?,
+            retry: form.get_str("retry")?.unwrap_or("0") != "0",
+            locator: form
+                .get_str("locator")?
+                .map(NascLocator::parse)
+                .transpose()?,
+            token: form.get_str("token")?.map(String::from),
+            date_time: form
+                .get_str("datetime")?
+                .map(NascDateTime::parse)
+                .transpose()?,
+        })
+    }
+
+    /// Encodes the response as a form body, which is what a server sends.
+    pub fn to_form_body(&self) -> String {
+        let mut fields = vec![];
+
+        if let Some(locator) = &self.locator {
+            fields.push(("locator", format!("{}", locator).into_bytes()));
+        }
+
+        fields.push(("retry", format!("{}", self.retry as u8).into_bytes()));
+        fields.push(("returncd", format!("{:03}", self.return_code).into_bytes()));
+
+        if let Some(token) = &self.token {
+            fields.push(("token", token.clone().into_bytes()));
+        }
+
+        if let Some(date_time) = &self.date_time {
+            fields.push(("datetime", format!("{}", date_time).into_bytes()));
+        }
+
+        encode_form(&fields)
+    }
+}
+
+/// An HTTP POST request that NASC forms are sent with.
+/// [HttpContext] sends it with the http sysmodule, but anything that can post a form works.
+pub trait NascHttpContext {
+    /// Adds a POST field, encoding the value with NASC's base64 alphabet.
+    fn add_post_base64_field(&self, name: &str, value: &[u8]) -> CtrResult;
+
+    /// Sends the request and reads the response body into the buffer.
+    fn download_data_into_buffer(&self, out_buffer: &mut [u8]) -> CtrResult;
+}
+
+impl NascHttpContext for HttpContext {
+    fn add_post_base64_field(&self, name: &str, value: &[u8]) -> CtrResult {
+        HttpContext::add_post_base64_field(self, name, value)
+    }
+
+    fn download_data_into_buffer(&self, out_buffer: &mut [u8]) -> CtrResult {
+        HttpContext::download_data_into_buffer(self, out_buffer)
+    }
+}
+
+/// Sends a login request to NASC and parses the response.
+/// An [HttpContext] needs to be created with [RequestMethod::Post](crate::http::RequestMethod::Post),
+/// and any certificates the server requires need to be added first.
+pub fn login(
+    context: &impl NascHttpContext,
+    request: &NascLoginRequest,
+) -> CtrResult<NascLoginResponse> {
+    request.add_to_context(context)?;
+
+    let mut response = [0u8; MAX_RESPONSE_SIZE];
+    context.download_data_into_buffer(&mut response)?;
+
+    let end = response
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(response.len());
+    NascLoginResponse::parse(str::from_utf8(&response[..end])?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod nasc_date_time {
+        use super::*;
+
+        #[test]
+        fn should_parse_server_date_times() {
+            let result = NascDateTime::parse("20231224183005").unwrap();
+
+            assert_eq!(
+                result,
+                NascDateTime {
+                    year: 2023,
+                    month: 12,
+                    day: 24,
+                    hour: 18,
+                    minute: 30,
+                    second: 5
+                }
+            );
+            assert_eq!(format!("{}", result), "20231224183005");
+            assert_eq!(result.to_device_time(), "231224183005");
+        }
+
+        #[test]
+        fn should_convert_system_timestamps() {
+            let timestamp = SystemTimestamp::from(FormattedTimestamp::new(2023, 12, 24, 18, 30, 5));
+            let result = NascDateTime::from(timestamp);
+
+            assert_eq!(result, NascDateTime::parse("20231224183005").unwrap());
+        }
+
+        #[test]
+        fn should_parse_device_times() {
+            let result = NascDateTime::parse_device_time("231224183005").unwrap();
+            assert_eq!(result, NascDateTime::parse("20231224183005").unwrap());
+        }
+
+        #[test]
+        fn should_return_an_error_for_invalid_date_times() {
+            assert_eq!(
+                NascDateTime::parse("20231324183005"),
+                Err(error::invalid_value())
+            );
+            assert_eq!(
+                NascDateTime::parse("2023122418300"),
+                Err(error::invalid_size())
+            );
+            assert!(NascDateTime::parse("2023122418300+").is_err());
+        }
+    }
+
+    mod nasc_locator {
+        use super::*;
+
+        #[test]
+        fn should_parse_the_locator() {
+            let result = NascLocator::parse("192.168.1.2:60000").unwrap();
+
+            assert_eq!(result.host, "192.168.1.2");
+            assert_eq!(result.port, 60000);
+            assert_eq!(format!("{}", result), "192.168.1.2:60000");
+        }
+
+        #[test]
+        fn should_return_an_error_without_a_port() {
+            assert_eq!(
+                NascLocator::parse("192.168.1.2"),
+                Err(error::invalid_value())
+            );
+        }
+    }
+
+    mod nasc_login_response {
+        use super::*;
+
+        #[test]
+        fn should_parse_a_response() {
+            let body = "locator=MTI3LjAuMC4xOjYwMDAw&retry=MA**&returncd=MDAx&token=dG9rZW4*&datetime=MjAyMzEyMjQxODMwMDU*\r\n";
+
+            let result = NascLoginResponse::parse(body).unwrap();
+
+            assert!(result.is_success());
+            assert!(!result.retry);
+            assert_eq!(
+                result.locator,
+                Some(NascLocator {
+                    host: String::from("127.0.0.1"),
+                    port: 60000
+                })
+            );
+            assert_eq!(result.token.as_deref(), Some("token"));
+            assert_eq!(result.date_time.unwrap().year, 2023);
+            assert_eq!(result.to_form_body(), body.trim_end());
+        }
+
+        #[test]
+        fn should_parse_a_failed_response() {
+            let result = NascLoginResponse::parse("retry=MA**&returncd=MTA5").unwrap();
+
+            assert!(!result.is_success());
+            assert_eq!(result.return_code, 109);
+            assert_eq!(result.locator, None);
+            assert_eq!(result.token, None);
+        }
+
+        #[test]
+        fn should_return_an_error_without_a_return_code() {
+            let result = NascLoginResponse::parse("retry=MA**");
+            assert_eq!(result, Err(error::not_found()));
+        }
+    }
+}
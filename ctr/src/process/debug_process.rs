@@ -1,7 +1,7 @@
 use super::Process;
 use crate::{
     res::{CtrResult, ResultCode},
-    svc, Handle,
+    svc, Handle, TitleId,
 };
 use alloc::vec::Vec;
 use core::{convert::TryFrom, mem};
@@ -13,7 +13,7 @@ pub struct DebugProcess {
 }
 
 impl DebugProcess {
-    pub fn new(title_id: u64) -> CtrResult<Self> {
+    pub fn new(title_id: impl Into<TitleId>) -> CtrResult<Self> {
         let process = Process::new_from_title_id(title_id)?;
         Self::new_from_process(process)
     }
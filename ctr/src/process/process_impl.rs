@@ -1,7 +1,7 @@
 use crate::{
     res::{error, CtrResult},
     svc::{self, MemQueryResponse},
-    Handle,
+    Handle, TitleId,
 };
 
 pub struct Process {
@@ -13,13 +13,14 @@ impl Process {
         &self.handle
     }
 
-    pub fn get_process_title_id(process: &Handle) -> CtrResult<u64> {
+    pub fn get_process_title_id(process: &Handle) -> CtrResult<TitleId> {
         let title_id = svc::get_process_info(process, svc::ProcessInfoType::TitleId)?;
         let title_id_bytes = title_id.to_ne_bytes();
-        Ok(u64::from_ne_bytes(title_id_bytes))
+        Ok(u64::from_ne_bytes(title_id_bytes).into())
     }
 
-    pub fn get_process_id_from_title_id(title_id: u64) -> Option<u32> {
+    pub fn get_process_id_from_title_id(title_id: impl Into<TitleId>) -> Option<u32> {
+        let title_id = title_id.into();
         let process_list = svc::get_process_list().unwrap();
 
         process_list.iter().find_map(|process_id| {
@@ -45,14 +46,14 @@ impl Process {
         Ok(Self { handle })
     }
 
-    pub fn new_from_title_id(title_id: u64) -> CtrResult<Self> {
+    pub fn new_from_title_id(title_id: impl Into<TitleId>) -> CtrResult<Self> {
         match Self::get_process_id_from_title_id(title_id) {
             Some(process_id) => Self::new_from_process_id(process_id),
             None => Err(error::invalid_value()),
         }
     }
 
-    pub fn get_title_id(&self) -> CtrResult<u64> {
+    pub fn get_title_id(&self) -> CtrResult<TitleId> {
         Self::get_process_title_id(&self.handle)
     }
 
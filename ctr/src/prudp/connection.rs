@@ -0,0 +1,752 @@
+use super::{
+    calculate_data_signature, PacketFlag, PacketType, PrudpPacket, VirtualPort, DEFAULT_PAYLOAD_KEY,
+};
+use crate::{
+    crypto::Rc4,
+    res::{error, CtrResult},
+};
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use core::mem;
+
+/// The largest payload a data packet carries before a message is split into fragments.
+pub const DEFAULT_FRAGMENT_SIZE: usize = 1300;
+
+/// Fragment ids are a u8, and the last fragment always has an id of 0.
+const MAX_FRAGMENT_COUNT: usize = 256;
+/// Reliable packets are only buffered this far ahead of the next expected packet, which is enough for a full message.
+const MAX_OUT_OF_ORDER_DISTANCE: u16 = MAX_FRAGMENT_COUNT as u16;
+/// The server numbers its reliable packets from 1, since its syn ack uses 0.
+const FIRST_REMOTE_SEQUENCE_ID: u16 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrudpSettings {
+    pub access_key: Vec<u8>,
+    /// The RC4 key data payloads are encrypted with.
+    /// Each direction has its own keystream that continues across packets.
+    pub payload_key: Vec<u8>,
+    pub fragment_size: usize,
+}
+
+impl PrudpSettings {
+    pub fn new(access_key: &[u8]) -> Self {
+        Self {
+            access_key: access_key.to_vec(),
+            payload_key: DEFAULT_PAYLOAD_KEY.to_vec(),
+            fragment_size: DEFAULT_FRAGMENT_SIZE,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    Idle,
+    /// Waiting for the server to acknowledge the syn packet.
+    SynSent,
+    /// Waiting for the server to acknowledge the connect packet.
+    Connecting,
+    Connected,
+    /// Waiting for the server to acknowledge the disconnect packet.
+    Disconnecting,
+    Disconnected,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PendingPacket {
+    packet_type: PacketType,
+    sequence_id: u16,
+    datagram: Vec<u8>,
+}
+
+/// The client side of a PRUDP v0 connection.
+///
+/// This doesn't own a socket.  Datagrams from the server are passed to [PrudpConnection::handle_datagram],
+/// and every method returns the datagrams that need to be sent.  Reliable packets stay in
+/// [PrudpConnection::unacknowledged_datagrams] until the server acknowledges them,
+/// so the caller decides when to resend them.
+pub struct PrudpConnection {
+    settings: PrudpSettings,
+    state: ConnectionState,
+    source: VirtualPort,
+    destination: VirtualPort,
+    session_id: u8,
+    remote_session_id: Option<u8>,
+    connection_signature: u32,
+    remote_connection_signature: u32,
+    sequence_id: u16,
+    ping_sequence_id: u16,
+    expected_sequence_id: u16,
+    encryption: Rc4,
+    decryption: Rc4,
+    unacknowledged: Vec<PendingPacket>,
+    out_of_order: Vec<PrudpPacket>,
+    fragments: Vec<u8>,
+    messages: VecDeque<Vec<u8>>,
+}
+
+impl PrudpConnection {
+    /// Creates a connection.  The session id and connection signature should be random.
+    pub fn new(
+        settings: PrudpSettings,
+        source: VirtualPort,
+        destination: VirtualPort,
+        session_id: u8,
+        connection_signature: u32,
+    ) -> Self {
+        let encryption = Rc4::new(&settings.payload_key);
+        let decryption = Rc4::new(&settings.payload_key);
+
+        Self {
+            settings,
+            state: ConnectionState::Idle,
+            source,
+            destination,
+            session_id,
+            remote_session_id: None,
+            connection_signature,
+            remote_connection_signature: 0,
+            sequence_id: 0,
+            ping_sequence_id: 0,
+            expected_sequence_id: FIRST_REMOTE_SEQUENCE_ID,
+            encryption,
+            decryption,
+            unacknowledged: vec![],
+            out_of_order: vec![],
+            fragments: vec![],
+            messages: VecDeque::new(),
+        }
+    }
+
+    pub fn get_state(&self) -> ConnectionState {
+        self.state
+    }
+
+    pub fn get_session_id(&self) -> u8 {
+        self.session_id
+    }
+
+    /// The server's session id, which is known once the connection is established.
+    pub fn get_remote_session_id(&self) -> Option<u8> {
+        self.remote_session_id
+    }
+
+    /// Reliable packets the server hasn't acknowledged yet, in the order they were sent.
+    pub fn unacknowledged_datagrams(&self) -> impl Iterator<Item = &[u8]> {
+        self.unacknowledged
+            .iter()
+            .map(|pending| pending.datagram.as_slice())
+    }
+
+    /// Returns the next message the server sent, after its fragments have been reassembled and decrypted.
+    pub fn receive(&mut self) -> Option<Vec<u8>> {
+        self.messages.pop_front()
+    }
+
+    /// Starts the handshake by creating a syn packet.
+    pub fn connect(&mut self) -> CtrResult<Vec<u8>> {
+        if self.state != ConnectionState::Idle {
+            return Err(error::already_initialized());
+        }
+
+        let mut packet = self.create_packet(PacketType::Syn, PacketFlag::NeedAck as u16);
+        packet.sequence_id = self.next_sequence_id();
+
+        let datagram = self.track(packet)?;
+        self.state = ConnectionState::SynSent;
+
+        Ok(datagram)
+    }
+
+    /// Encrypts a message and splits it into data packets.
+    pub fn send(&mut self, message: &[u8]) -> CtrResult<Vec<Vec<u8>>> {
+        if self.state != ConnectionState::Connected {
+            return Err(error::not_initialized());
+        }
+
+        let chunks = if message.is_empty() {
+            vec![message]
+        } else {
+            message.chunks(self.settings.fragment_size).collect()
+        };
+
+        if chunks.len() > MAX_FRAGMENT_COUNT {
+            return Err(error::too_large());
+        }
+
+        let last_index = chunks.len() - 1;
+        let mut datagrams = Vec::with_capacity(chunks.len());
+
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            let mut packet = self.create_packet(
+                PacketType::Data,
+                PacketFlag::Reliable | PacketFlag::NeedAck | PacketFlag::HasSize,
+            );
+            packet.sequence_id = self.next_sequence_id();
+            packet.fragment_id = if index == last_index {
+                0
+            } else {
+                (index + 1) as u8
+            };
+            packet.payload = chunk.to_vec();
+            self.encryption.apply(&mut packet.payload);
+
+            datagrams.push(self.track(packet)?);
+        }
+
+        Ok(datagrams)
+    }
+
+    /// Creates a ping to keep the connection alive.  Pings aren't resent, so they aren't tracked.
+    pub fn ping(&mut self) -> CtrResult<Vec<u8>> {
+        if self.state != ConnectionState::Connected {
+            return Err(error::not_initialized());
+        }
+
+        let mut packet = self.create_packet(PacketType::Ping, PacketFlag::NeedAck as u16);
+        packet.sequence_id = self.ping_sequence_id;
+        self.ping_sequence_id = self.ping_sequence_id.wrapping_add(1);
+        packet.signature = self.get_signature(&packet);
+
+        packet.encode(&self.settings.access_key)
+    }
+
+    pub fn disconnect(&mut self) -> CtrResult<Vec<u8>> {
+        if self.state != ConnectionState::Connected {
+            return Err(error::not_initialized());
+        }
+
+        let mut packet = self.create_packet(
+            PacketType::Disconnect,
+            PacketFlag::Reliable | PacketFlag::NeedAck,
+        );
+        packet.sequence_id = self.next_sequence_id();
+
+        let datagram = self.track(packet)?;
+        self.state = ConnectionState::Disconnecting;
+
+        Ok(datagram)
+    }
+
+    /// Handles a datagram from the server and returns the datagrams to send in response,
+    /// such as acknowledgements or the next step of the handshake.
+    pub fn handle_datagram(&mut self, datagram: &[u8]) -> CtrResult<Vec<Vec<u8>>> {
+        let packet = PrudpPacket::decode(datagram, &self.settings.access_key)?;
+
+        if packet.source != self.destination || packet.destination != self.source {
+            return Err(error::invalid_value());
+        }
+
+        if let Some(remote_session_id) = self.remote_session_id {
+            if packet.session_id != remote_session_id {
+                return Err(error::invalid_value());
+            }
+        }
+
+        if packet.is_ack() {
+            return self.handle_ack(packet);
+        }
+
+        self.validate_signature(&packet)?;
+
+        match packet.packet_type {
+            PacketType::Data => self.handle_data(packet),
+            PacketType::Disconnect => {
+                let ack = self.create_ack(&packet)?;
+                self.unacknowledged.clear();
+                self.state = ConnectionState::Disconnected;
+                Ok(vec![ack])
+            }
+            PacketType::Ping if packet.has_flag(PacketFlag::NeedAck) => {
+                Ok(vec![self.create_ack(&packet)?])
+            }
+            PacketType::Ping => Ok(vec![]),
+            PacketType::Syn | PacketType::Connect => Err(error::invalid_value()),
+        }
+    }
+
+    fn handle_ack(&mut self, packet: PrudpPacket) -> CtrResult<Vec<Vec<u8>>> {
+        match (packet.packet_type, self.state) {
+            (PacketType::Syn, ConnectionState::SynSent) => {
+                self.acknowledge(&packet);
+                self.remote_connection_signature = packet.connection_signature;
+
+                let mut connect = self.create_packet(
+                    PacketType::Connect,
+                    PacketFlag::Reliable | PacketFlag::NeedAck,
+                );
+                connect.sequence_id = self.next_sequence_id();
+                connect.connection_signature = self.connection_signature;
+
+                let datagram = self.track(connect)?;
+                self.state = ConnectionState::Connecting;
+
+                Ok(vec![datagram])
+            }
+            (PacketType::Connect, ConnectionState::Connecting) => {
+                self.validate_signature(&packet)?;
+                self.acknowledge(&packet);
+                self.remote_session_id = Some(packet.session_id);
+                self.state = ConnectionState::Connected;
+                Ok(vec![])
+            }
+            (PacketType::Disconnect, ConnectionState::Disconnecting) => {
+                self.validate_signature(&packet)?;
+                self.acknowledge(&packet);
+                self.state = ConnectionState::Disconnected;
+                Ok(vec![])
+            }
+            (PacketType::Data, _) => {
+                self.validate_signature(&packet)?;
+                self.acknowledge(&packet);
+                Ok(vec![])
+            }
+            // Pings aren't tracked, and late acks for packets that were already acknowledged can be ignored.
+            _ => Ok(vec![]),
+        }
+    }
+
+    fn handle_data(&mut self, packet: PrudpPacket) -> CtrResult<Vec<Vec<u8>>> {
+        if !matches!(
+            self.state,
+            ConnectionState::Connected | ConnectionState::Disconnecting
+        ) {
+            return Err(error::not_initialized());
+        }
+
+        let is_reliable = packet.has_flag(PacketFlag::Reliable);
+        let distance = packet.sequence_id.wrapping_sub(self.expected_sequence_id);
+
+        // Packets too far ahead aren't acknowledged, so the server sends them again once there's room.
+        if is_reliable && (MAX_OUT_OF_ORDER_DISTANCE..0x8000).contains(&distance) {
+            return Ok(vec![]);
+        }
+
+        let mut responses = vec![];
+
+        if packet.has_flag(PacketFlag::NeedAck) {
+            responses.push(self.create_ack(&packet)?);
+        }
+
+        if !is_reliable {
+            self.receive_fragment(packet);
+            return Ok(responses);
+        }
+
+        if distance == 0 {
+            self.receive_fragment(packet);
+            self.expected_sequence_id = self.expected_sequence_id.wrapping_add(1);
+
+            while let Some(index) = self
+                .out_of_order
+                .iter()
+                .position(|buffered| buffered.sequence_id == self.expected_sequence_id)
+            {
+                let buffered = self.out_of_order.swap_remove(index);
+                self.receive_fragment(buffered);
+                self.expected_sequence_id = self.expected_sequence_id.wrapping_add(1);
+            }
+        } else if distance < 0x8000
+            && !self
+                .out_of_order
+                .iter()
+                .any(|buffered| buffered.sequence_id == packet.sequence_id)
+        {
+            self.out_of_order.push(packet);
+        }
+
+        // Anything else was already received, and only needed to be acknowledged again.
+        Ok(responses)
+    }
+
+    /// Fragments have to be decrypted in order since the keystream continues across packets.
+    fn receive_fragment(&mut self, mut packet: PrudpPacket) {
+        self.decryption.apply(&mut packet.payload);
+        self.fragments.extend_from_slice(&packet.payload);
+
+        if packet.fragment_id == 0 {
+            self.messages.push_back(mem::take(&mut self.fragments));
+        }
+    }
+
+    fn create_packet(&self, packet_type: PacketType, flags: u16) -> PrudpPacket {
+        let mut packet = PrudpPacket::new(self.source, self.destination, packet_type, flags);
+        packet.session_id = self.session_id;
+        packet
+    }
+
+    fn create_ack(&self, packet: &PrudpPacket) -> CtrResult<Vec<u8>> {
+        let mut ack = self.create_packet(packet.packet_type, PacketFlag::Ack as u16);
+        ack.sequence_id = packet.sequence_id;
+        ack.fragment_id = packet.fragment_id;
+        ack.signature = self.get_signature(&ack);
+        ack.encode(&self.settings.access_key)
+    }
+
+    fn next_sequence_id(&mut self) -> u16 {
+        let sequence_id = self.sequence_id;
+        self.sequence_id = self.sequence_id.wrapping_add(1);
+        sequence_id
+    }
+
+    /// Data packets are signed with their payload, and other packets use the other side's connection signature.
+    fn get_signature(&self, packet: &PrudpPacket) -> u32 {
+        match packet.packet_type {
+            PacketType::Data => {
+                calculate_data_signature(&self.settings.access_key, &packet.payload)
+            }
+            _ => self.remote_connection_signature,
+        }
+    }
+
+    fn validate_signature(&self, packet: &PrudpPacket) -> CtrResult {
+        let expected = match packet.packet_type {
+            PacketType::Data => {
+                calculate_data_signature(&self.settings.access_key, &packet.payload)
+            }
+            _ => self.connection_signature,
+        };
+
+        if packet.signature != expected {
+            return Err(error::invalid_value());
+        }
+
+        Ok(())
+    }
+
+    fn track(&mut self, mut packet: PrudpPacket) -> CtrResult<Vec<u8>> {
+        packet.signature = self.get_signature(&packet);
+        let datagram = packet.encode(&self.settings.access_key)?;
+
+        self.unacknowledged.push(PendingPacket {
+            packet_type: packet.packet_type,
+            sequence_id: packet.sequence_id,
+            datagram: datagram.clone(),
+        });
+
+        Ok(datagram)
+    }
+
+    fn acknowledge(&mut self, ack: &PrudpPacket) {
+        self.unacknowledged.retain(|pending| {
+            pending.packet_type != ack.packet_type || pending.sequence_id != ack.sequence_id
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prudp::{StreamType, FRIENDS_ACCESS_KEY};
+
+    const CLIENT_SIGNATURE: u32 = 0x11111111;
+    const SERVER_SIGNATURE: u32 = 0x22222222;
+    const SERVER_SESSION_ID: u8 = 0x55;
+
+    fn client_port() -> VirtualPort {
+        VirtualPort::new(StreamType::RvSecure, 0xf)
+    }
+
+    fn server_port() -> VirtualPort {
+        VirtualPort::new(StreamType::RvSecure, 1)
+    }
+
+    fn create_connection() -> PrudpConnection {
+        let mut settings = PrudpSettings::new(FRIENDS_ACCESS_KEY);
+        settings.fragment_size = 4;
+        PrudpConnection::new(
+            settings,
+            client_port(),
+            server_port(),
+            0x12,
+            CLIENT_SIGNATURE,
+        )
+    }
+
+    fn server_packet(packet_type: PacketType, flags: u16, sequence_id: u16) -> PrudpPacket {
+        let mut packet = PrudpPacket::new(server_port(), client_port(), packet_type, flags);
+        packet.session_id = SERVER_SESSION_ID;
+        packet.sequence_id = sequence_id;
+        packet.signature = CLIENT_SIGNATURE;
+        packet
+    }
+
+    fn encode(packet: &PrudpPacket) -> Vec<u8> {
+        packet.encode(FRIENDS_ACCESS_KEY).unwrap()
+    }
+
+    fn decode(datagram: &[u8]) -> PrudpPacket {
+        PrudpPacket::decode(datagram, FRIENDS_ACCESS_KEY).unwrap()
+    }
+
+    fn create_connected_connection() -> PrudpConnection {
+        let mut connection = create_connection();
+        connection.connect().unwrap();
+
+        let mut syn_ack = server_packet(PacketType::Syn, PacketFlag::Ack as u16, 0);
+        syn_ack.signature = 0;
+        syn_ack.connection_signature = SERVER_SIGNATURE;
+        connection.handle_datagram(&encode(&syn_ack)).unwrap();
+
+        let connect_ack = server_packet(PacketType::Connect, PacketFlag::Ack as u16, 1);
+        connection.handle_datagram(&encode(&connect_ack)).unwrap();
+
+        connection
+    }
+
+    fn server_data(
+        encryption: &mut Rc4,
+        sequence_id: u16,
+        fragment_id: u8,
+        payload: &[u8],
+    ) -> Vec<u8> {
+        let mut packet = server_packet(
+            PacketType::Data,
+            PacketFlag::Reliable | PacketFlag::NeedAck | PacketFlag::HasSize,
+            sequence_id,
+        );
+        packet.fragment_id = fragment_id;
+        packet.payload = payload.to_vec();
+        encryption.apply(&mut packet.payload);
+        packet.signature = calculate_data_signature(FRIENDS_ACCESS_KEY, &packet.payload);
+        encode(&packet)
+    }
+
+    mod connect {
+        use super::*;
+
+        #[test]
+        fn should_complete_the_handshake() {
+            let mut connection = create_connection();
+
+            let syn = decode(&connection.connect().unwrap());
+            assert_eq!(syn.packet_type, PacketType::Syn);
+            assert_eq!(syn.sequence_id, 0);
+            assert_eq!(connection.get_state(), ConnectionState::SynSent);
+
+            let mut syn_ack = server_packet(PacketType::Syn, PacketFlag::Ack as u16, 0);
+            syn_ack.signature = 0;
+            syn_ack.connection_signature = SERVER_SIGNATURE;
+            let responses = connection.handle_datagram(&encode(&syn_ack)).unwrap();
+
+            let connect = decode(&responses[0]);
+            assert_eq!(connect.packet_type, PacketType::Connect);
+            assert_eq!(connect.sequence_id, 1);
+            assert_eq!(connect.signature, SERVER_SIGNATURE);
+            assert_eq!(connect.connection_signature, CLIENT_SIGNATURE);
+            assert_eq!(connection.get_state(), ConnectionState::Connecting);
+
+            let connect_ack = server_packet(PacketType::Connect, PacketFlag::Ack as u16, 1);
+            connection.handle_datagram(&encode(&connect_ack)).unwrap();
+
+            assert_eq!(connection.get_state(), ConnectionState::Connected);
+            assert_eq!(connection.get_remote_session_id(), Some(SERVER_SESSION_ID));
+            assert_eq!(connection.unacknowledged_datagrams().count(), 0);
+        }
+
+        #[test]
+        fn should_return_an_error_if_already_connecting() {
+            let mut connection = create_connection();
+            connection.connect().unwrap();
+
+            let result = connection.connect();
+
+            assert_eq!(result, Err(error::already_initialized()));
+        }
+
+        #[test]
+        fn should_return_an_error_for_a_bad_connect_signature() {
+            let mut connection = create_connection();
+            connection.connect().unwrap();
+
+            let mut syn_ack = server_packet(PacketType::Syn, PacketFlag::Ack as u16, 0);
+            syn_ack.connection_signature = SERVER_SIGNATURE;
+            connection.handle_datagram(&encode(&syn_ack)).unwrap();
+
+            let mut connect_ack = server_packet(PacketType::Connect, PacketFlag::Ack as u16, 1);
+            connect_ack.signature = 0xdeadbeef;
+            let result = connection.handle_datagram(&encode(&connect_ack));
+
+            assert_eq!(result, Err(error::invalid_value()));
+            assert_eq!(connection.get_state(), ConnectionState::Connecting);
+        }
+    }
+
+    mod send {
+        use super::*;
+
+        #[test]
+        fn should_fragment_and_encrypt_messages() {
+            let mut connection = create_connected_connection();
+
+            let datagrams = connection.send(b"Hello world").unwrap();
+            let packets = datagrams
+                .iter()
+                .map(|datagram| decode(datagram))
+                .collect::<Vec<PrudpPacket>>();
+
+            assert_eq!(
+                packets
+                    .iter()
+                    .map(|packet| (packet.sequence_id, packet.fragment_id))
+                    .collect::<Vec<(u16, u8)>>(),
+                [(2, 1), (3, 2), (4, 0)]
+            );
+
+            let mut decryption = Rc4::new(DEFAULT_PAYLOAD_KEY);
+            let mut message = vec![];
+            for packet in packets.iter() {
+                assert_eq!(
+                    packet.signature,
+                    calculate_data_signature(FRIENDS_ACCESS_KEY, &packet.payload)
+                );
+                let mut payload = packet.payload.clone();
+                decryption.apply(&mut payload);
+                message.extend_from_slice(&payload);
+            }
+
+            assert_eq!(message, b"Hello world");
+            assert_eq!(connection.unacknowledged_datagrams().count(), 3);
+        }
+
+        #[test]
+        fn should_stop_tracking_acknowledged_packets() {
+            let mut connection = create_connected_connection();
+            let datagrams = connection.send(b"Hello").unwrap();
+
+            let mut ack = server_packet(PacketType::Data, PacketFlag::Ack as u16, 2);
+            ack.fragment_id = 1;
+            ack.signature = calculate_data_signature(FRIENDS_ACCESS_KEY, &[]);
+            connection.handle_datagram(&encode(&ack)).unwrap();
+
+            assert_eq!(
+                connection
+                    .unacknowledged_datagrams()
+                    .collect::<Vec<&[u8]>>(),
+                [datagrams[1].as_slice()]
+            );
+        }
+
+        #[test]
+        fn should_return_an_error_if_not_connected() {
+            let mut connection = create_connection();
+            let result = connection.send(b"Hello");
+            assert_eq!(result, Err(error::not_initialized()));
+        }
+
+        #[test]
+        fn should_return_an_error_if_there_are_too_many_fragments() {
+            let mut connection = create_connected_connection();
+            let result = connection.send(&[0; 257 * 4]);
+            assert_eq!(result, Err(error::too_large()));
+        }
+    }
+
+    mod handle_datagram {
+        use super::*;
+
+        #[test]
+        fn should_reassemble_fragments_received_out_of_order() {
+            let mut connection = create_connected_connection();
+            let mut encryption = Rc4::new(DEFAULT_PAYLOAD_KEY);
+            let first = server_data(&mut encryption, 1, 1, b"Hell");
+            let second = server_data(&mut encryption, 2, 0, b"o!");
+
+            let responses = connection.handle_datagram(&second).unwrap();
+            let ack = decode(&responses[0]);
+            assert!(ack.is_ack());
+            assert_eq!(ack.sequence_id, 2);
+            assert_eq!(connection.receive(), None);
+
+            connection.handle_datagram(&first).unwrap();
+
+            assert_eq!(connection.receive(), Some(b"Hello!".to_vec()));
+            assert_eq!(connection.receive(), None);
+        }
+
+        #[test]
+        fn should_buffer_packets_up_to_the_out_of_order_window() {
+            let mut connection = create_connected_connection();
+            let mut encryption = Rc4::new(DEFAULT_PAYLOAD_KEY);
+            let last_in_window = FIRST_REMOTE_SEQUENCE_ID + MAX_OUT_OF_ORDER_DISTANCE - 1;
+            let datagram = server_data(&mut encryption, last_in_window, 0, b"Hi");
+
+            let responses = connection.handle_datagram(&datagram).unwrap();
+
+            assert_eq!(decode(&responses[0]).sequence_id, last_in_window);
+            assert_eq!(connection.out_of_order.len(), 1);
+        }
+
+        #[test]
+        fn should_drop_packets_past_the_out_of_order_window() {
+            let mut connection = create_connected_connection();
+            let mut encryption = Rc4::new(DEFAULT_PAYLOAD_KEY);
+            let past_window = FIRST_REMOTE_SEQUENCE_ID + MAX_OUT_OF_ORDER_DISTANCE;
+            let datagram = server_data(&mut encryption, past_window, 0, b"Hi");
+
+            let responses = connection.handle_datagram(&datagram).unwrap();
+
+            assert!(responses.is_empty());
+            assert!(connection.out_of_order.is_empty());
+            assert_eq!(connection.receive(), None);
+        }
+
+        #[test]
+        fn should_acknowledge_duplicates_without_receiving_them_again() {
+            let mut connection = create_connected_connection();
+            let mut encryption = Rc4::new(DEFAULT_PAYLOAD_KEY);
+            let datagram = server_data(&mut encryption, 1, 0, b"Hi");
+
+            connection.handle_datagram(&datagram).unwrap();
+            let responses = connection.handle_datagram(&datagram).unwrap();
+
+            assert_eq!(responses.len(), 1);
+            assert_eq!(connection.receive(), Some(b"Hi".to_vec()));
+            assert_eq!(connection.receive(), None);
+        }
+
+        #[test]
+        fn should_return_an_error_for_a_bad_data_signature() {
+            let mut connection = create_connected_connection();
+            let mut packet = decode(&server_data(
+                &mut Rc4::new(DEFAULT_PAYLOAD_KEY),
+                1,
+                0,
+                b"Hi",
+            ));
+            packet.signature ^= 1;
+
+            let result = connection.handle_datagram(&encode(&packet));
+
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+
+        #[test]
+        fn should_return_an_error_for_another_session() {
+            let mut connection = create_connected_connection();
+            let mut ping = server_packet(PacketType::Ping, PacketFlag::NeedAck as u16, 0);
+            ping.session_id = SERVER_SESSION_ID + 1;
+
+            let result = connection.handle_datagram(&encode(&ping));
+
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+
+        #[test]
+        fn should_disconnect_when_the_server_disconnects() {
+            let mut connection = create_connected_connection();
+            connection.send(b"Hello").unwrap();
+            let disconnect = server_packet(
+                PacketType::Disconnect,
+                PacketFlag::Reliable | PacketFlag::NeedAck,
+                1,
+            );
+
+            let responses = connection.handle_datagram(&encode(&disconnect)).unwrap();
+
+            let ack = decode(&responses[0]);
+            assert_eq!(ack.packet_type, PacketType::Disconnect);
+            assert_eq!(ack.signature, SERVER_SIGNATURE);
+            assert_eq!(connection.get_state(), ConnectionState::Disconnected);
+            assert_eq!(connection.unacknowledged_datagrams().count(), 0);
+        }
+    }
+}
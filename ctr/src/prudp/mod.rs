@@ -0,0 +1,10 @@
+//! PRUDP is the reliable UDP transport NEX servers use, including the friends server.
+//!
+//! This implements version 0, which is what the 3DS uses.  Packets are built and parsed here,
+//! but sending them is left to the caller so the same code works with any socket.
+
+mod connection;
+pub use connection::*;
+
+mod packet;
+pub use packet::*;
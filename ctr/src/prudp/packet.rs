@@ -0,0 +1,443 @@
+use crate::{
+    crypto::{hmac_md5, md5},
+    res::{error, CtrResult},
+};
+use alloc::{vec, vec::Vec};
+use core::{
+    convert::{TryFrom, TryInto},
+    ops::{BitAnd, BitOr},
+};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+/// The access key the friends servers use.
+pub const FRIENDS_ACCESS_KEY: &[u8] = b"ridfebb9";
+/// The RC4 key payloads are encrypted with unless a game uses its own.
+pub const DEFAULT_PAYLOAD_KEY: &[u8] = b"CD&ML";
+
+/// The source, destination, type and flags, session id, packet signature, and sequence id.
+const HEADER_SIZE: usize = 11;
+const CHECKSUM_SIZE: usize = 1;
+/// The signature of data packets without a payload.
+const EMPTY_DATA_SIGNATURE: u32 = 0x12345678;
+
+const PACKET_TYPE_MASK: u16 = 0xf;
+const FLAGS_SHIFT: u16 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u8)]
+pub enum StreamType {
+    Do = 1,
+    Rv = 2,
+    RvSecure = 3,
+    SbManagement = 4,
+    Nat = 5,
+    SessionDiscovery = 6,
+    NatEcho = 7,
+    Routing = 8,
+}
+
+/// A stream type and port, which are packed into one byte with the stream type in the upper 4 bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VirtualPort {
+    pub stream_type: StreamType,
+    pub port: u8,
+}
+
+impl VirtualPort {
+    pub fn new(stream_type: StreamType, port: u8) -> Self {
+        Self { stream_type, port }
+    }
+
+    pub fn parse(value: u8) -> CtrResult<Self> {
+        let stream_type =
+            StreamType::try_from(value >> 4).map_err(|_| error::invalid_enum_value())?;
+        Ok(Self {
+            stream_type,
+            port: value & 0xf,
+        })
+    }
+
+    pub fn to_u8(&self) -> u8 {
+        (u8::from(self.stream_type) << 4) | (self.port & 0xf)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u8)]
+pub enum PacketType {
+    Syn = 0,
+    Connect = 1,
+    Data = 2,
+    Disconnect = 3,
+    Ping = 4,
+}
+
+/// Each flag represents one bit of a packet's flags.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoPrimitive)]
+#[repr(u16)]
+pub enum PacketFlag {
+    Ack = 0x1,
+    Reliable = 0x2,
+    NeedAck = 0x4,
+    HasSize = 0x8,
+    MultiAck = 0x200,
+}
+
+impl BitAnd<PacketFlag> for u16 {
+    type Output = u16;
+
+    fn bitand(self, rhs: PacketFlag) -> Self::Output {
+        self & (rhs as u16)
+    }
+}
+
+impl BitOr for PacketFlag {
+    type Output = u16;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        (self as u16) | (rhs as u16)
+    }
+}
+
+impl BitOr<PacketFlag> for u16 {
+    type Output = u16;
+
+    fn bitor(self, rhs: PacketFlag) -> Self::Output {
+        self | (rhs as u16)
+    }
+}
+
+/// A PRUDP v0 packet, as used by the 3DS NEX servers.
+///
+/// The packet type and flags are stored in a u16 with the type in the lower 4 bits.
+/// Syn and connect packets have a connection signature, data packets have a fragment id,
+/// and the payload size is only present when the `HasSize` flag is set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrudpPacket {
+    pub source: VirtualPort,
+    pub destination: VirtualPort,
+    pub packet_type: PacketType,
+    pub flags: u16,
+    pub session_id: u8,
+    pub signature: u32,
+    pub sequence_id: u16,
+    /// Only used by syn and connect packets.
+    pub connection_signature: u32,
+    /// Only used by data packets.  The last fragment of a message has an id of 0.
+    pub fragment_id: u8,
+    pub payload: Vec<u8>,
+}
+
+impl PrudpPacket {
+    pub fn new(
+        source: VirtualPort,
+        destination: VirtualPort,
+        packet_type: PacketType,
+        flags: u16,
+    ) -> Self {
+        Self {
+            source,
+            destination,
+            packet_type,
+            flags,
+            session_id: 0,
+            signature: 0,
+            sequence_id: 0,
+            connection_signature: 0,
+            fragment_id: 0,
+            payload: vec![],
+        }
+    }
+
+    pub fn has_flag(&self, flag: PacketFlag) -> bool {
+        self.flags & flag != 0
+    }
+
+    pub fn is_ack(&self) -> bool {
+        self.has_flag(PacketFlag::Ack)
+    }
+
+    fn has_connection_signature(&self) -> bool {
+        matches!(self.packet_type, PacketType::Syn | PacketType::Connect)
+    }
+
+    /// Encodes the packet and appends its checksum.
+    /// The payload is written as is, so it should already be encrypted.
+    pub fn encode(&self, access_key: &[u8]) -> CtrResult<Vec<u8>> {
+        if self.flags > (u16::MAX >> FLAGS_SHIFT) {
+            return Err(error::invalid_value());
+        }
+
+        if !self.has_flag(PacketFlag::HasSize) && !self.payload.is_empty() {
+            return Err(error::invalid_combination());
+        }
+
+        let payload_size: u16 = self
+            .payload
+            .len()
+            .try_into()
+            .map_err(|_| error::too_large())?;
+        let type_flags = u16::from(u8::from(self.packet_type)) | (self.flags << FLAGS_SHIFT);
+
+        let mut result = Vec::with_capacity(HEADER_SIZE + self.payload.len() + 8);
+        result.push(self.source.to_u8());
+        result.push(self.destination.to_u8());
+        result.extend_from_slice(&type_flags.to_le_bytes());
+        result.push(self.session_id);
+        result.extend_from_slice(&self.signature.to_le_bytes());
+        result.extend_from_slice(&self.sequence_id.to_le_bytes());
+
+        if self.has_connection_signature() {
+            result.extend_from_slice(&self.connection_signature.to_le_bytes());
+        }
+
+        if self.packet_type == PacketType::Data {
+            result.push(self.fragment_id);
+        }
+
+        if self.has_flag(PacketFlag::HasSize) {
+            result.extend_from_slice(&payload_size.to_le_bytes());
+        }
+
+        result.extend_from_slice(&self.payload);
+        result.push(calculate_checksum(access_key, &result));
+
+        Ok(result)
+    }
+
+    /// Decodes a packet, returning an error if its checksum doesn't match.
+    /// Packets without the `HasSize` flag are expected to have an empty payload.
+    pub fn decode(bytes: &[u8], access_key: &[u8]) -> CtrResult<Self> {
+        if bytes.len() < HEADER_SIZE + CHECKSUM_SIZE {
+            return Err(error::invalid_size());
+        }
+
+        let (data, checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+
+        if calculate_checksum(access_key, data) != checksum[0] {
+            return Err(error::invalid_value());
+        }
+
+        let type_flags = u16::from_le_bytes([data[2], data[3]]);
+        let packet_type = PacketType::try_from((type_flags & PACKET_TYPE_MASK) as u8)
+            .map_err(|_| error::invalid_enum_value())?;
+
+        let mut packet = Self {
+            source: VirtualPort::parse(data[0])?,
+            destination: VirtualPort::parse(data[1])?,
+            packet_type,
+            flags: type_flags >> FLAGS_SHIFT,
+            session_id: data[4],
+            signature: u32::from_le_bytes(data[5..9].try_into().unwrap()),
+            sequence_id: u16::from_le_bytes([data[9], data[10]]),
+            connection_signature: 0,
+            fragment_id: 0,
+            payload: vec![],
+        };
+
+        let mut offset = HEADER_SIZE;
+
+        if packet.has_connection_signature() {
+            let bytes = data
+                .get(offset..offset + 4)
+                .ok_or_else(error::invalid_size)?;
+            packet.connection_signature = u32::from_le_bytes(bytes.try_into().unwrap());
+            offset += 4;
+        }
+
+        if packet.packet_type == PacketType::Data {
+            packet.fragment_id = *data.get(offset).ok_or_else(error::invalid_size)?;
+            offset += 1;
+        }
+
+        let payload_size = if packet.has_flag(PacketFlag::HasSize) {
+            let bytes = data
+                .get(offset..offset + 2)
+                .ok_or_else(error::invalid_size)?;
+            offset += 2;
+            u16::from_le_bytes([bytes[0], bytes[1]]) as usize
+        } else {
+            0
+        };
+
+        if data.len() != offset + payload_size {
+            return Err(error::invalid_size());
+        }
+
+        packet.payload = data[offset..].to_vec();
+
+        Ok(packet)
+    }
+}
+
+/// Sums the data's little endian words, then adds the bytes of that sum,
+/// the bytes left over at the end of the data, and the bytes of the access key.
+pub fn calculate_checksum(access_key: &[u8], data: &[u8]) -> u8 {
+    let words = data.chunks_exact(4);
+    let remainder = words.remainder();
+    let word_sum = words.fold(0u32, |sum, word| {
+        sum.wrapping_add(u32::from_le_bytes(word.try_into().unwrap()))
+    });
+
+    access_key
+        .iter()
+        .chain(remainder.iter())
+        .chain(word_sum.to_le_bytes().iter())
+        .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+/// The signature of a data packet, which is calculated from the encrypted payload.
+pub fn calculate_data_signature(access_key: &[u8], payload: &[u8]) -> u32 {
+    if payload.is_empty() {
+        return EMPTY_DATA_SIGNATURE;
+    }
+
+    let hmac = hmac_md5(&md5(access_key), payload);
+    u32::from_le_bytes(hmac[..4].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_data_packet() -> PrudpPacket {
+        let mut packet = PrudpPacket::new(
+            VirtualPort::new(StreamType::RvSecure, 0xf),
+            VirtualPort::new(StreamType::RvSecure, 1),
+            PacketType::Data,
+            PacketFlag::Reliable | PacketFlag::NeedAck | PacketFlag::HasSize,
+        );
+        packet.session_id = 0x42;
+        packet.signature = 0x11223344;
+        packet.sequence_id = 2;
+        packet.fragment_id = 1;
+        packet.payload = vec![1, 2, 3, 4, 5];
+        packet
+    }
+
+    mod virtual_port {
+        use super::*;
+
+        #[test]
+        fn should_pack_the_stream_type_and_port() {
+            let port = VirtualPort::parse(0x31).unwrap();
+            assert_eq!(port, VirtualPort::new(StreamType::RvSecure, 1));
+            assert_eq!(port.to_u8(), 0x31);
+        }
+
+        #[test]
+        fn should_return_an_error_for_unknown_stream_types() {
+            let result = VirtualPort::parse(0xf1);
+            assert_eq!(result, Err(error::invalid_enum_value()));
+        }
+    }
+
+    mod encode {
+        use super::*;
+
+        #[test]
+        fn should_encode_a_data_packet() {
+            let result = create_data_packet().encode(FRIENDS_ACCESS_KEY).unwrap();
+            assert_eq!(
+                result,
+                [
+                    0x3f, 0x31, 0xe2, 0x00, 0x42, 0x44, 0x33, 0x22, 0x11, 0x02, 0x00, 0x01, 0x05,
+                    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x5d
+                ]
+            );
+        }
+
+        #[test]
+        fn should_encode_a_syn_packet() {
+            let mut packet = PrudpPacket::new(
+                VirtualPort::new(StreamType::RvSecure, 0xf),
+                VirtualPort::new(StreamType::RvSecure, 1),
+                PacketType::Syn,
+                PacketFlag::Ack | PacketFlag::HasSize,
+            );
+            packet.connection_signature = 0xaabbccdd;
+
+            let result = packet.encode(FRIENDS_ACCESS_KEY).unwrap();
+
+            assert_eq!(
+                result,
+                [
+                    0x3f, 0x31, 0x90, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xdd, 0xcc,
+                    0xbb, 0xaa, 0x00, 0x00, 0x17
+                ]
+            );
+        }
+
+        #[test]
+        fn should_return_an_error_if_a_payload_has_no_size() {
+            let mut packet = create_data_packet();
+            packet.flags = PacketFlag::Reliable as u16;
+
+            let result = packet.encode(FRIENDS_ACCESS_KEY);
+
+            assert_eq!(result, Err(error::invalid_combination()));
+        }
+    }
+
+    mod decode {
+        use super::*;
+
+        #[test]
+        fn should_decode_an_encoded_packet() {
+            let packet = create_data_packet();
+            let bytes = packet.encode(FRIENDS_ACCESS_KEY).unwrap();
+
+            let result = PrudpPacket::decode(&bytes, FRIENDS_ACCESS_KEY).unwrap();
+
+            assert_eq!(result, packet);
+            assert!(result.has_flag(PacketFlag::NeedAck));
+            assert!(!result.is_ack());
+        }
+
+        #[test]
+        fn should_return_an_error_for_a_bad_checksum() {
+            let mut bytes = create_data_packet().encode(FRIENDS_ACCESS_KEY).unwrap();
+            *bytes.last_mut().unwrap() ^= 1;
+
+            let result = PrudpPacket::decode(&bytes, FRIENDS_ACCESS_KEY);
+
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+
+        #[test]
+        fn should_return_an_error_for_the_wrong_access_key() {
+            let bytes = create_data_packet().encode(FRIENDS_ACCESS_KEY).unwrap();
+            let result = PrudpPacket::decode(&bytes, b"6f599f81");
+            assert_eq!(result, Err(error::invalid_value()));
+        }
+
+        #[test]
+        fn should_return_an_error_if_the_payload_size_does_not_match() {
+            let mut packet = create_data_packet().encode(FRIENDS_ACCESS_KEY).unwrap();
+            packet.remove(packet.len() - 2);
+            let checksum_offset = packet.len() - 1;
+            packet[checksum_offset] =
+                calculate_checksum(FRIENDS_ACCESS_KEY, &packet[..checksum_offset]);
+
+            let result = PrudpPacket::decode(&packet, FRIENDS_ACCESS_KEY);
+
+            assert_eq!(result, Err(error::invalid_size()));
+        }
+    }
+
+    mod calculate_data_signature {
+        use super::*;
+
+        #[test]
+        fn should_sign_the_payload() {
+            let result = calculate_data_signature(FRIENDS_ACCESS_KEY, &[1, 2, 3, 4, 5]);
+            assert_eq!(result, 0x84f0e021);
+        }
+
+        #[test]
+        fn should_use_a_fixed_signature_for_empty_payloads() {
+            let result = calculate_data_signature(FRIENDS_ACCESS_KEY, &[]);
+            assert_eq!(result, 0x12345678);
+        }
+    }
+}
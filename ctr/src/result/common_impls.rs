@@ -4,6 +4,10 @@ use core::num::{ParseIntError, TryFromIntError};
 use cstr_core::NulError;
 use no_std_io::Error as NoStdIoError;
 
+/// The message of a read error caused by an unknown enum value,
+/// which is converted to [error::invalid_enum_value].
+pub(crate) const INVALID_ENUM_VALUE_MESSAGE: &str = "Invalid enum value";
+
 impl From<TryFromIntError> for ResultCode {
     fn from(_: TryFromIntError) -> Self {
         error::invalid_value()
@@ -39,6 +43,9 @@ impl From<NoStdIoError> for ResultCode {
         match error {
             NoStdIoError::InvalidAlignment { .. } => error::misaligned_address(),
             NoStdIoError::InvalidSize { .. } => error::invalid_size(),
+            NoStdIoError::InvalidRead { message } if message == INVALID_ENUM_VALUE_MESSAGE => {
+                error::invalid_enum_value()
+            }
             NoStdIoError::InvalidRead { .. } => error::invalid_value(),
             NoStdIoError::InvalidWrite { .. } => error::invalid_value(),
         }
@@ -1,4 +1,5 @@
 mod common_impls;
+pub(crate) use common_impls::INVALID_ENUM_VALUE_MESSAGE;
 
 pub mod error;
 
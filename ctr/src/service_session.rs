@@ -18,6 +18,23 @@ macro_rules! create_session_guard {
 }
 
 macro_rules! create_session_manager {
+    (mock) => {
+        mod service_session_manager {
+            pub fn get_handle() -> u32 {
+                0
+            }
+
+            pub fn init() -> crate::result::CtrResult {
+                Ok(())
+            }
+
+            pub fn exit() {}
+
+            crate::service_session::create_session_guard!();
+        }
+
+        pub use service_session_manager::{exit, get_handle, init, Session};
+    };
     ($init_impl:expr) => {
         mod service_session_manager {
             pub use super::*;
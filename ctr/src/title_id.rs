@@ -0,0 +1,279 @@
+/*
+    Thanks to 3dbrew (https://www.3dbrew.org/wiki/Titles) for the documentation
+*/
+
+use crate::{
+    fs::{FsPath, MediaType},
+    res::{error, ResultCode},
+};
+use core::{fmt, mem, str::FromStr};
+use no_std_io::{EndianRead, EndianWrite, ReadOutput};
+
+const PLATFORM_CTR: u16 = 0x0004;
+
+const CATEGORY_DEMO: u16 = 0x0002;
+const CATEGORY_UPDATE: u16 = 0x000e;
+const CATEGORY_SYSTEM: u16 = 0x0010;
+const CATEGORY_APPLET: u16 = 0x0030;
+const CATEGORY_DLC: u16 = 0x008c;
+const CATEGORY_TWL: u16 = 0x8000;
+
+/// System titles that only run on the New 3DS have this bit set in their low title id.
+const NEW_3DS_VARIATION: u32 = 0x20000000;
+/// Applications that only run on the New 3DS use unique ids in this range, such as `0xf70cc` for Xenoblade Chronicles 3D.
+const NEW_3DS_UNIQUE_IDS: core::ops::RangeInclusive<u32> = 0xf7000..=0xf7fff;
+
+/// A title id, such as `0004013000003202` for the friends sysmodule.
+///
+/// The high 32 bits hold the platform and category, and the low 32 bits hold the unique id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct TitleId(u64);
+
+impl TitleId {
+    pub const fn new(raw_title_id: u64) -> Self {
+        Self(raw_title_id)
+    }
+
+    pub const fn raw(&self) -> u64 {
+        self.0
+    }
+
+    pub const fn high(&self) -> u32 {
+        (self.0 >> 32) as u32
+    }
+
+    pub const fn low(&self) -> u32 {
+        self.0 as u32
+    }
+
+    /// The platform, which is 4 for 3DS titles.
+    pub const fn platform(&self) -> u16 {
+        (self.0 >> 48) as u16
+    }
+
+    pub const fn category(&self) -> u16 {
+        (self.0 >> 32) as u16
+    }
+
+    pub const fn unique_id(&self) -> u32 {
+        (self.low() >> 8) & 0xfffff
+    }
+
+    pub const fn variation(&self) -> u8 {
+        self.0 as u8
+    }
+
+    pub fn is_ctr(&self) -> bool {
+        self.platform() == PLATFORM_CTR
+    }
+
+    pub fn is_system(&self) -> bool {
+        self.category() & CATEGORY_SYSTEM != 0
+    }
+
+    pub fn is_applet(&self) -> bool {
+        self.category() == CATEGORY_APPLET
+    }
+
+    pub fn is_dlc(&self) -> bool {
+        self.category() == CATEGORY_DLC
+    }
+
+    pub fn is_update(&self) -> bool {
+        self.category() == CATEGORY_UPDATE
+    }
+
+    pub fn is_demo(&self) -> bool {
+        self.category() == CATEGORY_DEMO
+    }
+
+    pub fn is_dsiware(&self) -> bool {
+        self.category() & CATEGORY_TWL != 0
+    }
+
+    pub fn is_new_3ds_only(&self) -> bool {
+        if self.is_system() {
+            self.low() & NEW_3DS_VARIATION != 0
+        } else {
+            NEW_3DS_UNIQUE_IDS.contains(&self.unique_id())
+        }
+    }
+
+    /// The id of the title's save data, which is the low title id.
+    pub const fn save_data_id(&self) -> u32 {
+        self.low()
+    }
+
+    /// The id of the system save data used by a system title, such as `0x10032` for the friends sysmodule.
+    pub const fn system_save_data_id(&self) -> u32 {
+        0x10000 | self.unique_id()
+    }
+
+    /// The binary path of the title's save archive.
+    /// System titles use the media type followed by their system save data id,
+    /// and other titles use the media type followed by the low and high title id.
+    ///
+    /// For example, the friends sysmodule's system save archive is `[0, 0x10032]` on the NAND.
+    pub fn save_archive_path(&self, media_type: MediaType) -> FsPath {
+        if self.is_system() {
+            FsPath::new_binary([media_type.into(), self.system_save_data_id()])
+        } else {
+            FsPath::new_binary([media_type.into(), self.low(), self.high()])
+        }
+    }
+}
+
+impl From<u64> for TitleId {
+    fn from(raw_title_id: u64) -> Self {
+        Self(raw_title_id)
+    }
+}
+
+impl From<TitleId> for u64 {
+    fn from(title_id: TitleId) -> Self {
+        title_id.0
+    }
+}
+
+impl fmt::Display for TitleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for TitleId {
+    type Err = ResultCode;
+
+    /// Parses a title id formatted as 16 hex digits, such as `0004013000003202`.
+    fn from_str(title_id: &str) -> Result<Self, Self::Err> {
+        if title_id.len() != 16 || !title_id.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(error::invalid_value());
+        }
+
+        Ok(Self(u64::from_str_radix(title_id, 16)?))
+    }
+}
+
+impl EndianRead for TitleId {
+    fn try_read_le(bytes: &[u8]) -> Result<ReadOutput<Self>, no_std_io::Error> {
+        Ok(u64::try_read_le(bytes)?.into_other())
+    }
+
+    fn try_read_be(bytes: &[u8]) -> Result<ReadOutput<Self>, no_std_io::Error> {
+        Ok(u64::try_read_be(bytes)?.into_other())
+    }
+}
+
+impl EndianWrite for TitleId {
+    fn get_size(&self) -> usize {
+        mem::size_of::<u64>()
+    }
+
+    fn try_write_le(&self, dst: &mut [u8]) -> Result<usize, no_std_io::Error> {
+        self.0.try_write_le(dst)
+    }
+
+    fn try_write_be(&self, dst: &mut [u8]) -> Result<usize, no_std_io::Error> {
+        self.0.try_write_be(dst)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use no_std_io::{Reader, Writer};
+
+    const FRIENDS_SYSMODULE: TitleId = TitleId::new(0x0004013000003202);
+
+    #[test]
+    fn should_split_the_title_id() {
+        assert_eq!(FRIENDS_SYSMODULE.platform(), 0x0004);
+        assert_eq!(FRIENDS_SYSMODULE.category(), 0x0130);
+        assert_eq!(FRIENDS_SYSMODULE.high(), 0x00040130);
+        assert_eq!(FRIENDS_SYSMODULE.low(), 0x00003202);
+        assert_eq!(FRIENDS_SYSMODULE.unique_id(), 0x32);
+        assert_eq!(FRIENDS_SYSMODULE.variation(), 0x02);
+    }
+
+    #[test]
+    fn should_classify_titles() {
+        assert!(FRIENDS_SYSMODULE.is_ctr());
+        assert!(FRIENDS_SYSMODULE.is_system());
+        assert!(TitleId::new(0x0004003000008f02).is_applet());
+        assert!(TitleId::new(0x0004008c00030000).is_dlc());
+        assert!(TitleId::new(0x0004000e00030000).is_update());
+        assert!(TitleId::new(0x0004000200030000).is_demo());
+        assert!(TitleId::new(0x0004800542383841).is_dsiware());
+        assert!(!TitleId::new(0x0004000000030000).is_system());
+    }
+
+    #[test]
+    fn should_detect_new_3ds_only_titles() {
+        assert!(TitleId::new(0x0004013020003202).is_new_3ds_only());
+        assert!(TitleId::new(0x000400000f70cc00).is_new_3ds_only());
+        assert!(!TitleId::new(0x00040000000f7000).is_new_3ds_only());
+        assert!(!FRIENDS_SYSMODULE.is_new_3ds_only());
+        assert!(!TitleId::new(0x0004000000030000).is_new_3ds_only());
+    }
+
+    #[test]
+    fn should_get_save_data_ids() {
+        assert_eq!(FRIENDS_SYSMODULE.system_save_data_id(), 0x10032);
+        assert_eq!(TitleId::new(0x0004000000030000).save_data_id(), 0x00030000);
+    }
+
+    #[test]
+    fn should_get_save_archive_paths() {
+        assert_eq!(
+            FRIENDS_SYSMODULE.save_archive_path(MediaType::Nand),
+            FsPath::new_binary([0, 0x10032])
+        );
+        assert_eq!(
+            TitleId::new(0x0004000000030000).save_archive_path(MediaType::Sd),
+            FsPath::new_binary([1, 0x00030000, 0x00040000])
+        );
+        assert_eq!(
+            TitleId::new(0x0004000000030000)
+                .save_archive_path(MediaType::Sd)
+                .get_inner(),
+            [1, 0, 0, 0, 0, 0, 3, 0, 0, 0, 4, 0]
+        );
+    }
+
+    #[test]
+    fn should_format_as_fixed_width_hex() {
+        assert_eq!(TitleId::new(0x10032).to_string(), "0000000000010032");
+    }
+
+    #[test]
+    fn should_parse_fixed_width_hex() {
+        let result: TitleId = "0004013000003202".parse().unwrap();
+        assert_eq!(result, FRIENDS_SYSMODULE);
+    }
+
+    #[test]
+    fn should_not_parse_other_formats() {
+        assert_eq!(
+            "10032".parse::<TitleId>().unwrap_err(),
+            error::invalid_value()
+        );
+        assert_eq!(
+            "+004013000003202".parse::<TitleId>().unwrap_err(),
+            error::invalid_value()
+        );
+    }
+
+    #[test]
+    fn should_read_and_write_as_a_u64() {
+        let mut bytes = [0u8; 8];
+        bytes.write_le(0, &FRIENDS_SYSMODULE).unwrap();
+
+        assert_eq!(bytes, 0x0004013000003202u64.to_le_bytes());
+        assert_eq!(bytes.read_le::<TitleId>(0).unwrap(), FRIENDS_SYSMODULE);
+        assert_eq!(
+            bytes.read_be::<TitleId>(0).unwrap(),
+            TitleId::new(0x0232000030010400)
+        );
+    }
+}
@@ -0,0 +1,195 @@
+// These tests only run with the host_fs feature, e.g. `cargo test --features host_fs`
+#![cfg(feature = "host_fs")]
+
+use ctr::fs::{
+    self, ArchiveId, DirectoryOps, FsArchive, FsPath, MediaType, OpenFlags, SaveData,
+    SaveDataConfig,
+};
+use std::{convert::TryFrom, env, fs as host_fs, path::PathBuf};
+
+fn get_root() -> PathBuf {
+    env::temp_dir().join(format!("ctr_host_fs_{}", std::process::id()))
+}
+
+fn setup(test_name: &str) -> PathBuf {
+    let root = get_root();
+    let test_dir = root.join("sdmc").join(test_name);
+
+    host_fs::create_dir_all(&test_dir).unwrap();
+    host_fs::create_dir_all(root.join("syssave").join("0000000000010032")).unwrap();
+    fs::set_host_root(&root);
+
+    test_dir
+}
+
+#[test]
+fn should_read_sd_files() {
+    let test_dir = setup("read");
+    host_fs::write(test_dir.join("file.txt"), b"test data").unwrap();
+
+    let result = fs::read("sd:/read/file.txt").unwrap();
+    assert_eq!(result, b"test data");
+}
+
+#[test]
+fn should_append_to_sd_files() {
+    let test_dir = setup("write");
+    host_fs::write(test_dir.join("file.txt"), b"test ").unwrap();
+
+    fs::write("sd:/write/file.txt", "data").unwrap();
+
+    let result = host_fs::read(test_dir.join("file.txt")).unwrap();
+    assert_eq!(result, b"test data");
+}
+
+#[test]
+fn should_create_and_read_directories() {
+    let test_dir = setup("dir");
+    host_fs::write(test_dir.join("写真.jpg"), b"1234").unwrap();
+
+    fs::create_dir("sd:/dir/sub").unwrap();
+
+    let mut entries = fs::read_dir("sd:/dir")
+        .unwrap()
+        .map(|entry| (entry.name().to_string(), entry.size()))
+        .collect::<Vec<(String, u64)>>();
+    entries.sort();
+
+    assert_eq!(
+        entries,
+        vec![("sub".to_string(), 0), ("写真.jpg".to_string(), 4)]
+    );
+}
+
+#[test]
+fn should_read_system_save_files() {
+    setup("syssave");
+    let save_dir = get_root().join("syssave/0000000000010032/1");
+    host_fs::create_dir_all(&save_dir).unwrap();
+    host_fs::write(save_dir.join("friendlist"), b"friends").unwrap();
+
+    let result = fs::read("syssave:/0000000000010032/1/friendlist").unwrap();
+    assert_eq!(result, b"friends");
+}
+
+#[test]
+fn should_return_fs_errors() {
+    setup("errors");
+
+    let result = fs::read("sd:/errors/missing.txt").unwrap_err();
+    assert_eq!(result, fs::error::file_not_found());
+
+    let result = fs::create_dir("sd:/errors/missing/dir").unwrap_err();
+    assert_eq!(result, fs::error::path_not_found());
+
+    let result = FsArchive::new(
+        ArchiveId::SystemSaveData,
+        &FsPath::new_binary([0, 0x12345678]),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(result, fs::error::save_data_not_found());
+}
+
+#[test]
+fn should_use_archives_and_files_directly() {
+    setup("archive");
+
+    let archive = FsArchive::new(ArchiveId::Sdmc, &FsPath::new_empty_path()).unwrap();
+    let file_path = FsPath::try_from("/archive/file.bin").unwrap();
+    let mut file = archive
+        .open_file(&file_path, OpenFlags::ReadWriteCreate)
+        .unwrap();
+
+    file.write(&[1, 2, 3, 4]).unwrap();
+
+    assert_eq!(file.size().unwrap(), 4);
+    assert_eq!(file.read(1, 2).unwrap(), vec![2, 3]);
+    assert_eq!(file.read(2, 10).unwrap(), vec![3, 4]);
+}
+
+#[test]
+fn should_create_and_commit_system_save_data() {
+    setup("save_data");
+    let save_data = SaveData::new_system(MediaType::Nand, 0x20032);
+    let _ = save_data.delete();
+
+    let result = save_data.open().err().unwrap();
+    assert_eq!(result, fs::error::save_data_not_found());
+
+    let archive = save_data
+        .open_or_create(&SaveDataConfig::default())
+        .unwrap();
+    archive
+        .create_directory(&FsPath::try_from("/1").unwrap(), 0)
+        .unwrap();
+    archive.commit().unwrap();
+
+    let root = FsPath::new_empty_path();
+    let entry = archive.open_directory(&root).unwrap().read_next().unwrap();
+    assert_eq!(entry.unwrap().name(), "1");
+
+    save_data.format(&SaveDataConfig::default()).unwrap();
+    let entry = archive.open_directory(&root).unwrap().read_next().unwrap();
+    assert_eq!(entry, None);
+}
+
+#[test]
+fn should_set_secure_values() {
+    assert_eq!(fs::get_this_save_data_secure_value(1).unwrap(), None);
+
+    fs::set_this_save_data_secure_value(1, 0x1234).unwrap();
+    assert_eq!(
+        fs::get_this_save_data_secure_value(1).unwrap(),
+        Some(0x1234)
+    );
+}
+
+#[test]
+fn should_write_files_atomically() {
+    let test_dir = setup("atomic");
+    host_fs::write(test_dir.join("config.bin"), b"old contents").unwrap();
+
+    fs::write_atomic("sd:/atomic/config.bin", "new").unwrap();
+
+    let result = host_fs::read(test_dir.join("config.bin")).unwrap();
+    assert_eq!(result, b"new");
+    assert!(!test_dir.join("config.bin.tmp").exists());
+    assert!(!test_dir.join("config.bin.new").exists());
+}
+
+#[test]
+fn should_recover_interrupted_atomic_writes_when_reading() {
+    let test_dir = setup("recover");
+    host_fs::write(test_dir.join("config.bin.new"), b"new").unwrap();
+
+    let result = fs::read("sd:/recover/config.bin").unwrap();
+    assert_eq!(result, b"new");
+    assert!(!test_dir.join("config.bin.new").exists());
+}
+
+#[test]
+fn should_copy_files_in_chunks() {
+    let test_dir = setup("copy");
+    let contents = (0..100u8).collect::<Vec<u8>>();
+    host_fs::write(test_dir.join("src.bin"), &contents).unwrap();
+
+    let archive = FsArchive::new(ArchiveId::Sdmc, &FsPath::new_empty_path()).unwrap();
+    let src = archive
+        .open_file(&FsPath::try_from("/copy/src.bin").unwrap(), OpenFlags::Read)
+        .unwrap();
+    let mut dst = archive
+        .open_file(
+            &FsPath::try_from("/copy/dst.bin").unwrap(),
+            OpenFlags::ReadWriteCreate,
+        )
+        .unwrap();
+
+    assert_eq!(fs::copy(&src, &mut dst, 7).unwrap(), 100);
+    assert_eq!(fs::read_to_end(&dst, 9).unwrap(), contents);
+
+    let mut reader = fs::BufReader::with_chunk_size(src, 16);
+    let mut out = [0; 20];
+    assert_eq!(reader.read(&mut out).unwrap(), 16);
+    assert_eq!(out[..16], contents[..16]);
+}
@@ -0,0 +1,209 @@
+// This file runs a NASC login against a local stand-in server over HTTP, and serves as an example.
+
+use ctr::{
+    cfg::{Region, SystemLanguage},
+    fs::{MediaType, ProductInfo, ProgramInfo},
+    nasc::{self, NascHttpContext, NascLocator, NascLoginRequest, NascLoginResponse},
+    res::CtrResult,
+    time::{FormattedTimestamp, SystemTimestamp},
+    utils::base64_encode,
+    TitleId,
+};
+use std::{
+    cell::RefCell,
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    thread,
+};
+
+const FRIENDS_TITLE_ID: TitleId = TitleId::new(0x0004013000003202);
+
+/// Accepts logins from consoles with a known serial number.
+struct StandInNascServer {
+    allowed_serial_number: &'static str,
+}
+
+impl StandInNascServer {
+    fn handle(&self, body: &str) -> String {
+        let response = match NascLoginRequest::parse_form_body(body) {
+            Ok(request) if request.serial_number == self.allowed_serial_number => {
+                NascLoginResponse {
+                    return_code: 1,
+                    retry: false,
+                    locator: Some(NascLocator {
+                        host: String::from("127.0.0.1"),
+                        port: 60000,
+                    }),
+                    token: Some(format!("token-{:08x}", request.game_id)),
+                    date_time: Some(request.device_time),
+                }
+            }
+            _ => NascLoginResponse {
+                return_code: 109,
+                retry: false,
+                locator: None,
+                token: None,
+                date_time: None,
+            },
+        };
+
+        response.to_form_body()
+    }
+
+    /// Answers one HTTP POST, then returns the form body it received.
+    fn serve_one(&self, listener: &TcpListener) -> String {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut content_length = 0;
+
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+        let body = String::from_utf8(body).unwrap();
+
+        let response_body = self.handle(&body);
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response_body.len(),
+            response_body
+        )
+        .unwrap();
+
+        body
+    }
+}
+
+/// Posts forms over a TCP connection, standing in for the http sysmodule.
+struct LoopbackHttpContext {
+    address: SocketAddr,
+    fields: RefCell<Vec<String>>,
+}
+
+impl LoopbackHttpContext {
+    fn new(address: SocketAddr) -> Self {
+        Self {
+            address,
+            fields: RefCell::new(vec![]),
+        }
+    }
+}
+
+impl NascHttpContext for LoopbackHttpContext {
+    fn add_post_base64_field(&self, name: &str, value: &[u8]) -> CtrResult {
+        self.fields
+            .borrow_mut()
+            .push(format!("{}={}", name, base64_encode(value)));
+        Ok(())
+    }
+
+    fn download_data_into_buffer(&self, out_buffer: &mut [u8]) -> CtrResult {
+        let body = self.fields.borrow().join("&");
+        let mut stream = TcpStream::connect(self.address).unwrap();
+        write!(
+            stream,
+            "POST /ac HTTP/1.1\r\nHost: {}\r\nContent-Type: application/x-www-form-urlencoded\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.address,
+            body.len(),
+            body
+        )
+        .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (_, response_body) = response.split_once("\r\n\r\n").unwrap();
+        out_buffer[..response_body.len()].copy_from_slice(response_body.as_bytes());
+
+        Ok(())
+    }
+}
+
+fn create_request(serial_number: &str) -> NascLoginRequest {
+    let program_info = ProgramInfo {
+        program_id: FRIENDS_TITLE_ID,
+        media_type: MediaType::Nand,
+        padding: [0; 7],
+    };
+    let product_info = ProductInfo {
+        product_code: *b"CTR-N-FRDE\0\0\0\0\0\0",
+        company_code: *b"00",
+        remaster_version: 0x10,
+    };
+    let device_time = SystemTimestamp::from(FormattedTimestamp::new(2023, 12, 24, 18, 30, 5));
+
+    let mut request = NascLoginRequest::with_device(
+        &program_info,
+        &product_info,
+        [0x00, 0x1f, 0x32, 0xaa, 0xbb, 0xcc],
+        vec![0x5a; 0x180],
+        device_time,
+    );
+    request.language = SystemLanguage::French;
+    request.region = Region::Europe;
+    request.serial_number = String::from(serial_number);
+    request.local_friend_code_seed = [0x78, 0x56, 0x34, 0x12, 0x02, 0, 0, 0];
+    request.user_id = Some(1234);
+    request
+}
+
+/// Logs in with a request, returning what the server received and what the client parsed.
+fn log_in(request: &NascLoginRequest) -> (NascLoginRequest, NascLoginResponse) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let context = LoopbackHttpContext::new(listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let server = StandInNascServer {
+            allowed_serial_number: "CW123456789",
+        };
+        server.serve_one(&listener)
+    });
+
+    let response = nasc::login(&context, request).unwrap();
+    let received = NascLoginRequest::parse_form_body(&server.join().unwrap()).unwrap();
+
+    (received, response)
+}
+
+#[test]
+fn should_log_in_to_the_stand_in_server() {
+    let request = create_request("CW123456789");
+
+    let (received, response) = log_in(&request);
+
+    assert_eq!(received, request);
+    assert_eq!(received.game_id, 0x32);
+    assert_eq!(&received.game_code, b"FRDE");
+    assert_eq!(received.mac_address, [0x00, 0x1f, 0x32, 0xaa, 0xbb, 0xcc]);
+    assert_eq!(received.device_cert, vec![0x5a; 0x180]);
+    assert_eq!(received.device_time.to_device_time(), "231224183005");
+    assert!(response.is_success());
+    assert_eq!(response.locator.unwrap().port, 60000);
+    assert_eq!(response.token.as_deref(), Some("token-00000032"));
+    assert_eq!(response.date_time.unwrap().year, 2023);
+}
+
+#[test]
+fn should_handle_rejected_logins() {
+    let request = create_request("CW000000000");
+
+    let (_, response) = log_in(&request);
+
+    assert!(!response.is_success());
+    assert_eq!(response.return_code, 109);
+    assert_eq!(response.locator, None);
+}
@@ -0,0 +1,218 @@
+// This file runs a PRUDP connection against a local stand-in server over UDP, and serves as an example.
+
+use ctr::{
+    crypto::Rc4,
+    prudp::{
+        calculate_data_signature, ConnectionState, PacketFlag, PacketType, PrudpConnection,
+        PrudpPacket, PrudpSettings, StreamType, VirtualPort, DEFAULT_PAYLOAD_KEY,
+        FRIENDS_ACCESS_KEY,
+    },
+};
+use std::{
+    net::{SocketAddr, UdpSocket},
+    thread,
+    time::Duration,
+};
+
+const SERVER_SIGNATURE: u32 = 0x5ca1ab1e;
+const SERVER_SESSION_ID: u8 = 0x7e;
+const FRAGMENT_SIZE: usize = 16;
+
+fn client_port() -> VirtualPort {
+    VirtualPort::new(StreamType::RvSecure, 0xf)
+}
+
+fn server_port() -> VirtualPort {
+    VirtualPort::new(StreamType::RvSecure, 1)
+}
+
+/// Echoes every message back in reverse until the client disconnects.
+struct StandInPrudpServer {
+    socket: UdpSocket,
+    client_signature: u32,
+    sequence_id: u16,
+    encryption: Rc4,
+    decryption: Rc4,
+    message: Vec<u8>,
+}
+
+impl StandInPrudpServer {
+    fn new(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            client_signature: 0,
+            sequence_id: 1,
+            encryption: Rc4::new(DEFAULT_PAYLOAD_KEY),
+            decryption: Rc4::new(DEFAULT_PAYLOAD_KEY),
+            message: vec![],
+        }
+    }
+
+    fn create_packet(&self, packet_type: PacketType, flags: u16) -> PrudpPacket {
+        let mut packet = PrudpPacket::new(server_port(), client_port(), packet_type, flags);
+        packet.session_id = SERVER_SESSION_ID;
+        packet.signature = self.client_signature;
+        packet
+    }
+
+    fn send(&self, packet: &PrudpPacket, address: SocketAddr) {
+        let datagram = packet.encode(FRIENDS_ACCESS_KEY).unwrap();
+        self.socket.send_to(&datagram, address).unwrap();
+    }
+
+    fn acknowledge(&self, packet: &PrudpPacket, address: SocketAddr) {
+        let mut ack = self.create_packet(packet.packet_type, PacketFlag::Ack as u16);
+        ack.sequence_id = packet.sequence_id;
+        ack.fragment_id = packet.fragment_id;
+
+        match packet.packet_type {
+            PacketType::Syn => {
+                ack.signature = 0;
+                ack.connection_signature = SERVER_SIGNATURE;
+            }
+            PacketType::Data => ack.signature = calculate_data_signature(FRIENDS_ACCESS_KEY, &[]),
+            _ => {}
+        }
+
+        self.send(&ack, address);
+    }
+
+    fn reply(&mut self, message: &[u8], address: SocketAddr) {
+        let chunks = message.chunks(FRAGMENT_SIZE).collect::<Vec<&[u8]>>();
+
+        for (index, chunk) in chunks.iter().enumerate() {
+            let mut packet = self.create_packet(
+                PacketType::Data,
+                PacketFlag::Reliable | PacketFlag::NeedAck | PacketFlag::HasSize,
+            );
+            packet.sequence_id = self.sequence_id;
+            packet.fragment_id = if index == chunks.len() - 1 {
+                0
+            } else {
+                (index + 1) as u8
+            };
+            packet.payload = chunk.to_vec();
+            self.encryption.apply(&mut packet.payload);
+            packet.signature = calculate_data_signature(FRIENDS_ACCESS_KEY, &packet.payload);
+            self.sequence_id += 1;
+
+            self.send(&packet, address);
+        }
+    }
+
+    fn run(mut self) {
+        let mut buffer = [0u8; 0x800];
+
+        loop {
+            let (size, address) = self.socket.recv_from(&mut buffer).unwrap();
+            let packet = PrudpPacket::decode(&buffer[..size], FRIENDS_ACCESS_KEY).unwrap();
+
+            if packet.is_ack() {
+                continue;
+            }
+
+            if packet.packet_type == PacketType::Connect {
+                self.client_signature = packet.connection_signature;
+            }
+
+            self.acknowledge(&packet, address);
+
+            match packet.packet_type {
+                PacketType::Data => {
+                    let mut payload = packet.payload;
+                    self.decryption.apply(&mut payload);
+                    self.message.extend_from_slice(&payload);
+
+                    if packet.fragment_id == 0 {
+                        let mut reply = std::mem::take(&mut self.message);
+                        reply.reverse();
+                        self.reply(&reply, address);
+                    }
+                }
+                PacketType::Disconnect => return,
+                _ => {}
+            }
+        }
+    }
+}
+
+struct Client {
+    socket: UdpSocket,
+    connection: PrudpConnection,
+}
+
+impl Client {
+    fn send_all(&self, datagrams: &[Vec<u8>]) {
+        for datagram in datagrams {
+            self.socket.send(datagram).unwrap();
+        }
+    }
+
+    fn handle_next_datagram(&mut self) {
+        let mut buffer = [0u8; 0x800];
+        let size = self.socket.recv(&mut buffer).unwrap();
+        let responses = self.connection.handle_datagram(&buffer[..size]).unwrap();
+        self.send_all(&responses);
+    }
+
+    /// Handles datagrams until the condition is met.
+    fn poll_until(&mut self, condition: impl Fn(&PrudpConnection) -> bool) {
+        while !condition(&self.connection) {
+            self.handle_next_datagram();
+        }
+    }
+
+    fn receive_message(&mut self) -> Vec<u8> {
+        loop {
+            if let Some(message) = self.connection.receive() {
+                return message;
+            }
+
+            self.handle_next_datagram();
+        }
+    }
+}
+
+#[test]
+fn should_exchange_messages_over_a_loopback_connection() {
+    let server_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let server_address = server_socket.local_addr().unwrap();
+    let server = thread::spawn(move || StandInPrudpServer::new(server_socket).run());
+
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket.connect(server_address).unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    let mut settings = PrudpSettings::new(FRIENDS_ACCESS_KEY);
+    settings.fragment_size = FRAGMENT_SIZE;
+    let mut client = Client {
+        socket,
+        connection: PrudpConnection::new(settings, client_port(), server_port(), 0x21, 0xc0ffee),
+    };
+
+    let syn = client.connection.connect().unwrap();
+    client.send_all(&[syn]);
+    client.poll_until(|connection| connection.get_state() == ConnectionState::Connected);
+    assert_eq!(
+        client.connection.get_remote_session_id(),
+        Some(SERVER_SESSION_ID)
+    );
+
+    let message = (0..100).collect::<Vec<u8>>();
+    let datagrams = client.connection.send(&message).unwrap();
+    assert_eq!(datagrams.len(), 7);
+    client.send_all(&datagrams);
+    client.poll_until(|connection| connection.unacknowledged_datagrams().count() == 0);
+
+    let mut expected = message;
+    expected.reverse();
+    assert_eq!(client.receive_message(), expected);
+
+    let disconnect = client.connection.disconnect().unwrap();
+    client.send_all(&[disconnect]);
+    client.poll_until(|connection| connection.get_state() == ConnectionState::Disconnected);
+
+    server.join().unwrap();
+}
@@ -0,0 +1,98 @@
+// This file makes sure the frd server definitions can be routed with the macros,
+// and serves as an example.
+
+use ctr::{
+    frd::{
+        server::{
+            FrdACommand, FrdAServer, FrdNCommand, FrdNServer, FrdUCommand, FrdUServer,
+            GetFriendKeyListIn,
+        },
+        FriendKey,
+    },
+    ipc::WrittenCommand,
+    result::CtrResult,
+    sysmodule::server::{Service, ServiceRouter},
+};
+use ctr_macros::match_ctr_route;
+
+struct FriendSysmodule {
+    my_friend_key: FriendKey,
+}
+
+// Unimplemented commands use the default methods, which return not_implemented
+impl FrdUServer for FriendSysmodule {
+    fn get_my_friend_key(&mut self, _session_index: usize) -> CtrResult<FriendKey> {
+        Ok(self.my_friend_key)
+    }
+
+    fn principal_id_to_friend_code(
+        &mut self,
+        _session_index: usize,
+        principal_id: u32,
+    ) -> CtrResult<u64> {
+        Ok(principal_id.into())
+    }
+}
+
+impl FrdAServer for FriendSysmodule {
+    fn set_my_nc_principal_id(&mut self, _session_index: usize, principal_id: u32) -> CtrResult {
+        self.my_friend_key.principal_id = principal_id;
+        Ok(())
+    }
+}
+
+impl FrdNServer for FriendSysmodule {}
+
+ctr::impl_frd_u_routes!(FriendSysmodule);
+ctr::impl_frd_a_routes!(FriendSysmodule);
+ctr::impl_frd_n_routes!(FriendSysmodule);
+
+impl ServiceRouter for FriendSysmodule {
+    fn handle_request(
+        &mut self,
+        service_id: usize,
+        session_index: usize,
+    ) -> CtrResult<WrittenCommand> {
+        match_ctr_route!(
+            FriendSysmodule,
+            service_id,
+            session_index,
+            FrdUCommand::GetMyFriendKey,
+            FrdUCommand::GetFriendKeyList,
+            FrdUCommand::PrincipalIdToFriendCode,
+            FrdACommand::GetMyFriendKey,
+            FrdACommand::SetMyNcPrincipalId,
+            FrdNCommand::Unknown1,
+        )
+    }
+
+    fn accept_session(&mut self, _session_index: usize) {}
+
+    fn close_session(&mut self, _session_index: usize) {}
+}
+
+#[test]
+fn should_use_handlers_directly() {
+    let mut sysmodule = FriendSysmodule {
+        my_friend_key: FriendKey::default(),
+    };
+
+    FrdAServer::set_my_nc_principal_id(&mut sysmodule, 0, 5).unwrap();
+
+    assert_eq!(
+        FrdUServer::get_my_friend_key(&mut sysmodule, 0)
+            .unwrap()
+            .principal_id,
+        5
+    );
+    assert!(FrdUServer::get_friend_key_list(
+        &mut sysmodule,
+        0,
+        GetFriendKeyListIn {
+            offset: 0,
+            max_count: 1
+        }
+    )
+    .is_err());
+    assert_eq!(FrdUCommand::NAME, "frd:u");
+}